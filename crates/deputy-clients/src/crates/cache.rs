@@ -1,6 +1,11 @@
+use std::{sync::Arc, time::Duration};
+
 use crate::shared::{RequestCacheMap, RequestResult};
 
-use super::models::{CrateDataMulti, CrateDataSingle, IndexMetadata, LocalMetadata};
+use super::config::CargoConfig;
+use super::models::{
+    CargoWorkspace, CrateDataMulti, CrateDataSingle, IndexMetadata, LocalMetadata,
+};
 
 #[derive(Debug, Clone)]
 pub(super) struct CratesCache {
@@ -8,6 +13,8 @@ pub(super) struct CratesCache {
     pub crate_datas: RequestCacheMap<RequestResult<CrateDataSingle>>,
     pub crate_search: RequestCacheMap<RequestResult<CrateDataMulti>>,
     pub local_metadatas: RequestCacheMap<Option<LocalMetadata>>,
+    pub configs: RequestCacheMap<CargoConfig>,
+    pub workspaces: RequestCacheMap<Option<Arc<CargoWorkspace>>>,
 }
 
 impl CratesCache {
@@ -17,6 +24,22 @@ impl CratesCache {
             crate_datas: RequestCacheMap::new_mins(240, 120),
             crate_search: RequestCacheMap::new_mins(480, 240),
             local_metadatas: RequestCacheMap::new_secs(5, 5),
+            configs: RequestCacheMap::new_secs(30, 30),
+            workspaces: RequestCacheMap::new_secs(5, 5),
         }
     }
+
+    /**
+        Invalidates all data fetched from the crates.io API, but not from the index.
+    */
+    pub fn invalidate_api(&self) {
+        self.crate_datas.invalidate();
+        self.crate_search.invalidate();
+    }
+
+    pub fn set_time_to_live(&self, time_to_live: Option<Duration>) {
+        self.index_metadatas.set_time_to_live(time_to_live);
+        self.crate_datas.set_time_to_live(time_to_live);
+        self.crate_search.set_time_to_live(time_to_live);
+    }
 }
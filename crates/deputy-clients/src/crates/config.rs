@@ -0,0 +1,321 @@
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use tracing::debug;
+
+use super::CratesClient;
+use super::consts::{CRATES_IO_REGISTRY, MAX_SOURCE_REPLACEMENTS, SPARSE_INDEX_PREFIX};
+use super::models::CrateRegistry;
+
+impl CratesClient {
+    /**
+        Resolves the registry that crates should be fetched from, for a
+        manifest in the given directory, and an optional registry name.
+
+        Reads the same `.cargo/config.toml` files as Cargo does - starting
+        in the manifest directory and going up through all its ancestors,
+        finally reading the config in `$CARGO_HOME`. Tokens are read from
+        `$CARGO_HOME/credentials.toml`, or `CARGO_REGISTRIES_<NAME>_TOKEN`.
+
+        Returns `None` if the registry is unknown, or if it
+        does not use a sparse index, which is not supported.
+    */
+    pub async fn get_registry(
+        &self,
+        manifest_dir: &Path,
+        registry: Option<&str>,
+    ) -> Option<CrateRegistry> {
+        let cache_key = manifest_dir.to_string_lossy().to_string();
+
+        let manifest_dir = manifest_dir.to_path_buf();
+        let fut = async {
+            debug!("Reading cargo config for '{}'", manifest_dir.display());
+            CargoConfig::read(&manifest_dir).await
+        };
+
+        let config = self.cache.configs.with_caching(cache_key, fut).await;
+        let resolved = config.resolve(registry.unwrap_or(CRATES_IO_REGISTRY))?;
+
+        // Any override only replaces crates.io itself, not a configured mirror of it
+        match self.registry_override() {
+            Some(url) if resolved == CrateRegistry::crates_io() => Some(CrateRegistry {
+                index_url: url
+                    .trim_start_matches(SPARSE_INDEX_PREFIX)
+                    .trim_end_matches('/')
+                    .to_string(),
+                ..resolved
+            }),
+            _ => Some(resolved),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct RegistryEntry {
+    index: Option<String>,
+    token: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SourceEntry {
+    replace_with: Option<String>,
+    registry: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    registries: HashMap<String, RegistryEntry>,
+    #[serde(default)]
+    source: HashMap<String, SourceEntry>,
+}
+
+/**
+    Merged registry & source configuration from all `.cargo/config.toml`
+    files that apply to a manifest, as well as any registry credentials.
+*/
+#[derive(Debug, Clone, Default)]
+pub(super) struct CargoConfig {
+    registries: HashMap<String, RegistryEntry>,
+    sources: HashMap<String, SourceEntry>,
+}
+
+impl CargoConfig {
+    async fn read(manifest_dir: &Path) -> Self {
+        let cargo_home = cargo_home();
+
+        // Config files closer to the manifest take precedence, so we read
+        // those first and only fill in values that have not yet been set
+        let mut dirs = manifest_dir
+            .ancestors()
+            .map(|dir| dir.join(".cargo"))
+            .collect::<Vec<_>>();
+        if let Some(home) = &cargo_home
+            && !dirs.contains(home)
+        {
+            dirs.push(home.clone());
+        }
+
+        let mut config = Self::default();
+        for dir in dirs {
+            for file_name in ["config.toml", "config"] {
+                if let Some(file) = read_config_file(&dir.join(file_name)).await {
+                    config.merge(file);
+                    break;
+                }
+            }
+        }
+
+        // Credentials override any tokens set in config files
+        if let Some(home) = &cargo_home {
+            for file_name in ["credentials.toml", "credentials"] {
+                if let Some(file) = read_config_file(&home.join(file_name)).await {
+                    for (name, entry) in file.registries {
+                        if entry.token.is_some() {
+                            config.registries.entry(name).or_default().token = entry.token;
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+
+        config
+    }
+
+    fn merge(&mut self, file: ConfigFile) {
+        for (name, entry) in file.registries {
+            let existing = self.registries.entry(name).or_default();
+            existing.index = existing.index.take().or(entry.index);
+            existing.token = existing.token.take().or(entry.token);
+        }
+        for (name, entry) in file.source {
+            let existing = self.sources.entry(name).or_default();
+            existing.replace_with = existing.replace_with.take().or(entry.replace_with);
+            existing.registry = existing.registry.take().or(entry.registry);
+        }
+    }
+
+    fn resolve(&self, registry: &str) -> Option<CrateRegistry> {
+        // Follow any source replacements, such as crates.io being replaced with a mirror
+        let mut name = registry.to_string();
+        for _ in 0..MAX_SOURCE_REPLACEMENTS {
+            match self
+                .sources
+                .get(&name)
+                .and_then(|s| s.replace_with.as_ref())
+            {
+                Some(replacement) => name.clone_from(replacement),
+                None => break,
+            }
+        }
+
+        let entry = self.registries.get(&name);
+        let index = env_var_for_registry(&name, "INDEX")
+            .or_else(|| entry.and_then(|e| e.index.clone()))
+            .or_else(|| self.sources.get(&name).and_then(|s| s.registry.clone()));
+        let token =
+            env_var_for_registry(&name, "TOKEN").or_else(|| entry.and_then(|e| e.token.clone()));
+
+        // Replacing crates.io with a mirror or vendored sources still means that
+        // crates are the same as on crates.io, so we can always fall back to it
+        let is_crates_io = registry == CRATES_IO_REGISTRY;
+        let Some(index_url) = index
+            .as_deref()
+            .and_then(|i| i.strip_prefix(SPARSE_INDEX_PREFIX))
+        else {
+            if is_crates_io {
+                return Some(CrateRegistry::crates_io());
+            }
+            debug!("No sparse index was found for cargo registry '{name}'");
+            return None;
+        };
+
+        Some(CrateRegistry {
+            name: if is_crates_io {
+                registry.to_string()
+            } else {
+                name
+            },
+            index_url: index_url.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+}
+
+async fn read_config_file(path: &Path) -> Option<ConfigFile> {
+    let contents = tokio::fs::read_to_string(path).await.ok()?;
+    match toml::from_str(&contents) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            debug!("Failed to parse cargo config at '{}' - {e}", path.display());
+            None
+        }
+    }
+}
+
+pub(super) fn cargo_home() -> Option<PathBuf> {
+    env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")))
+}
+
+fn env_var_for_registry(name: &str, key: &str) -> Option<String> {
+    let name = name.to_ascii_uppercase().replace('-', "_");
+    env::var(format!("CARGO_REGISTRIES_{name}_{key}")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(files: &[&str]) -> CargoConfig {
+        let mut config = CargoConfig::default();
+        for file in files {
+            config.merge(toml::from_str(file).unwrap());
+        }
+        config
+    }
+
+    // resolve
+
+    #[test]
+    fn resolves_crates_io_by_default() {
+        let config = config(&[]);
+        assert_eq!(
+            config.resolve(CRATES_IO_REGISTRY),
+            Some(CrateRegistry::crates_io())
+        );
+        assert_eq!(config.resolve("deputy-test-unknown"), None);
+    }
+
+    #[test]
+    fn resolves_alternate_registries() {
+        let config = config(&[r#"
+            [registries.deputy-test-sparse]
+            index = "sparse+https://cargo.example.com/index/"
+            token = "Bearer secret"
+
+            [registries.deputy-test-git]
+            index = "https://git.example.com/index.git"
+        "#]);
+        assert_eq!(
+            config.resolve("deputy-test-sparse"),
+            Some(CrateRegistry {
+                name: String::from("deputy-test-sparse"),
+                index_url: String::from("https://cargo.example.com/index"),
+                token: Some(String::from("Bearer secret")),
+            })
+        );
+        assert_eq!(config.resolve("deputy-test-git"), None);
+    }
+
+    #[test]
+    fn resolves_source_replacements() {
+        let config = config(&[r#"
+            [source.crates-io]
+            replace-with = "deputy-test-mirror"
+
+            [source.deputy-test-mirror]
+            registry = "sparse+https://mirror.example.com/index/"
+        "#]);
+        let resolved = config.resolve(CRATES_IO_REGISTRY).unwrap();
+        assert!(resolved.is_crates_io());
+        assert_eq!(resolved.index_url, "https://mirror.example.com/index");
+    }
+
+    #[test]
+    fn resolves_unsupported_crates_io_replacements_to_crates_io() {
+        let config = config(&[r#"
+            [source.crates-io]
+            replace-with = "deputy-test-vendored"
+
+            [source.deputy-test-vendored]
+            directory = "vendor"
+        "#]);
+        assert_eq!(
+            config.resolve(CRATES_IO_REGISTRY),
+            Some(CrateRegistry::crates_io())
+        );
+    }
+
+    #[test]
+    fn resolves_cyclic_source_replacements() {
+        let config = config(&[r#"
+            [source.crates-io]
+            replace-with = "deputy-test-cycle"
+
+            [source.deputy-test-cycle]
+            replace-with = "crates-io"
+        "#]);
+        assert_eq!(
+            config.resolve(CRATES_IO_REGISTRY),
+            Some(CrateRegistry::crates_io())
+        );
+    }
+
+    // merge
+
+    #[test]
+    fn closer_config_files_take_precedence() {
+        let config = config(&[
+            r#"
+            [registries.deputy-test-merged]
+            index = "sparse+https://near.example.com/index/"
+            "#,
+            r#"
+            [registries.deputy-test-merged]
+            index = "sparse+https://far.example.com/index/"
+            token = "far-token"
+            "#,
+        ]);
+        let resolved = config.resolve("deputy-test-merged").unwrap();
+        assert_eq!(resolved.index_url, "https://near.example.com/index");
+        assert_eq!(resolved.token.as_deref(), Some("far-token"));
+    }
+}
@@ -1,6 +1,10 @@
 pub const BASE_URL_INDEX: &str = "https://index.crates.io";
 pub const BASE_URL_CRATES: &str = "https://crates.io/api/v1/crates";
 
+pub const CRATES_IO_REGISTRY: &str = "crates-io";
+pub const SPARSE_INDEX_PREFIX: &str = "sparse+";
+pub const MAX_SOURCE_REPLACEMENTS: usize = 8; // Guards against cyclic source replacements
+
 pub const QUERY_STRING_CRATE_SINGLE: &str = "?include=downloads,versions"; // Fetch only what we need
 pub const QUERY_STRING_CRATE_MULTI: &str = "?page=1&per_page=32"; // First page only, and a reasonable amount
 
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, path::Path, process::Stdio};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
 
 use serde::Deserialize;
 use tokio::{
@@ -11,6 +15,7 @@ use tracing::debug;
 use deputy_versioning::Version;
 
 use super::CratesClient;
+use super::config::cargo_home;
 use super::models::LocalMetadata;
 
 impl CratesClient {
@@ -36,6 +41,50 @@ impl CratesClient {
             .with_caching(cache_key, fut)
             .await
     }
+
+    /**
+        Finds all versions of a crate that have been downloaded and unpacked
+        by Cargo, in `$CARGO_HOME/registry/src`, along with their manifest paths.
+
+        Sources from all registries are included, and the same version may be
+        returned more than once if it was downloaded from several registries.
+    */
+    pub async fn get_unpacked_crates(&self, name: &str) -> Vec<(Version, PathBuf)> {
+        let Some(src_dir) = cargo_home().map(|home| home.join("registry").join("src")) else {
+            return Vec::new();
+        };
+
+        let prefix = format!("{name}-");
+        let mut crates = Vec::new();
+
+        let Ok(mut registries) = tokio::fs::read_dir(&src_dir).await else {
+            return Vec::new();
+        };
+        while let Ok(Some(registry)) = registries.next_entry().await {
+            let Ok(mut entries) = tokio::fs::read_dir(registry.path()).await else {
+                continue;
+            };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let file_name = entry.file_name();
+                let Some(version) = file_name
+                    .to_str()
+                    .and_then(|s| s.strip_prefix(&prefix))
+                    .and_then(|s| s.parse::<Version>().ok())
+                else {
+                    continue;
+                };
+                let manifest_path = entry.path().join("Cargo.toml");
+                if tokio::fs::try_exists(&manifest_path)
+                    .await
+                    .unwrap_or_default()
+                {
+                    crates.push((version, manifest_path));
+                }
+            }
+        }
+
+        crates
+    }
 }
 
 // The full and proper `cargo metadata` output is our primary source...
@@ -1,6 +1,6 @@
 use std::{
     sync::{
-        Arc,
+        Arc, Mutex, PoisonError,
         atomic::{AtomicBool, Ordering},
     },
     time::Duration,
@@ -12,12 +12,14 @@ use tokio::{
 };
 use tracing::error;
 
-use crate::shared::{Request, RequestError, RequestResult};
+use crate::shared::{Request, RequestContext, RequestError, RequestResult};
 
 mod cache;
+mod config;
 mod consts;
 mod local;
 mod requests;
+mod workspace;
 
 pub mod models;
 
@@ -28,21 +30,86 @@ pub struct CratesClient {
     cache: CratesCache,
     crawl_channel: Sender<()>,
     crawl_limited: Arc<AtomicBool>,
+    registry_override: Arc<Mutex<Option<String>>>,
+    api_url_override: Arc<Mutex<Option<String>>>,
+    context: RequestContext,
 }
 
 impl CratesClient {
     #[must_use]
-    pub fn new() -> Self {
+    pub(crate) fn new(context: RequestContext) -> Self {
         let crawl_channel = channel(1).0;
         Self {
             cache: CratesCache::new(),
             crawl_channel,
             crawl_limited: Arc::new(AtomicBool::new(false)),
+            registry_override: Arc::new(Mutex::new(None)),
+            api_url_override: Arc::new(Mutex::new(None)),
+            context,
         }
     }
 
     async fn request_get(&self, url: impl Into<String>) -> RequestResult<Vec<u8>> {
-        Request::get(url).send().await
+        Request::get(url).send(&self.context).await
+    }
+
+    async fn request_get_with_token(
+        &self,
+        url: impl Into<String>,
+        token: Option<&str>,
+    ) -> RequestResult<Vec<u8>> {
+        // Cargo sends registry tokens as-is, without any "Bearer" prefix
+        Request::get(url)
+            .with_header_opt("Authorization", token)
+            .send(&self.context)
+            .await
+    }
+
+    /**
+        Overrides how long registry data is cached for,
+        or resets it to the default if `None` is given.
+    */
+    pub fn set_cache_ttl(&self, ttl: Option<Duration>) {
+        self.cache.set_time_to_live(ttl);
+    }
+
+    /**
+        Overrides the sparse index url that is used instead of crates.io,
+        or resets it if `None` is given. Registries and source replacements
+        configured in `.cargo/config.toml` still take precedence.
+    */
+    pub fn set_registry_override(&self, url: Option<String>) {
+        *self
+            .registry_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = url;
+    }
+
+    fn registry_override(&self) -> Option<String> {
+        self.registry_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /**
+        Overrides the url of the crates.io API, which is used for crate
+        metadata and searching, or resets it if `None` is given.
+    */
+    pub fn set_api_url_override(&self, url: Option<String>) {
+        *self
+            .api_url_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = url;
+        self.cache.invalidate_api();
+    }
+
+    fn api_url(&self) -> String {
+        self.api_url_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .unwrap_or_else(|| consts::BASE_URL_CRATES.to_string())
     }
 
     fn emit_result<T>(result: &RequestResult<T>) {
@@ -78,6 +145,33 @@ impl CratesClient {
 
 impl Default for CratesClient {
     fn default() -> Self {
-        Self::new()
+        Self::new(RequestContext::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU32;
+
+    use super::*;
+
+    // set_api_url_override
+
+    #[tokio::test]
+    async fn api_url_override_invalidates_cache() {
+        let client = CratesClient::default();
+        let runs = AtomicU32::new(0);
+        let fetch = || async {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Err(RequestError::Unknown)
+        };
+
+        let _ = client.cache.crate_datas.with_caching("key", fetch()).await;
+        let _ = client.cache.crate_datas.with_caching("key", fetch()).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        client.set_api_url_override(Some(String::from("https://mirror.example.com/api")));
+        let _ = client.cache.crate_datas.with_caching("key", fetch()).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
     }
 }
@@ -1,7 +1,11 @@
 mod crates_io;
 mod index;
 mod local;
+mod registry;
+mod workspace;
 
 pub use self::crates_io::*;
 pub use self::index::*;
 pub use self::local::*;
+pub use self::registry::*;
+pub use self::workspace::*;
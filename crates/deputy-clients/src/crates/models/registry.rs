@@ -0,0 +1,40 @@
+use super::super::consts::{BASE_URL_INDEX, CRATES_IO_REGISTRY};
+
+/**
+    A registry with a sparse index that crates can be fetched from.
+
+    Defaults to the crates.io registry, but may also be an
+    alternate registry configured in `.cargo/config.toml`.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateRegistry {
+    pub name: String,
+    pub index_url: String,
+    pub token: Option<String>,
+}
+
+impl CrateRegistry {
+    #[must_use]
+    pub fn crates_io() -> Self {
+        Self {
+            name: CRATES_IO_REGISTRY.to_string(),
+            index_url: BASE_URL_INDEX.to_string(),
+            token: None,
+        }
+    }
+
+    /**
+        Returns `true` if this is the crates.io registry, or a
+        source replacement for it, such as a mirror of crates.io.
+    */
+    #[must_use]
+    pub fn is_crates_io(&self) -> bool {
+        self.name == CRATES_IO_REGISTRY
+    }
+}
+
+impl Default for CrateRegistry {
+    fn default() -> Self {
+        Self::crates_io()
+    }
+}
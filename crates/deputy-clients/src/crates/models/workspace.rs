@@ -0,0 +1,104 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+/**
+    A Cargo workspace, read from the `Cargo.toml` at the workspace root.
+*/
+#[derive(Debug, Clone)]
+pub struct CargoWorkspace {
+    pub manifest_path: PathBuf,
+    pub dependencies: HashMap<String, WorkspaceDependency>,
+    /// The contents of the workspace root manifest, when it was read
+    pub manifest_text: String,
+}
+
+impl CargoWorkspace {
+    /**
+        The directory containing the workspace root manifest,
+        which any dependency paths are relative to.
+    */
+    #[must_use]
+    pub fn root_dir(&self) -> &Path {
+        self.manifest_path.parent().unwrap_or(Path::new(""))
+    }
+
+    /**
+        Gets the entry in `[workspace.dependencies]` with the given name.
+    */
+    #[must_use]
+    pub fn dependency(&self, name: &str) -> Option<&WorkspaceDependency> {
+        self.dependencies.get(name)
+    }
+}
+
+/**
+    A dependency in `[workspace.dependencies]`, which
+    members may inherit using `workspace = true`.
+*/
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(from = "WorkspaceDependencySpec")]
+pub struct WorkspaceDependency {
+    pub version: Option<String>,
+    pub package: Option<String>,
+    pub path: Option<String>,
+    pub git: Option<String>,
+    pub branch: Option<String>,
+    pub tag: Option<String>,
+    pub rev: Option<String>,
+    pub registry: Option<String>,
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum WorkspaceDependencySpec {
+    Version(String),
+    Table {
+        version: Option<String>,
+        package: Option<String>,
+        path: Option<String>,
+        git: Option<String>,
+        branch: Option<String>,
+        tag: Option<String>,
+        rev: Option<String>,
+        registry: Option<String>,
+        #[serde(default)]
+        features: Vec<String>,
+    },
+}
+
+impl From<WorkspaceDependencySpec> for WorkspaceDependency {
+    fn from(spec: WorkspaceDependencySpec) -> Self {
+        match spec {
+            WorkspaceDependencySpec::Version(version) => Self {
+                version: Some(version),
+                ..Default::default()
+            },
+            WorkspaceDependencySpec::Table {
+                version,
+                package,
+                path,
+                git,
+                branch,
+                tag,
+                rev,
+                registry,
+                features,
+            } => Self {
+                version,
+                package,
+                path,
+                git,
+                branch,
+                tag,
+                rev,
+                registry,
+                features,
+            },
+        }
+    }
+}
@@ -1,9 +1,7 @@
 use tracing::debug;
 
-use super::consts::{
-    BASE_URL_CRATES, BASE_URL_INDEX, QUERY_STRING_CRATE_MULTI, QUERY_STRING_CRATE_SINGLE,
-};
-use super::models::{CrateDataMulti, CrateDataSingle, IndexMetadata};
+use super::consts::{QUERY_STRING_CRATE_MULTI, QUERY_STRING_CRATE_SINGLE};
+use super::models::{CrateDataMulti, CrateDataSingle, CrateRegistry, IndexMetadata};
 use super::{CratesClient, RequestError, RequestResult};
 
 impl CratesClient {
@@ -12,26 +10,47 @@ impl CratesClient {
         &self,
         name: &str,
     ) -> RequestResult<Vec<IndexMetadata>> {
+        self.get_registry_crate_metadatas(&CrateRegistry::crates_io(), name)
+            .await
+    }
+
+    /**
+        Fetches all versions of a crate from the sparse index of the given registry.
+
+        See [`CratesClient::get_registry`] for resolving alternate registries.
+    */
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn get_registry_crate_metadatas(
+        &self,
+        registry: &CrateRegistry,
+        name: &str,
+    ) -> RequestResult<Vec<IndexMetadata>> {
+        let base_url = &registry.index_url;
         let name_low = name.to_ascii_lowercase();
         let index_url = if name_low.len() <= 2 {
-            format!("{BASE_URL_INDEX}/{}/{name_low}", name_low.len())
+            format!("{base_url}/{}/{name_low}", name_low.len())
         } else if name_low.len() == 3 {
-            format!("{BASE_URL_INDEX}/3/{}/{name_low}", &name_low[..1])
+            format!("{base_url}/3/{}/{name_low}", &name_low[..1])
         } else {
             format!(
-                "{BASE_URL_INDEX}/{}/{}/{name_low}",
+                "{base_url}/{}/{}/{name_low}",
                 &name_low[..2],
                 &name_low[2..4]
             )
         };
 
         let fut = async {
-            debug!("Fetching crates index metadatas for '{name}'");
+            debug!(
+                "Fetching crates index metadatas for '{name}' from '{}'",
+                registry.name
+            );
 
             // NOTE: We make this inner scope so that
             // we can catch and emit all errors at once
             let mut inner = async {
-                let bytes = self.request_get(&index_url).await?;
+                let bytes = self
+                    .request_get_with_token(&index_url, registry.token.as_deref())
+                    .await?;
                 let text = String::from_utf8(bytes.clone())?;
                 Ok(IndexMetadata::try_from_lines(text.lines().collect())?)
             }
@@ -54,7 +73,7 @@ impl CratesClient {
 
         self.cache
             .index_metadatas
-            .with_caching(index_url.clone(), fut)
+            .with_revalidation(index_url.clone(), fut)
             .await
     }
 
@@ -80,7 +99,10 @@ impl CratesClient {
     #[allow(clippy::missing_errors_doc)]
     pub async fn get_crate_data(&self, name: &str) -> RequestResult<CrateDataSingle> {
         let crates_name = name.trim().to_ascii_lowercase();
-        let crates_url = format!("{BASE_URL_CRATES}/{crates_name}{QUERY_STRING_CRATE_SINGLE}");
+        let crates_url = format!(
+            "{}/{crates_name}{QUERY_STRING_CRATE_SINGLE}",
+            self.api_url()
+        );
 
         let fut = async {
             self.wait_for_crawl_limit().await;
@@ -108,7 +130,7 @@ impl CratesClient {
 
         self.cache
             .crate_datas
-            .with_caching(crates_url.clone(), fut)
+            .with_revalidation(crates_url.clone(), fut)
             .await
     }
 
@@ -132,7 +154,10 @@ impl CratesClient {
     #[allow(clippy::missing_errors_doc)]
     pub async fn search_crates(&self, query: &str) -> RequestResult<CrateDataMulti> {
         let crates_query = query.trim().to_ascii_lowercase();
-        let crates_url = format!("{BASE_URL_CRATES}{QUERY_STRING_CRATE_MULTI}&q={crates_query}");
+        let crates_url = format!(
+            "{}{QUERY_STRING_CRATE_MULTI}&q={crates_query}",
+            self.api_url()
+        );
 
         let fut = async {
             self.wait_for_crawl_limit().await;
@@ -155,7 +180,7 @@ impl CratesClient {
 
         self.cache
             .crate_search
-            .with_caching(crates_url.clone(), fut)
+            .with_revalidation(crates_url.clone(), fut)
             .await
     }
 }
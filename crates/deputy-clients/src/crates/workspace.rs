@@ -0,0 +1,156 @@
+use std::{collections::HashMap, fmt::Write, path::Path, sync::Arc, time::UNIX_EPOCH};
+
+use serde::Deserialize;
+use tracing::debug;
+
+use super::CratesClient;
+use super::models::{CargoWorkspace, WorkspaceDependency};
+
+impl CratesClient {
+    /**
+        Finds the workspace that the manifest in the given directory belongs to.
+
+        Uses the `package.workspace` key of the manifest if it is set, otherwise
+        looks for the closest manifest with a `[workspace]` table, starting in
+        the manifest directory itself and going up through all its ancestors.
+
+        Workspaces are cached until any of those manifests are modified on disk.
+    */
+    pub async fn get_workspace(&self, manifest_dir: &Path) -> Option<Arc<CargoWorkspace>> {
+        let cache_key = workspace_cache_key(manifest_dir).await;
+
+        let manifest_dir = manifest_dir.to_path_buf();
+        let fut = async {
+            debug!("Finding cargo workspace for '{}'", manifest_dir.display());
+            find_workspace(&manifest_dir).await.map(Arc::new)
+        };
+
+        self.cache.workspaces.with_caching(cache_key, fut).await
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ManifestFile {
+    package: Option<ManifestPackage>,
+    workspace: Option<ManifestWorkspace>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ManifestPackage {
+    workspace: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ManifestWorkspace {
+    #[serde(default)]
+    dependencies: HashMap<String, WorkspaceDependency>,
+}
+
+/**
+    Creates a cache key from the manifest directory and the modification times of
+    all manifests that the workspace may be read from, so that any changes to them
+    are picked up right away - even before the cached workspace would expire.
+*/
+async fn workspace_cache_key(manifest_dir: &Path) -> String {
+    let mut cache_key = manifest_dir.to_string_lossy().to_string();
+    for dir in manifest_dir.ancestors() {
+        let modified = tokio::fs::metadata(dir.join("Cargo.toml"))
+            .await
+            .and_then(|meta| meta.modified());
+        if let Ok(modified) = modified {
+            let nanos = modified
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or_default();
+            let _ = write!(cache_key, ":{nanos}");
+        }
+    }
+    cache_key
+}
+
+async fn find_workspace(manifest_dir: &Path) -> Option<CargoWorkspace> {
+    let explicit_root = read_manifest_file(&manifest_dir.join("Cargo.toml"))
+        .await
+        .and_then(|file| file.package?.workspace)
+        .map(|root| manifest_dir.join(root));
+
+    let candidates = match explicit_root {
+        Some(root) => vec![root],
+        None => manifest_dir.ancestors().map(Path::to_path_buf).collect(),
+    };
+
+    for dir in candidates {
+        let manifest_path = dir.join("Cargo.toml");
+        let Ok(contents) = tokio::fs::read_to_string(&manifest_path).await else {
+            continue;
+        };
+        if let Some(file) = parse_manifest_file(&manifest_path, &contents)
+            && let Some(workspace) = file.workspace
+        {
+            return Some(CargoWorkspace {
+                manifest_path,
+                dependencies: workspace.dependencies,
+                manifest_text: contents,
+            });
+        }
+    }
+
+    None
+}
+
+async fn read_manifest_file(path: &Path) -> Option<ManifestFile> {
+    let contents = tokio::fs::read_to_string(path).await.ok()?;
+    parse_manifest_file(path, &contents)
+}
+
+fn parse_manifest_file(path: &Path, contents: &str) -> Option<ManifestFile> {
+    match toml::from_str(contents) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            debug!(
+                "Failed to parse cargo manifest at '{}' - {e}",
+                path.display()
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+
+    // workspace_cache_key
+
+    #[tokio::test]
+    async fn cache_key_changes_when_manifests_are_modified() {
+        let dir =
+            std::env::temp_dir().join(format!("deputy-cargo-workspace-{}", std::process::id()));
+        let member = dir.join("crates").join("member");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&member).unwrap();
+        std::fs::write(member.join("Cargo.toml"), "[package]\nname = \"member\"\n").unwrap();
+
+        let before = workspace_cache_key(&member).await;
+        assert_eq!(before, workspace_cache_key(&member).await);
+
+        // Adding a workspace root changes the key
+        let root_manifest = dir.join("Cargo.toml");
+        std::fs::write(&root_manifest, "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+        let added = workspace_cache_key(&member).await;
+        assert_ne!(before, added);
+
+        // ... and so does modifying it
+        std::fs::File::options()
+            .write(true)
+            .open(&root_manifest)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_mins(1))
+            .unwrap();
+        assert_ne!(added, workspace_cache_key(&member).await);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
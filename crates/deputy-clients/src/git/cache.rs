@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use crate::shared::{RequestCacheMap, RequestResult};
+
+use super::models::GitRefs;
+
+#[derive(Debug, Clone)]
+pub(super) struct GitCache {
+    pub refs: RequestCacheMap<RequestResult<GitRefs>>,
+}
+
+impl GitCache {
+    pub fn new() -> Self {
+        Self {
+            refs: RequestCacheMap::new_mins(15, 5),
+        }
+    }
+
+    pub fn set_time_to_live(&self, time_to_live: Option<Duration>) {
+        self.refs.set_time_to_live(time_to_live);
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use tracing::error;
+
+use crate::shared::{RequestContext, RequestResult};
+
+use super::github::GithubClient;
+
+mod cache;
+mod requests;
+
+pub mod models;
+
+use self::cache::GitCache;
+
+/**
+    A client for git repositories on any host, such as those used for git
+    dependencies in `Cargo.toml` files, using the git smart HTTP protocol.
+
+    Repositories hosted on GitHub may also use the GitHub API, through
+    the `GithubClient`, for information that git itself does not list.
+*/
+#[derive(Debug, Clone)]
+pub struct GitClient {
+    cache: GitCache,
+    github: GithubClient,
+    context: RequestContext,
+}
+
+impl GitClient {
+    #[must_use]
+    pub(crate) fn new(github: GithubClient, context: RequestContext) -> Self {
+        Self {
+            cache: GitCache::new(),
+            github,
+            context,
+        }
+    }
+
+    /**
+        Overrides how long repository data is cached for,
+        or resets it to the default if `None` is given.
+    */
+    pub fn set_cache_ttl(&self, ttl: Option<Duration>) {
+        self.cache.set_time_to_live(ttl);
+    }
+
+    fn emit_result<T>(result: &RequestResult<T>) {
+        if let Err(e) = &result {
+            error!("Git error: {e}");
+        }
+    }
+}
+
+impl Default for GitClient {
+    fn default() -> Self {
+        Self::new(GithubClient::default(), RequestContext::default())
+    }
+}
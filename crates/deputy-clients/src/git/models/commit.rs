@@ -0,0 +1,21 @@
+/**
+    A single commit in a git repository.
+*/
+#[derive(Debug, Clone)]
+pub struct GitCommit {
+    pub sha: String,
+    /// The first line of the commit message
+    pub summary: Option<String>,
+    /// The date the commit was made, in ISO 8601 format
+    pub date: Option<String>,
+}
+
+impl GitCommit {
+    /**
+        The abbreviated commit hash, as shown by `git log --oneline`.
+    */
+    #[must_use]
+    pub fn short_sha(&self) -> &str {
+        self.sha.get(..7).unwrap_or(&self.sha)
+    }
+}
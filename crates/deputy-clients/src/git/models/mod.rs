@@ -0,0 +1,7 @@
+mod commit;
+mod refs;
+mod remote;
+
+pub use self::commit::*;
+pub use self::refs::*;
+pub use self::remote::*;
@@ -0,0 +1,266 @@
+use deputy_versioning::Versioned;
+
+const REF_PREFIX_BRANCH: &str = "refs/heads/";
+const REF_PREFIX_TAG: &str = "refs/tags/";
+const REF_SUFFIX_PEELED: &str = "^{}";
+
+/**
+    A single branch or tag in a git repository.
+*/
+#[derive(Debug, Clone)]
+pub struct GitRef {
+    /// The full name of the ref, such as `refs/tags/v1.0.0`
+    pub name: String,
+    pub sha: String,
+    /// The commit that an annotated tag points to, if this is one
+    pub peeled_sha: Option<String>,
+}
+
+impl GitRef {
+    #[must_use]
+    pub fn branch_name(&self) -> Option<&str> {
+        self.name.strip_prefix(REF_PREFIX_BRANCH)
+    }
+
+    #[must_use]
+    pub fn tag_name(&self) -> Option<&str> {
+        self.name.strip_prefix(REF_PREFIX_TAG)
+    }
+
+    /**
+        The commit that this ref points to - for annotated
+        tags, this is not the same as the sha of the tag itself.
+    */
+    #[must_use]
+    pub fn commit_sha(&self) -> &str {
+        self.peeled_sha.as_deref().unwrap_or(&self.sha)
+    }
+}
+
+impl Versioned for GitRef {
+    fn raw_version_string(&self) -> String {
+        let name = self.tag_name().or(self.branch_name()).unwrap_or(&self.name);
+        name.trim_start_matches('v').to_string()
+    }
+}
+
+/**
+    All branches and tags advertised by a git repository.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct GitRefs {
+    /// The ref that `HEAD` points to, usually the default branch
+    pub head: Option<String>,
+    pub refs: Vec<GitRef>,
+}
+
+impl GitRefs {
+    /**
+        Parses the ref advertisement sent by a git server for
+        `info/refs?service=git-upload-pack`, in the `pkt-line` format.
+
+        See <https://git-scm.com/docs/http-protocol> for more information.
+    */
+    #[must_use]
+    pub fn parse_upload_pack(bytes: &[u8]) -> Option<Self> {
+        let mut refs = Self::default();
+        let mut rest = bytes;
+        let mut seen_service = false;
+
+        while rest.len() >= 4 {
+            let len = std::str::from_utf8(&rest[..4]).ok()?;
+            let len = usize::from_str_radix(len, 16).ok()?;
+            if len == 0 {
+                // Flush packet
+                rest = &rest[4..];
+                continue;
+            }
+            if len < 4 || len > rest.len() {
+                return None;
+            }
+
+            let line = String::from_utf8_lossy(&rest[4..len]);
+            let line = line.trim_end_matches('\n');
+            rest = &rest[len..];
+
+            if line.starts_with("# service=") {
+                seen_service = true;
+                continue;
+            }
+
+            // The first ref is followed by a list of capabilities
+            let (line, capabilities) = line.split_once('\0').unwrap_or((line, ""));
+            if let Some(head) = capabilities
+                .split(' ')
+                .find_map(|c| c.strip_prefix("symref=HEAD:"))
+            {
+                refs.head = Some(head.to_string());
+            }
+
+            let Some((sha, name)) = line.split_once(' ') else {
+                continue;
+            };
+            if let Some(name) = name.strip_suffix(REF_SUFFIX_PEELED) {
+                // Peeled annotated tags always directly follow the tag itself
+                if let Some(last) = refs.refs.last_mut()
+                    && last.name == name
+                {
+                    last.peeled_sha = Some(sha.to_string());
+                }
+                continue;
+            }
+            refs.refs.push(GitRef {
+                name: name.to_string(),
+                sha: sha.to_string(),
+                peeled_sha: None,
+            });
+        }
+
+        seen_service.then_some(refs)
+    }
+
+    #[must_use]
+    pub fn branch(&self, name: &str) -> Option<&GitRef> {
+        self.refs.iter().find(|r| r.branch_name() == Some(name))
+    }
+
+    #[must_use]
+    pub fn tag(&self, name: &str) -> Option<&GitRef> {
+        self.refs.iter().find(|r| r.tag_name() == Some(name))
+    }
+
+    pub fn tags(&self) -> impl Iterator<Item = &GitRef> {
+        self.refs.iter().filter(|r| r.tag_name().is_some())
+    }
+
+    /**
+        The default branch of the repository, if the server advertised one.
+    */
+    #[must_use]
+    pub fn default_branch(&self) -> Option<&GitRef> {
+        let head = self.head.as_deref()?;
+        self.refs.iter().find(|r| r.name == head)
+    }
+
+    /**
+        Finds a branch or tag that points to a commit starting with the
+        given, possibly abbreviated, commit hash.
+
+        Note that commits that are not the tip of any branch or tag can not
+        be found here, since git only advertises the commits for its refs.
+    */
+    #[must_use]
+    pub fn find_commit(&self, rev: &str) -> Option<&GitRef> {
+        let rev = rev.to_ascii_lowercase();
+        self.refs
+            .iter()
+            .find(|r| r.sha.starts_with(&rev) || r.commit_sha().starts_with(&rev))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: &str = "1111111111111111111111111111111111111111";
+    const FEATURE: &str = "2222222222222222222222222222222222222222";
+    const TAG_LIGHT: &str = "3333333333333333333333333333333333333333";
+    const TAG_ANNOTATED: &str = "4444444444444444444444444444444444444444";
+    const TAG_COMMIT: &str = "5555555555555555555555555555555555555555";
+
+    fn pkt_line(line: &str) -> String {
+        format!("{:04x}{line}", line.len() + 4)
+    }
+
+    fn advertisement(lines: &[String]) -> Vec<u8> {
+        let mut bytes = pkt_line("# service=git-upload-pack\n");
+        bytes.push_str("0000");
+        for line in lines {
+            bytes.push_str(&pkt_line(line));
+        }
+        bytes.push_str("0000");
+        bytes.into_bytes()
+    }
+
+    fn refs() -> GitRefs {
+        GitRefs::parse_upload_pack(&advertisement(&[
+            format!("{MAIN} HEAD\0multi_ack symref=HEAD:refs/heads/main agent=git/2.43.0\n"),
+            format!("{FEATURE} refs/heads/feature/parser\n"),
+            format!("{MAIN} refs/heads/main\n"),
+            format!("{TAG_LIGHT} refs/tags/v1.0.0\n"),
+            format!("{TAG_ANNOTATED} refs/tags/v1.1.0\n"),
+            format!("{TAG_COMMIT} refs/tags/v1.1.0^{{}}\n"),
+        ]))
+        .unwrap()
+    }
+
+    // parse_upload_pack
+
+    #[test]
+    fn parses_refs_and_default_branch() {
+        let refs = refs();
+        assert_eq!(refs.head.as_deref(), Some("refs/heads/main"));
+        assert_eq!(refs.refs.len(), 5);
+        assert_eq!(refs.default_branch().unwrap().sha, MAIN);
+        assert_eq!(refs.branch("feature/parser").unwrap().sha, FEATURE);
+        assert_eq!(refs.tags().count(), 2);
+    }
+
+    #[test]
+    fn parses_peeled_tags() {
+        let refs = refs();
+        let light = refs.tag("v1.0.0").unwrap();
+        assert_eq!(light.peeled_sha, None);
+        assert_eq!(light.commit_sha(), TAG_LIGHT);
+
+        let annotated = refs.tag("v1.1.0").unwrap();
+        assert_eq!(annotated.sha, TAG_ANNOTATED);
+        assert_eq!(annotated.commit_sha(), TAG_COMMIT);
+    }
+
+    #[test]
+    fn parses_empty_repository() {
+        let refs = GitRefs::parse_upload_pack(&advertisement(&[])).unwrap();
+        assert!(refs.refs.is_empty());
+        assert!(refs.default_branch().is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_advertisements() {
+        for bytes in [
+            // Not a smart HTTP response, such as a login page
+            &b"<!DOCTYPE html><html></html>"[..],
+            // Missing the service announcement
+            b"003f1111111111111111111111111111111111111111 refs/heads/main\n0000",
+            // Length longer than the response
+            b"001e# service=git-upload-pack\n0000ffff",
+            // Length shorter than the length itself
+            b"0002",
+        ] {
+            assert!(
+                GitRefs::parse_upload_pack(bytes).is_none(),
+                "{}",
+                String::from_utf8_lossy(bytes)
+            );
+        }
+    }
+
+    // find_commit
+
+    #[test]
+    fn finds_commits_by_abbreviated_hash() {
+        let refs = refs();
+        assert_eq!(refs.find_commit("2222222").unwrap().sha, FEATURE);
+        assert_eq!(refs.find_commit("555555").unwrap().sha, TAG_ANNOTATED);
+        assert!(refs.find_commit("6666666").is_none());
+    }
+
+    // raw_version_string
+
+    #[test]
+    fn versions_strip_prefix() {
+        let refs = refs();
+        assert_eq!(refs.tag("v1.0.0").unwrap().raw_version_string(), "1.0.0");
+        assert_eq!(refs.branch("main").unwrap().raw_version_string(), "main");
+    }
+}
@@ -1,6 +1,8 @@
+use std::time::Duration;
+
 use crate::shared::{RequestCacheMap, RequestResult};
 
-use super::models::{GitTreeRoot, RepositoryMetrics, RepositoryRelease};
+use super::models::{GitTreeRoot, RepositoryCommit, RepositoryMetrics, RepositoryRelease};
 
 #[allow(clippy::struct_field_names)]
 #[derive(Debug, Clone)]
@@ -9,6 +11,7 @@ pub(super) struct GithubCache {
     pub repository_releases: RequestCacheMap<RequestResult<Vec<RepositoryRelease>>>,
     pub repository_trees: RequestCacheMap<RequestResult<GitTreeRoot>>,
     pub repository_files: RequestCacheMap<RequestResult<Vec<u8>>>,
+    pub repository_commits: RequestCacheMap<RequestResult<RepositoryCommit>>,
 }
 
 impl GithubCache {
@@ -18,6 +21,7 @@ impl GithubCache {
             repository_releases: RequestCacheMap::new_mins(30, 5),
             repository_trees: RequestCacheMap::new_mins(45, 10),
             repository_files: RequestCacheMap::new_mins(10, 5),
+            repository_commits: RequestCacheMap::new_mins(15, 5),
         }
     }
 
@@ -26,5 +30,14 @@ impl GithubCache {
         self.repository_releases.invalidate();
         self.repository_trees.invalidate();
         self.repository_files.invalidate();
+        self.repository_commits.invalidate();
+    }
+
+    pub fn set_time_to_live(&self, time_to_live: Option<Duration>) {
+        self.repository_metrics.set_time_to_live(time_to_live);
+        self.repository_releases.set_time_to_live(time_to_live);
+        self.repository_trees.set_time_to_live(time_to_live);
+        self.repository_files.set_time_to_live(time_to_live);
+        self.repository_commits.set_time_to_live(time_to_live);
     }
 }
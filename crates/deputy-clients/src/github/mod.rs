@@ -1,11 +1,15 @@
-use std::sync::{
-    Arc, Mutex,
-    atomic::{AtomicBool, Ordering},
+use std::{
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
 };
 
+use base64::{Engine, prelude::BASE64_STANDARD};
 use tracing::error;
 
-use crate::shared::{Request, RequestResult};
+use crate::shared::{Request, RequestContext, RequestResult};
 
 mod cache;
 mod consts;
@@ -21,15 +25,19 @@ pub struct GithubClient {
     auth_token: Arc<Mutex<Option<String>>>,
     cache: GithubCache,
     rate_limited: Arc<AtomicBool>,
+    api_url_override: Arc<Mutex<Option<String>>>,
+    context: RequestContext,
 }
 
 impl GithubClient {
     #[must_use]
-    pub fn new() -> Self {
+    pub(crate) fn new(context: RequestContext) -> Self {
         Self {
             auth_token: Arc::new(Mutex::new(None)),
             cache: GithubCache::new(),
             rate_limited: Arc::new(AtomicBool::new(false)),
+            api_url_override: Arc::new(Mutex::new(None)),
+            context,
         }
     }
 
@@ -40,7 +48,7 @@ impl GithubClient {
             .with_header("Content-Type", consts::GITHUB_API_CONTENT_TYPE)
             .with_header(GITHUB_API_VERSION_NAME, GITHUB_API_VERSION_VALUE)
             .with_header_opt("Authorization", auth_token)
-            .send()
+            .send(&self.context)
             .await
     }
 
@@ -59,6 +67,52 @@ impl GithubClient {
         self.rate_limited.load(Ordering::SeqCst)
     }
 
+    /**
+        Overrides how long repository data is cached for,
+        or resets it to the default if `None` is given.
+    */
+    pub fn set_cache_ttl(&self, ttl: Option<Duration>) {
+        self.cache.set_time_to_live(ttl);
+    }
+
+    /**
+        Overrides the url of the GitHub API, such as for GitHub
+        Enterprise Server, or resets it if `None` is given.
+    */
+    pub fn set_api_url_override(&self, url: Option<String>) {
+        *self
+            .api_url_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = url;
+        self.cache.invalidate();
+    }
+
+    fn api_url(&self) -> String {
+        self.api_url_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .unwrap_or_else(|| consts::GITHUB_API_BASE_URL.to_string())
+    }
+
+    /**
+        Gets the authorization to send with git smart HTTP requests to GitHub, such as
+        for private repositories, using the same token as for the API - git only accepts
+        it as the password for basic authentication, and not as a bearer token.
+    */
+    pub(crate) fn git_authorization(&self) -> Option<String> {
+        let auth_token = self
+            .auth_token
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()?;
+        let token = auth_token.strip_prefix("Bearer ").unwrap_or(&auth_token);
+        Some(format!(
+            "Basic {}",
+            BASE64_STANDARD.encode(format!("x-access-token:{token}"))
+        ))
+    }
+
     #[allow(clippy::missing_panics_doc)]
     pub fn set_auth_token(&self, token: impl AsRef<str>) {
         let mut auth_token = self
@@ -77,6 +131,25 @@ impl GithubClient {
 
 impl Default for GithubClient {
     fn default() -> Self {
-        Self::new()
+        Self::new(RequestContext::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // git_authorization
+
+    #[test]
+    fn git_authorization_uses_basic_auth() {
+        let client = GithubClient::default();
+        assert_eq!(client.git_authorization(), None);
+
+        client.set_auth_token("ghp_token");
+        assert_eq!(
+            client.git_authorization().as_deref(),
+            Some("Basic eC1hY2Nlc3MtdG9rZW46Z2hwX3Rva2Vu")
+        );
     }
 }
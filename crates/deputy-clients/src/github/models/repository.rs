@@ -36,3 +36,23 @@ pub struct RepositoryReleaseAsset {
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
 }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepositoryCommit {
+    pub sha: String,
+    pub html_url: Option<String>,
+    pub commit: RepositoryCommitDetails,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepositoryCommitDetails {
+    pub message: String,
+    pub author: Option<RepositoryCommitSignature>,
+    pub committer: Option<RepositoryCommitSignature>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RepositoryCommitSignature {
+    pub name: Option<String>,
+    pub date: Option<String>,
+}
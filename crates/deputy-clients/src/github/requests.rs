@@ -1,10 +1,12 @@
+use reqwest::StatusCode;
 use tracing::debug;
 
 use super::consts::{
-    GITHUB_API_BASE_URL, GITHUB_API_CONTENT_TYPE_RAW, GITHUB_API_VERSION_NAME,
-    GITHUB_API_VERSION_VALUE,
+    GITHUB_API_CONTENT_TYPE_RAW, GITHUB_API_VERSION_NAME, GITHUB_API_VERSION_VALUE,
 };
-use super::models::{GitTreeRoot, RepositoryMetrics, RepositoryRelease};
+use crate::shared::{RequestError, ResponseError};
+
+use super::models::{GitTreeRoot, RepositoryCommit, RepositoryMetrics, RepositoryRelease};
 use super::{GithubClient, Request, RequestResult};
 
 impl GithubClient {
@@ -17,8 +19,10 @@ impl GithubClient {
         let owner_low = owner.to_ascii_lowercase();
         let repository_low = repository.to_ascii_lowercase();
 
-        let metrics_url =
-            format!("{GITHUB_API_BASE_URL}/repos/{owner_low}/{repository_low}/community/profile");
+        let metrics_url = format!(
+            "{}/repos/{owner_low}/{repository_low}/community/profile",
+            self.api_url()
+        );
 
         let fut = async move {
             debug!("Fetching GitHub metrics for {owner}/{repository}");
@@ -38,7 +42,7 @@ impl GithubClient {
 
         self.cache
             .repository_metrics
-            .with_caching(format!("{owner_low}/{repository_low}"), fut)
+            .with_revalidation(format!("{owner_low}/{repository_low}"), fut)
             .await
     }
 
@@ -51,8 +55,10 @@ impl GithubClient {
         let owner_low = owner.to_ascii_lowercase();
         let repository_low = repository.to_ascii_lowercase();
 
-        let releases_url =
-            format!("{GITHUB_API_BASE_URL}/repos/{owner_low}/{repository_low}/releases");
+        let releases_url = format!(
+            "{}/repos/{owner_low}/{repository_low}/releases",
+            self.api_url()
+        );
 
         let fut = async move {
             debug!("Fetching GitHub releases for {owner}/{repository}");
@@ -72,7 +78,7 @@ impl GithubClient {
 
         self.cache
             .repository_releases
-            .with_caching(format!("{owner_low}/{repository_low}"), fut)
+            .with_revalidation(format!("{owner_low}/{repository_low}"), fut)
             .await
     }
 
@@ -87,8 +93,10 @@ impl GithubClient {
         let repository_low = repository.to_ascii_lowercase();
         let sha_low = sha.to_ascii_lowercase();
 
-        let git_tree_url =
-            format!("{GITHUB_API_BASE_URL}/repos/{owner_low}/{repository_low}/git/trees/{sha_low}");
+        let git_tree_url = format!(
+            "{}/repos/{owner_low}/{repository_low}/git/trees/{sha_low}",
+            self.api_url()
+        );
 
         let fut = async move {
             debug!("Fetching GitHub tree for {owner}/{repository}/{sha}");
@@ -108,7 +116,7 @@ impl GithubClient {
 
         self.cache
             .repository_trees
-            .with_caching(format!("{owner_low}/{repository_low}/{sha_low}"), fut)
+            .with_revalidation(format!("{owner_low}/{repository_low}/{sha_low}"), fut)
             .await
     }
 
@@ -123,8 +131,10 @@ impl GithubClient {
         let owner_low = owner.to_ascii_lowercase();
         let repository_low = repository.to_ascii_lowercase();
 
-        let git_file_url =
-            format!("{GITHUB_API_BASE_URL}/repos/{owner_low}/{repository_low}/contents/{path}");
+        let git_file_url = format!(
+            "{}/repos/{owner_low}/{repository_low}/contents/{path}",
+            self.api_url()
+        );
 
         let agent_auth = self.auth_token.lock().unwrap().clone();
         let fut = async move {
@@ -134,7 +144,7 @@ impl GithubClient {
                 .with_header("Accept", GITHUB_API_CONTENT_TYPE_RAW)
                 .with_header(GITHUB_API_VERSION_NAME, GITHUB_API_VERSION_VALUE)
                 .with_header_opt("Authorization", agent_auth)
-                .send()
+                .send(&self.context)
                 .await;
 
             self.emit_result(&result);
@@ -144,7 +154,58 @@ impl GithubClient {
 
         self.cache
             .repository_files
-            .with_caching(format!("{owner_low}/{repository_low}/{path}"), fut)
+            .with_revalidation(format!("{owner_low}/{repository_low}/{path}"), fut)
+            .await
+    }
+
+    /**
+        Fetches a single commit, given as a branch, tag, or commit hash.
+
+        Commits that do not exist are returned as not found errors.
+    */
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn get_repository_commit(
+        &self,
+        owner: &str,
+        repository: &str,
+        rev: &str,
+    ) -> RequestResult<RepositoryCommit> {
+        let owner_low = owner.to_ascii_lowercase();
+        let repository_low = repository.to_ascii_lowercase();
+
+        let commit_url = format!(
+            "{}/repos/{owner_low}/{repository_low}/commits/{rev}",
+            self.api_url()
+        );
+
+        let fut = async move {
+            debug!("Fetching GitHub commit for {owner}/{repository}@{rev}");
+
+            // NOTE: We make this inner scope so that
+            // we can catch and emit all errors at once
+            let inner = async {
+                let bytes = self.request_get(&commit_url).await?;
+                Ok(serde_json::from_slice::<RepositoryCommit>(&bytes)?)
+            }
+            .await;
+
+            // GitHub responds with 422 instead of 404 for unknown commit hashes
+            let inner = inner.map_err(|e| match e {
+                RequestError::Response(r) if r.status() == StatusCode::UNPROCESSABLE_ENTITY => {
+                    ResponseError::from_status_and_string(StatusCode::NOT_FOUND, r.to_string())
+                        .into()
+                }
+                e => e,
+            });
+
+            self.emit_result(&inner);
+
+            inner
+        };
+
+        self.cache
+            .repository_commits
+            .with_revalidation(format!("{owner_low}/{repository_low}/{rev}"), fut)
             .await
     }
 }
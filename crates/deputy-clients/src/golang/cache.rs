@@ -1,11 +1,14 @@
+use std::time::Duration;
+
 use crate::shared::{RequestCacheMap, RequestResult};
 
-use super::models::ModuleVersion;
+use super::models::{GoProxyConfig, ModuleVersion};
 
 #[derive(Debug, Clone)]
 pub(super) struct GolangCache {
     pub version_lists: RequestCacheMap<RequestResult<Vec<String>>>,
     pub latest_versions: RequestCacheMap<RequestResult<ModuleVersion>>,
+    pub configs: RequestCacheMap<GoProxyConfig>,
 }
 
 impl GolangCache {
@@ -13,6 +16,12 @@ impl GolangCache {
         Self {
             version_lists: RequestCacheMap::new_mins(60, 15),
             latest_versions: RequestCacheMap::new_mins(60, 15),
+            configs: RequestCacheMap::new_mins(5, 5),
         }
     }
+
+    pub fn set_time_to_live(&self, time_to_live: Option<Duration>) {
+        self.version_lists.set_time_to_live(time_to_live);
+        self.latest_versions.set_time_to_live(time_to_live);
+    }
 }
@@ -0,0 +1,123 @@
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use tokio::process::Command;
+use tracing::debug;
+
+use super::GolangClient;
+use super::consts::BASE_URL_PROXY;
+use super::models::GoProxyConfig;
+
+const GO_ENV_KEYS: [&str; 4] = ["GOPROXY", "GOPRIVATE", "GONOPROXY", "GONOSUMDB"];
+const GO_ENV_CACHE_KEY: &str = "go env";
+
+impl GolangClient {
+    /**
+        Resolves the module proxy settings for a `go.mod` in the given directory.
+
+        Uses the output of `go env`, which includes both environment variables and
+        any values set using `go env -w`. If the go command is not available, the
+        environment variables and the `go env -w` config file are read directly.
+
+        These settings are the same for all modules, so they are only read once,
+        and cached for a few minutes, instead of running `go env` for every module.
+    */
+    pub async fn get_proxy_config(&self, module_dir: &Path) -> GoProxyConfig {
+        let module_dir = module_dir.to_path_buf();
+        let fut = async {
+            debug!("Reading go proxy config in '{}'", module_dir.display());
+
+            let values = match try_go_env(&module_dir).await {
+                Some(values) => values,
+                None => read_go_env_fallback().await,
+            };
+
+            let get = |key: &str| values.get(key).map(String::as_str);
+            GoProxyConfig::from_values(
+                get("GOPROXY"),
+                get("GOPRIVATE"),
+                get("GONOPROXY"),
+                get("GONOSUMDB"),
+            )
+        };
+
+        let mut config = self.cache.configs.with_caching(GO_ENV_CACHE_KEY, fut).await;
+        if let Some(url) = self.registry_override() {
+            config.replace_proxy(BASE_URL_PROXY, &url);
+        }
+        config
+    }
+}
+
+// The output of `go env` is our primary source, since it is always correct...
+
+async fn try_go_env(module_dir: &Path) -> Option<HashMap<String, String>> {
+    let output = Command::new("go")
+        .arg("env")
+        .arg("-json")
+        .args(GO_ENV_KEYS)
+        .current_dir(module_dir)
+        // Never download a newer toolchain just to read settings, for modules that require one
+        .env("GOTOOLCHAIN", "local")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let values: HashMap<String, String> = serde_json::from_slice(&output.stdout).ok()?;
+    Some(
+        values
+            .into_iter()
+            .filter(|(_, value)| !value.is_empty())
+            .collect(),
+    )
+}
+
+// ... and we fall back to reading environment variables and the config file, if go is not available
+
+async fn read_go_env_fallback() -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    for key in GO_ENV_KEYS {
+        if let Ok(value) = env::var(key)
+            && !value.is_empty()
+        {
+            values.insert(key.to_string(), value);
+        }
+    }
+
+    // Values set using `go env -w` have lower priority than environment variables
+    if let Some(path) = go_env_file_path()
+        && let Ok(contents) = tokio::fs::read_to_string(&path).await
+    {
+        for line in contents.lines() {
+            let Some((key, value)) = line.trim().split_once('=') else {
+                continue;
+            };
+            if GO_ENV_KEYS.contains(&key) && !value.is_empty() {
+                values
+                    .entry(key.to_string())
+                    .or_insert_with(|| value.to_string());
+            }
+        }
+    }
+
+    values
+}
+
+fn go_env_file_path() -> Option<PathBuf> {
+    match env::var_os("GOENV") {
+        Some(path) if path == "off" => None,
+        Some(path) => Some(PathBuf::from(path)),
+        None => dirs::config_dir().map(|dir| dir.join("go").join("env")),
+    }
+}
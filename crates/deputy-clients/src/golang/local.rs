@@ -0,0 +1,58 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use super::GolangClient;
+
+impl GolangClient {
+    /**
+        Finds the `go.mod` of a module version that has been downloaded to the module cache.
+
+        The module cache is read from `GOMODCACHE`, or `pkg/mod` inside of
+        the first directory in `GOPATH`, which defaults to `$HOME/go`.
+    */
+    pub async fn find_cached_module(&self, path: &str, version: &str) -> Option<PathBuf> {
+        let cache_dir = module_cache_dir()?;
+        let module_dir = cache_dir.join(format!(
+            "{}@{}",
+            escape_module_path(path),
+            escape_module_path(version)
+        ));
+
+        let go_mod = module_dir.join("go.mod");
+        if tokio::fs::try_exists(&go_mod).await.unwrap_or_default() {
+            Some(go_mod)
+        } else {
+            None
+        }
+    }
+}
+
+fn module_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("GOMODCACHE").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    let gopath = env::var_os("GOPATH")
+        .filter(|path| !path.is_empty())
+        .and_then(|paths| env::split_paths(&paths).next())
+        .or_else(|| dirs::home_dir().map(|home| home.join("go")))?;
+    Some(Path::new(&gopath).join("pkg").join("mod"))
+}
+
+/**
+    Escapes a module path or version for use in the module cache,
+    where uppercase letters are replaced with `!` and a lowercase letter.
+*/
+fn escape_module_path(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if c.is_ascii_uppercase() {
+            escaped.push('!');
+            escaped.push(c.to_ascii_lowercase());
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
@@ -1,11 +1,18 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
 use tracing::error;
 
-use crate::shared::{Request, RequestResult};
+use crate::shared::{Request, RequestContext, RequestResult};
 
 use super::github::GithubClient;
 
 mod cache;
+mod config;
 mod consts;
+mod local;
 mod requests;
 mod util;
 
@@ -17,19 +24,50 @@ use self::cache::GolangCache;
 pub struct GolangClient {
     cache: GolangCache,
     github: GithubClient,
+    registry_override: Arc<Mutex<Option<String>>>,
+    context: RequestContext,
 }
 
 impl GolangClient {
     #[must_use]
-    pub fn new(github: GithubClient) -> Self {
+    pub(crate) fn new(github: GithubClient, context: RequestContext) -> Self {
         Self {
             cache: GolangCache::new(),
             github,
+            registry_override: Arc::new(Mutex::new(None)),
+            context,
         }
     }
 
     async fn request_get(&self, url: impl Into<String>) -> RequestResult<Vec<u8>> {
-        Request::get(url).send().await
+        Request::get(url).send(&self.context).await
+    }
+
+    /**
+        Overrides how long registry data is cached for,
+        or resets it to the default if `None` is given.
+    */
+    pub fn set_cache_ttl(&self, ttl: Option<Duration>) {
+        self.cache.set_time_to_live(ttl);
+    }
+
+    /**
+        Overrides the proxy url that is used instead of the public Go module
+        proxy, or resets it if `None` is given. Any other proxies configured
+        using `GOPROXY` are used as before.
+    */
+    pub fn set_registry_override(&self, url: Option<String>) {
+        *self
+            .registry_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = url;
+    }
+
+    fn registry_override(&self) -> Option<String> {
+        self.registry_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
     }
 
     fn emit_result<T>(result: &RequestResult<T>) {
@@ -41,6 +79,6 @@ impl GolangClient {
 
 impl Default for GolangClient {
     fn default() -> Self {
-        Self::new(GithubClient::new())
+        Self::new(GithubClient::default(), RequestContext::default())
     }
 }
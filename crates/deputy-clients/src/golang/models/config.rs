@@ -0,0 +1,330 @@
+use super::super::consts::BASE_URL_PROXY;
+
+const DEFAULT_GOPROXY: &str = "https://proxy.golang.org,direct";
+
+/**
+    A single entry in the `GOPROXY` list.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GoProxyTarget {
+    /// A module proxy, reachable at the given url
+    Url(String),
+    /// Fetch modules directly from version control
+    Direct,
+    /// Disallow fetching modules entirely
+    Off,
+}
+
+/**
+    A single entry in the `GOPROXY` list, along with its fallback behavior.
+
+    Entries separated by a comma only fall back to the next entry if a module
+    was not found, while entries separated by a pipe fall back on any error.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoProxy {
+    pub target: GoProxyTarget,
+    pub fallback_on_any_error: bool,
+}
+
+/**
+    Module proxy settings, matching `GOPROXY`, `GOPRIVATE`,
+    `GONOPROXY` and `GONOSUMDB` for the go command.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoProxyConfig {
+    pub proxies: Vec<GoProxy>,
+    pub no_proxy: Vec<String>,
+    pub no_sum_db: Vec<String>,
+}
+
+impl GoProxyConfig {
+    /**
+        Creates proxy settings from the values of the `GOPROXY`, `GOPRIVATE`,
+        `GONOPROXY` and `GONOSUMDB` variables, using the same defaults as Go.
+    */
+    #[must_use]
+    pub fn from_values(
+        goproxy: Option<&str>,
+        goprivate: Option<&str>,
+        gonoproxy: Option<&str>,
+        gonosumdb: Option<&str>,
+    ) -> Self {
+        let goproxy = goproxy.filter(|s| !s.trim().is_empty());
+        let private = parse_patterns(goprivate.unwrap_or_default());
+        Self {
+            proxies: parse_proxies(goproxy.unwrap_or(DEFAULT_GOPROXY)),
+            no_proxy: gonoproxy.map_or_else(|| private.clone(), parse_patterns),
+            no_sum_db: gonosumdb.map_or_else(|| private.clone(), parse_patterns),
+        }
+    }
+
+    /**
+        Returns the proxies to try, in order, when fetching the given module.
+
+        Modules matching `GONOPROXY` are always fetched directly.
+    */
+    #[must_use]
+    pub fn proxies_for(&self, module: &str) -> Vec<GoProxy> {
+        if matches_any_pattern(&self.no_proxy, module) {
+            vec![GoProxy {
+                target: GoProxyTarget::Direct,
+                fallback_on_any_error: false,
+            }]
+        } else {
+            self.proxies.clone()
+        }
+    }
+
+    /**
+        Replaces any proxy with the given url, such as the public Go module proxy, with another one.
+    */
+    pub fn replace_proxy(&mut self, url: &str, replacement: &str) {
+        for proxy in &mut self.proxies {
+            if proxy.target == GoProxyTarget::Url(url.to_string()) {
+                proxy.target = GoProxyTarget::Url(replacement.trim_end_matches('/').to_string());
+            }
+        }
+    }
+
+    /**
+        Checks if the given module is private - meaning it matches any
+        of the `GOPRIVATE`, `GONOPROXY` or `GONOSUMDB` patterns, and that
+        it is not expected to be available from any public proxy.
+    */
+    #[must_use]
+    pub fn is_private(&self, module: &str) -> bool {
+        matches_any_pattern(&self.no_proxy, module) || matches_any_pattern(&self.no_sum_db, module)
+    }
+}
+
+impl Default for GoProxyConfig {
+    fn default() -> Self {
+        Self::from_values(None, None, None, None)
+    }
+}
+
+fn parse_proxies(value: &str) -> Vec<GoProxy> {
+    let mut proxies = Vec::new();
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let (entry, separator, remaining) = match rest.find([',', '|']) {
+            Some(index) => (
+                &rest[..index],
+                rest[index..].chars().next(),
+                &rest[index + 1..],
+            ),
+            None => (rest, None, ""),
+        };
+        rest = remaining;
+
+        let target = match entry.trim() {
+            "" => continue,
+            "direct" => GoProxyTarget::Direct,
+            "off" => GoProxyTarget::Off,
+            url => {
+                let url = url.trim_end_matches('/');
+                if url.contains("://") {
+                    GoProxyTarget::Url(url.to_string())
+                } else {
+                    GoProxyTarget::Url(format!("https://{url}"))
+                }
+            }
+        };
+        proxies.push(GoProxy {
+            target,
+            fallback_on_any_error: separator == Some('|'),
+        });
+    }
+    if proxies.is_empty() {
+        proxies.push(GoProxy {
+            target: GoProxyTarget::Url(BASE_URL_PROXY.to_string()),
+            fallback_on_any_error: false,
+        });
+    }
+    proxies
+}
+
+fn parse_patterns(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| pattern.trim_end_matches('/').to_string())
+        .collect()
+}
+
+/**
+    Checks if any of the given glob patterns match a prefix of the module
+    path, the same way as the go command - a pattern such as `example.com`
+    matches `example.com/foo` as well as `example.com/foo/bar`, for example.
+*/
+fn matches_any_pattern(patterns: &[String], module: &str) -> bool {
+    let module_parts = module.split('/').collect::<Vec<_>>();
+    patterns.iter().any(|pattern| {
+        let pattern_parts = pattern.split('/').collect::<Vec<_>>();
+        pattern_parts.len() <= module_parts.len()
+            && pattern_parts
+                .iter()
+                .zip(&module_parts)
+                .all(|(pattern, part)| matches_glob(pattern, part))
+    })
+}
+
+fn matches_glob(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((bp, bt)) => {
+                    backtrack = Some((bp, bt + 1));
+                    p = bp + 1;
+                    t = bt + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str, fallback_on_any_error: bool) -> GoProxy {
+        GoProxy {
+            target: GoProxyTarget::Url(url.to_string()),
+            fallback_on_any_error,
+        }
+    }
+
+    fn target(target: GoProxyTarget) -> GoProxy {
+        GoProxy {
+            target,
+            fallback_on_any_error: false,
+        }
+    }
+
+    // from_values
+
+    #[test]
+    fn parses_goproxy() {
+        for (goproxy, expected) in [
+            (
+                None,
+                vec![url(BASE_URL_PROXY, false), target(GoProxyTarget::Direct)],
+            ),
+            (
+                Some(" "),
+                vec![url(BASE_URL_PROXY, false), target(GoProxyTarget::Direct)],
+            ),
+            (Some("off"), vec![target(GoProxyTarget::Off)]),
+            (
+                Some("proxy.example.com/go/|https://other.example.com,direct"),
+                vec![
+                    url("https://proxy.example.com/go", true),
+                    url("https://other.example.com", false),
+                    target(GoProxyTarget::Direct),
+                ],
+            ),
+            (Some(",,"), vec![url(BASE_URL_PROXY, false)]),
+        ] {
+            assert_eq!(
+                GoProxyConfig::from_values(goproxy, None, None, None).proxies,
+                expected,
+                "{goproxy:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn goprivate_is_the_default_for_gonoproxy_and_gonosumdb() {
+        let config = GoProxyConfig::from_values(None, Some("example.com/private/"), None, None);
+        assert_eq!(config.no_proxy, ["example.com/private"]);
+        assert_eq!(config.no_sum_db, ["example.com/private"]);
+
+        let config = GoProxyConfig::from_values(
+            None,
+            Some("example.com/private"),
+            Some(""),
+            Some("example.com/nosum, example.com/other"),
+        );
+        assert!(config.no_proxy.is_empty());
+        assert_eq!(config.no_sum_db, ["example.com/nosum", "example.com/other"]);
+    }
+
+    // proxies_for & is_private
+
+    #[test]
+    fn private_modules_are_fetched_directly() {
+        let config = GoProxyConfig::from_values(
+            None,
+            Some("*.corp.example.com,example.com/team"),
+            None,
+            None,
+        );
+        for (module, private) in [
+            ("git.corp.example.com/repo", true),
+            ("git.corp.example.com/repo/sub", true),
+            ("corp.example.com/repo", false),
+            ("example.com/team", true),
+            ("example.com/team/repo", true),
+            ("example.com/teams/repo", false),
+            ("example.com", false),
+            ("github.com/owner/repo", false),
+        ] {
+            assert_eq!(config.is_private(module), private, "{module}");
+            let expected = if private {
+                vec![target(GoProxyTarget::Direct)]
+            } else {
+                config.proxies.clone()
+            };
+            assert_eq!(config.proxies_for(module), expected, "{module}");
+        }
+    }
+
+    // replace_proxy
+
+    #[test]
+    fn replaces_proxy_urls() {
+        let mut config = GoProxyConfig::default();
+        config.replace_proxy(BASE_URL_PROXY, "https://mirror.example.com/");
+        assert_eq!(
+            config.proxies,
+            [
+                url("https://mirror.example.com", false),
+                target(GoProxyTarget::Direct)
+            ]
+        );
+    }
+
+    // matches_glob
+
+    #[test]
+    fn matches_globs() {
+        for (pattern, text, expected) in [
+            ("*", "anything", true),
+            ("git.*.com", "git.example.com", true),
+            ("git.*.com", "git.example.org", false),
+            ("v?", "v2", true),
+            ("v?", "v10", false),
+            ("*-*", "a-b-c", true),
+            ("exact", "exact", true),
+            ("exact", "exactly", false),
+        ] {
+            assert_eq!(matches_glob(pattern, text), expected, "{pattern} {text}");
+        }
+    }
+}
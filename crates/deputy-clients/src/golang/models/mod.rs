@@ -1,3 +1,5 @@
+mod config;
 mod proxy;
 
+pub use self::config::*;
 pub use self::proxy::*;
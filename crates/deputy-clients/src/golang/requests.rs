@@ -3,16 +3,66 @@ use tracing::debug;
 use crate::github::models::RepositoryMetrics;
 use crate::shared::RequestError;
 
-use super::consts::BASE_URL_PROXY;
-use super::models::ModuleVersion;
+use super::models::{GoProxyConfig, GoProxyTarget, ModuleVersion};
 use super::util::{encode_module_path, extract_github_owner_repo};
 use super::{GolangClient, RequestResult};
 
 impl GolangClient {
     #[allow(clippy::missing_errors_doc)]
     pub async fn get_module_versions(&self, module: &str) -> RequestResult<Vec<String>> {
+        self.get_module_versions_from(&GoProxyConfig::default(), module)
+            .await
+    }
+
+    /**
+        Fetches all known versions of a module, trying each of the
+        proxies in the given config in order, the same way Go does.
+
+        See [`GolangClient::get_proxy_config`] for resolving proxy settings.
+    */
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn get_module_versions_from(
+        &self,
+        config: &GoProxyConfig,
+        module: &str,
+    ) -> RequestResult<Vec<String>> {
+        try_proxies(config, module, |proxy_url| {
+            self.get_proxy_module_versions(proxy_url, module)
+        })
+        .await
+    }
+
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn get_module_latest(&self, module: &str) -> RequestResult<ModuleVersion> {
+        self.get_module_latest_from(&GoProxyConfig::default(), module)
+            .await
+    }
+
+    /**
+        Fetches the latest version of a module, trying each of the
+        proxies in the given config in order, the same way Go does.
+
+        See [`GolangClient::get_proxy_config`] for resolving proxy settings.
+    */
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn get_module_latest_from(
+        &self,
+        config: &GoProxyConfig,
+        module: &str,
+    ) -> RequestResult<ModuleVersion> {
+        try_proxies(config, module, |proxy_url| {
+            self.get_proxy_module_latest(proxy_url, module)
+        })
+        .await
+    }
+
+    async fn get_proxy_module_versions(
+        &self,
+        proxy_url: String,
+        module: &str,
+    ) -> RequestResult<Vec<String>> {
         let encoded = encode_module_path(module);
-        let url = format!("{BASE_URL_PROXY}/{encoded}/@v/list");
+        let url = format!("{proxy_url}/{encoded}/@v/list");
 
         let fut = async {
             debug!("Fetching Go module versions for '{module}'");
@@ -38,14 +88,17 @@ impl GolangClient {
 
         self.cache
             .version_lists
-            .with_caching(encoded.clone(), fut)
+            .with_revalidation(url.clone(), fut)
             .await
     }
 
-    #[allow(clippy::missing_errors_doc)]
-    pub async fn get_module_latest(&self, module: &str) -> RequestResult<ModuleVersion> {
+    async fn get_proxy_module_latest(
+        &self,
+        proxy_url: String,
+        module: &str,
+    ) -> RequestResult<ModuleVersion> {
         let encoded = encode_module_path(module);
-        let url = format!("{BASE_URL_PROXY}/{encoded}/@latest");
+        let url = format!("{proxy_url}/{encoded}/@latest");
 
         let fut = async {
             debug!("Fetching Go module latest for '{module}'");
@@ -65,7 +118,7 @@ impl GolangClient {
 
         self.cache
             .latest_versions
-            .with_caching(encoded.clone(), fut)
+            .with_revalidation(url.clone(), fut)
             .await
     }
 
@@ -80,3 +133,164 @@ impl GolangClient {
         self.github.get_repository_metrics(&owner, &repo).await
     }
 }
+
+/**
+    Tries each proxy for the given module in order, falling back to the
+    next proxy when a module is not found or gone, or on any error if the proxy
+    was followed by a pipe instead of a comma, matching the go command.
+
+    Fetching modules directly from version control is not supported, so
+    we stop at `direct` or `off` and return the last error, if any, or
+    an error describing why the module could not be fetched at all.
+*/
+async fn try_proxies<T, F, Fut>(config: &GoProxyConfig, module: &str, fetch: F) -> RequestResult<T>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = RequestResult<T>>,
+{
+    let mut error = None;
+    for proxy in config.proxies_for(module) {
+        let proxy_url = match proxy.target {
+            GoProxyTarget::Url(proxy_url) => proxy_url,
+            GoProxyTarget::Direct => break,
+            GoProxyTarget::Off => {
+                return Err(error.unwrap_or_else(|| {
+                    RequestError::Client(format!(
+                        "Module '{module}' can not be fetched, since module lookups are disabled by GOPROXY=off"
+                    ))
+                }));
+            }
+        };
+        match fetch(proxy_url).await {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                let should_fall_back =
+                    proxy.fallback_on_any_error || e.is_not_found_or_gone_error();
+                error = Some(e);
+                if !should_fall_back {
+                    break;
+                }
+            }
+        }
+    }
+    Err(error.unwrap_or_else(|| {
+        RequestError::Client(format!(
+            "Module '{module}' must be fetched directly, which is not supported"
+        ))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use reqwest::StatusCode;
+
+    use crate::shared::ResponseError;
+
+    use super::*;
+
+    fn not_found() -> RequestError {
+        ResponseError::from_status_and_string(StatusCode::NOT_FOUND, "not found").into()
+    }
+
+    fn gone() -> RequestError {
+        ResponseError::from_status_and_string(StatusCode::GONE, "gone").into()
+    }
+
+    fn unavailable() -> RequestError {
+        ResponseError::from_status_and_string(StatusCode::BAD_GATEWAY, "bad gateway").into()
+    }
+
+    /**
+        Tries all proxies for a module, where each proxy url maps to the
+        result of fetching from it, and returns the result along with
+        all of the proxies that were tried, in order.
+    */
+    async fn try_all(
+        goproxy: &str,
+        results: &[(&str, Option<RequestError>)],
+    ) -> (RequestResult<String>, Vec<String>) {
+        let config = GoProxyConfig::from_values(Some(goproxy), None, None, None);
+        let tried = Mutex::new(Vec::new());
+        let result = try_proxies(&config, "example.com/module", |url| {
+            tried.lock().unwrap().push(url.clone());
+            let result = results
+                .iter()
+                .find(|(proxy, _)| *proxy == url)
+                .map_or_else(|| Ok(url.clone()), |(_, e)| Err(e.clone().unwrap()));
+            async move { result }
+        })
+        .await;
+        (result, tried.into_inner().unwrap())
+    }
+
+    // try_proxies
+
+    #[tokio::test]
+    async fn falls_back_on_not_found() {
+        let (result, tried) = try_all(
+            "https://a.example.com,https://b.example.com",
+            &[("https://a.example.com", Some(not_found()))],
+        )
+        .await;
+        assert_eq!(result.unwrap(), "https://b.example.com");
+        assert_eq!(tried, ["https://a.example.com", "https://b.example.com"]);
+    }
+
+    #[tokio::test]
+    async fn falls_back_on_gone() {
+        let (result, tried) = try_all(
+            "https://a.example.com,direct",
+            &[("https://a.example.com", Some(gone()))],
+        )
+        .await;
+        assert!(result.unwrap_err().is_not_found_or_gone_error());
+        assert_eq!(tried, ["https://a.example.com"]);
+
+        let (result, tried) = try_all(
+            "https://a.example.com,https://b.example.com",
+            &[("https://a.example.com", Some(gone()))],
+        )
+        .await;
+        assert_eq!(result.unwrap(), "https://b.example.com");
+        assert_eq!(tried, ["https://a.example.com", "https://b.example.com"]);
+    }
+
+    #[tokio::test]
+    async fn falls_back_on_any_error_after_pipe() {
+        let (result, tried) = try_all(
+            "https://a.example.com,https://b.example.com",
+            &[("https://a.example.com", Some(unavailable()))],
+        )
+        .await;
+        assert!(!result.unwrap_err().is_not_found_error());
+        assert_eq!(tried, ["https://a.example.com"]);
+
+        let (result, _) = try_all(
+            "https://a.example.com|https://b.example.com",
+            &[("https://a.example.com", Some(unavailable()))],
+        )
+        .await;
+        assert_eq!(result.unwrap(), "https://b.example.com");
+    }
+
+    #[tokio::test]
+    async fn stops_at_direct_and_off() {
+        let (result, tried) = try_all(
+            "https://a.example.com,direct",
+            &[("https://a.example.com", Some(not_found()))],
+        )
+        .await;
+        assert!(result.unwrap_err().is_not_found_error());
+        assert_eq!(tried, ["https://a.example.com"]);
+
+        let (result, tried) = try_all("direct", &[]).await;
+        assert!(result.unwrap_err().to_string().contains("fetched directly"));
+        assert!(tried.is_empty());
+
+        let (result, tried) = try_all("off", &[]).await;
+        assert!(result.unwrap_err().to_string().contains("GOPROXY=off"));
+        assert!(tried.is_empty());
+    }
+}
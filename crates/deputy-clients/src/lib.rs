@@ -1,54 +1,172 @@
 #![allow(dead_code)]
 
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
 mod shared;
 
 pub mod crates;
+pub mod git;
 pub mod github;
 pub mod golang;
+pub mod lockfile;
 pub mod npm;
+pub mod osv;
 pub mod pypi;
 pub mod wally;
 
+pub use self::shared::{
+    HttpConfig, HttpTransport, RecordingTransport, RegistryOutage, ReplayTransport, RequestError,
+    RequestResult, ReqwestTransport, ResponseError, TransportFuture, TransportRequest,
+    TransportResponse, track_used_hosts,
+};
+
 use self::crates::CratesClient;
+use self::git::GitClient;
 use self::github::GithubClient;
 use self::golang::GolangClient;
+use self::lockfile::LockfileClient;
 use self::npm::NpmClient;
+use self::osv::OsvClient;
 use self::pypi::PyPiClient;
+use self::shared::RequestContext;
 use self::wally::WallyClient;
 
 #[derive(Debug, Clone)]
 pub struct Clients {
+    context: RequestContext,
     pub crates: CratesClient,
+    pub git: GitClient,
     pub github: GithubClient,
     pub golang: GolangClient,
+    pub lockfile: LockfileClient,
     pub npm: NpmClient,
+    pub osv: OsvClient,
     pub pypi: PyPiClient,
     pub wally: WallyClient,
 }
 
 impl Clients {
+    /**
+        Creates all clients, sending every request through the given transport -
+        use [`ReqwestTransport`] to send requests over the network, which is also
+        what [`Clients::default`] does.
+
+        Each instance has its own response cache and circuit breakers, shared by all of
+        its clients - note that the response cache persists responses on disk unless
+        disabled using [`Clients::set_persistent_cache`], which is usually wanted for tests.
+    */
     #[must_use]
-    pub fn new() -> Self {
-        let crates = CratesClient::new();
-        let github = GithubClient::new();
-        let golang = GolangClient::new(github.clone());
-        let npm = NpmClient::new();
-        let pypi = PyPiClient::new();
+    pub fn new(transport: Arc<dyn HttpTransport>) -> Self {
+        let context = RequestContext::new(transport);
+        let crates = CratesClient::new(context.clone());
+        let github = GithubClient::new(context.clone());
+        let git = GitClient::new(github.clone(), context.clone());
+        let golang = GolangClient::new(github.clone(), context.clone());
+        let lockfile = LockfileClient::new();
+        let npm = NpmClient::new(context.clone());
+        let osv = OsvClient::new(context.clone());
+        let pypi = PyPiClient::new(context.clone());
         let wally = WallyClient::new(github.clone());
 
         Self {
+            context,
             crates,
+            git,
             github,
             golang,
+            lockfile,
             npm,
+            osv,
             pypi,
             wally,
         }
     }
+
+    /**
+        Overrides how long data from registries is cached for,
+        for all clients, or resets it to the default if `None` is given.
+    */
+    pub fn set_cache_ttl(&self, ttl: Option<Duration>) {
+        self.crates.set_cache_ttl(ttl);
+        self.git.set_cache_ttl(ttl);
+        self.github.set_cache_ttl(ttl);
+        self.golang.set_cache_ttl(ttl);
+        self.npm.set_cache_ttl(ttl);
+        self.osv.set_cache_ttl(ttl);
+        self.pypi.set_cache_ttl(ttl);
+    }
+
+    /**
+        Enables or disables the persistent cache, which stores responses on
+        disk so that they are still available after restarting, optionally
+        in a custom directory instead of the platform cache directory.
+
+        The persistent cache is enabled by default, and is shared by all clients.
+    */
+    pub fn set_persistent_cache(&self, enabled: bool, dir: Option<PathBuf>) {
+        self.context.cache.set_disk_enabled(enabled, dir);
+    }
+
+    /**
+        Enables or disables persisting responses to requests that were sent with
+        credentials, such as for private registries - these are only cached in
+        memory by default, so that private data is never written to disk.
+    */
+    pub fn set_persist_authenticated(&self, enabled: bool) {
+        self.context.cache.set_persist_authenticated(enabled);
+    }
+
+    /**
+        Enables or disables offline mode - while offline, no requests are
+        sent, and all data is read from the persistent cache instead.
+    */
+    pub fn set_offline(&self, offline: bool) {
+        self.context.cache.set_offline(offline);
+    }
+
+    /**
+        Checks if offline mode is enabled.
+    */
+    #[must_use]
+    pub fn is_offline(&self) -> bool {
+        self.context.cache.is_offline()
+    }
+
+    /**
+        Configures how requests are sent by all clients - through which proxy, with which
+        extra root certificates and client certificate, and with which timeouts.
+
+        The configuration is passed on to the transport, and only applies to this instance.
+
+        If the configuration is invalid, such as when a certificate file can not
+        be read, an error is returned and the previous configuration is kept.
+    */
+    #[allow(clippy::missing_errors_doc)]
+    pub fn set_http_config(&self, config: HttpConfig) -> RequestResult<()> {
+        self.context.transport.configure(config)
+    }
+
+    /**
+        Gets all hosts that requests are currently being rejected for by a proxy,
+        either directly or because of an untrusted certificate, until a request succeeds.
+    */
+    #[must_use]
+    pub fn rejected_hosts(&self) -> Vec<RegistryOutage> {
+        self.context.circuits.rejections()
+    }
+
+    /**
+        Subscribes to outages of registries and APIs - an outage is reported once when
+        requests to a host keep failing, and requests to it are paused for a while.
+    */
+    #[must_use]
+    pub fn subscribe_outages(&self) -> tokio::sync::broadcast::Receiver<RegistryOutage> {
+        self.context.circuits.subscribe()
+    }
 }
 
 impl Default for Clients {
     fn default() -> Self {
-        Self::new()
+        Self::new(Arc::new(ReqwestTransport::new()))
     }
 }
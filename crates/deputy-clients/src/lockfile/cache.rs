@@ -0,0 +1,18 @@
+use std::sync::Arc;
+
+use crate::shared::RequestCacheMap;
+
+use super::models::Lockfile;
+
+#[derive(Debug, Clone)]
+pub(super) struct LockfileCache {
+    pub lockfiles: RequestCacheMap<Option<Arc<Lockfile>>>,
+}
+
+impl LockfileCache {
+    pub fn new() -> Self {
+        Self {
+            lockfiles: RequestCacheMap::new_secs(10, 10),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use super::models::Lockfile;
+
+/**
+    Reads module versions from a `go.sum` file.
+
+    Lines that only contain a checksum for the `go.mod` file of a module are skipped,
+    since those are also added for modules that are never downloaded or built.
+*/
+pub(super) fn read_go_sum(lockfile: &mut Lockfile, contents: &str) {
+    for line in contents.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(module), Some(version)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if !version.ends_with("/go.mod") {
+            lockfile.insert(module, version);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::super::models::LockfileKind;
+    use super::*;
+
+    const GO_SUM: &str = "\
+github.com/google/uuid v1.6.0 h1:NIvaJDMOsjHA8n1jAhLSgzrAzy1Hgr+hNrb57e+94F0=
+github.com/google/uuid v1.6.0/go.mod h1:TIyPZe4MgqvfeYDBFedMoGGpEw/LqOeaOT+nhxU+yHo=
+golang.org/x/net v0.20.0 h1:aCL9BSgETF1k+blQaYUBx9hJ9LOGP3gAVemcZlf1Kpo=
+golang.org/x/net v0.21.0 h1:AQyQV4dYCvJ7vGmJyKki9+PBdyvhkSd8EIx/qb0AYv4=
+golang.org/x/text v0.14.0/go.mod h1:18ZOQIKpY8NJVqYksKHtTdi31H5itFRjB5/qKTNYzSU=
+
+malformed
+";
+
+    #[test]
+    fn reads_go_sum() {
+        let mut lockfile = Lockfile::new(LockfileKind::Go, PathBuf::from("go.sum"));
+        read_go_sum(&mut lockfile, GO_SUM);
+
+        for (module, expected) in [
+            ("github.com/google/uuid", &["v1.6.0"][..]),
+            ("golang.org/x/net", &["v0.20.0", "v0.21.0"]),
+            // Only the go.mod file was needed, so the module was never downloaded
+            ("golang.org/x/text", &[]),
+            ("malformed", &[]),
+        ] {
+            assert_eq!(lockfile.locked_versions(module), expected, "{module}");
+        }
+    }
+}
@@ -0,0 +1,35 @@
+mod cache;
+mod gosum;
+mod npm;
+mod package_list;
+mod pnpm;
+mod requests;
+mod yarn;
+
+pub mod models;
+
+use self::cache::LockfileCache;
+
+/**
+    A client for reading lockfiles, such as `Cargo.lock` or `package-lock.json`,
+    to find the versions of dependencies that are actually installed.
+*/
+#[derive(Debug, Clone)]
+pub struct LockfileClient {
+    cache: LockfileCache,
+}
+
+impl LockfileClient {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            cache: LockfileCache::new(),
+        }
+    }
+}
+
+impl Default for LockfileClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
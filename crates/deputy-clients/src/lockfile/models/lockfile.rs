@@ -0,0 +1,104 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::pypi::util::normalize_name;
+
+/**
+    The ecosystem of a lockfile, which determines the
+    lockfiles that are searched for, and how they are read.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LockfileKind {
+    /// `Cargo.lock`
+    Cargo,
+    /// `package-lock.json`, `npm-shrinkwrap.json`, `pnpm-lock.yaml` or `yarn.lock`
+    Npm,
+    /// `uv.lock` or `poetry.lock`
+    Python,
+    /// `go.sum`
+    Go,
+}
+
+impl LockfileKind {
+    /**
+        The names of lockfiles for this ecosystem, in priority order.
+    */
+    #[must_use]
+    pub const fn file_names(self) -> &'static [&'static str] {
+        match self {
+            Self::Cargo => &["Cargo.lock"],
+            Self::Npm => &[
+                "npm-shrinkwrap.json",
+                "package-lock.json",
+                "pnpm-lock.yaml",
+                "yarn.lock",
+            ],
+            Self::Python => &["uv.lock", "poetry.lock"],
+            Self::Go => &["go.sum"],
+        }
+    }
+
+    /**
+        Checks if lockfiles for this ecosystem may be found in parent directories of
+        the manifest, such as for workspaces - otherwise only the manifest directory is used.
+    */
+    #[must_use]
+    pub const fn searches_ancestors(self) -> bool {
+        !matches!(self, Self::Go)
+    }
+
+    pub(super) fn normalize_name(self, name: &str) -> String {
+        match self {
+            Self::Python => normalize_name(name),
+            Self::Cargo | Self::Npm | Self::Go => name.to_string(),
+        }
+    }
+}
+
+/**
+    The packages in a lockfile, and all of their locked versions.
+
+    A package may be locked at several versions at once, for example
+    when it is both a direct dependency and a transitive dependency.
+*/
+#[derive(Debug, Clone)]
+pub struct Lockfile {
+    pub kind: LockfileKind,
+    pub path: PathBuf,
+    packages: HashMap<String, Vec<String>>,
+}
+
+impl Lockfile {
+    pub(crate) fn new(kind: LockfileKind, path: PathBuf) -> Self {
+        Self {
+            kind,
+            path,
+            packages: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, name: &str, version: impl Into<String>) {
+        let version = version.into();
+        let versions = self
+            .packages
+            .entry(self.kind.normalize_name(name))
+            .or_default();
+        if !versions.contains(&version) {
+            versions.push(version);
+        }
+    }
+
+    /**
+        Returns all locked versions of the package with the given name, if any.
+    */
+    #[must_use]
+    pub fn locked_versions(&self, name: &str) -> &[String] {
+        self.packages
+            .get(&self.kind.normalize_name(name))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+}
@@ -0,0 +1,3 @@
+mod lockfile;
+
+pub use self::lockfile::*;
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use tracing::debug;
+
+use super::models::Lockfile;
+
+#[derive(Debug, Default, Deserialize)]
+struct PackageLock {
+    #[serde(default)]
+    packages: HashMap<String, PackageLockEntry>,
+    #[serde(default)]
+    dependencies: HashMap<String, PackageLockDependency>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PackageLockEntry {
+    version: Option<String>,
+    #[serde(default)]
+    link: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PackageLockDependency {
+    version: Option<String>,
+    #[serde(default)]
+    dependencies: HashMap<String, PackageLockDependency>,
+}
+
+/**
+    Reads packages from a `package-lock.json` or `npm-shrinkwrap.json` file.
+
+    Newer lockfiles list packages by their path in `node_modules`, while
+    lockfiles from npm 6 and older contain a tree of nested dependencies.
+*/
+pub(super) fn read_package_lock(lockfile: &mut Lockfile, contents: &str) {
+    let file = match serde_json::from_str::<PackageLock>(contents) {
+        Ok(file) => file,
+        Err(e) => {
+            debug!(
+                "Failed to parse lockfile at '{}' - {e}",
+                lockfile.path.display()
+            );
+            return;
+        }
+    };
+
+    for (path, entry) in &file.packages {
+        let Some((_, name)) = path.rsplit_once("node_modules/") else {
+            continue; // The root package, or a workspace package
+        };
+        if let Some(version) = &entry.version
+            && !entry.link
+            && is_registry_version(version)
+        {
+            lockfile.insert(name, version.clone());
+        }
+    }
+
+    let mut stack = file.dependencies.iter().collect::<Vec<_>>();
+    while let Some((name, dep)) = stack.pop() {
+        if let Some(version) = &dep.version
+            && is_registry_version(version)
+        {
+            lockfile.insert(name, version.clone());
+        }
+        stack.extend(&dep.dependencies);
+    }
+}
+
+/**
+    Checks if a locked version is a plain version from a registry,
+    and not a path, git url, or tarball url for the package.
+*/
+pub(super) fn is_registry_version(version: &str) -> bool {
+    version.starts_with(|c: char| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::super::models::LockfileKind;
+    use super::*;
+
+    const PACKAGE_LOCK: &str = r#"{
+        "name": "app",
+        "lockfileVersion": 3,
+        "packages": {
+            "": { "name": "app", "version": "1.0.0" },
+            "node_modules/@types/node": { "version": "20.11.5" },
+            "node_modules/lodash": { "version": "4.17.21" },
+            "node_modules/chalk/node_modules/ansi-styles": { "version": "4.3.0" },
+            "node_modules/ansi-styles": { "version": "6.2.1" },
+            "node_modules/shared": { "resolved": "packages/shared", "link": true },
+            "packages/shared": { "version": "0.1.0" },
+            "node_modules/forked": { "version": "git+ssh://git@github.com/user/forked.git#abc123" },
+            "node_modules/local": { "version": "file:../local" }
+        }
+    }"#;
+
+    const PACKAGE_LOCK_LEGACY: &str = r#"{
+        "name": "app",
+        "lockfileVersion": 1,
+        "dependencies": {
+            "lodash": { "version": "4.17.21" },
+            "chalk": {
+                "version": "4.1.2",
+                "dependencies": {
+                    "ansi-styles": { "version": "4.3.0" }
+                }
+            },
+            "forked": { "version": "github:user/forked#abc123" }
+        }
+    }"#;
+
+    fn read(contents: &str) -> Lockfile {
+        let mut lockfile = Lockfile::new(LockfileKind::Npm, PathBuf::from("package-lock.json"));
+        read_package_lock(&mut lockfile, contents);
+        lockfile
+    }
+
+    #[test]
+    fn reads_package_lock() {
+        let lockfile = read(PACKAGE_LOCK);
+        for (name, expected) in [
+            ("@types/node", &["20.11.5"][..]),
+            ("lodash", &["4.17.21"]),
+            ("ansi-styles", &["4.3.0", "6.2.1"]),
+            ("app", &[]),
+            ("shared", &[]),
+            ("forked", &[]),
+            ("local", &[]),
+        ] {
+            let mut versions = lockfile.locked_versions(name).to_vec();
+            versions.sort();
+            assert_eq!(versions, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn reads_legacy_package_lock() {
+        let lockfile = read(PACKAGE_LOCK_LEGACY);
+        for (name, expected) in [
+            ("lodash", &["4.17.21"][..]),
+            ("chalk", &["4.1.2"]),
+            ("ansi-styles", &["4.3.0"]),
+            ("forked", &[]),
+        ] {
+            assert_eq!(lockfile.locked_versions(name), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn ignores_invalid_package_lock() {
+        assert!(read("{ \"packages\": ").is_empty());
+    }
+
+    // is_registry_version
+
+    #[test]
+    fn registry_versions() {
+        for (version, expected) in [
+            ("1.2.3", true),
+            ("0.0.0-beta.1", true),
+            ("file:../local", false),
+            ("git+https://github.com/user/repo.git", false),
+            ("https://example.com/package.tgz", false),
+            ("npm:other@1.0.0", false),
+            ("", false),
+        ] {
+            assert_eq!(is_registry_version(version), expected, "{version}");
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use serde::Deserialize;
+use tracing::debug;
+
+use super::models::Lockfile;
+
+#[derive(Debug, Default, Deserialize)]
+struct PackageList {
+    #[serde(default)]
+    package: Vec<PackageListEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageListEntry {
+    name: String,
+    version: String,
+}
+
+/**
+    Reads packages from a TOML lockfile with a `[[package]]` list - this is
+    the format used by `Cargo.lock`, and also by `uv.lock` and `poetry.lock`.
+*/
+pub(super) fn read_package_list(lockfile: &mut Lockfile, contents: &str) {
+    let file = match toml::from_str::<PackageList>(contents) {
+        Ok(file) => file,
+        Err(e) => {
+            debug!(
+                "Failed to parse lockfile at '{}' - {e}",
+                lockfile.path.display()
+            );
+            return;
+        }
+    };
+    for package in file.package {
+        lockfile.insert(&package.name, package.version);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::super::models::LockfileKind;
+    use super::*;
+
+    const CARGO_LOCK: &str = r#"
+version = 4
+
+[[package]]
+name = "deputy"
+version = "0.7.0"
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.87"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+    const UV_LOCK: &str = r#"
+version = 1
+
+[[package]]
+name = "typing-extensions"
+version = "4.12.2"
+source = { registry = "https://pypi.org/simple" }
+
+[[package]]
+name = "Flask_SQLAlchemy"
+version = "3.1.1"
+"#;
+
+    fn read(kind: LockfileKind, contents: &str) -> Lockfile {
+        let mut lockfile = Lockfile::new(kind, PathBuf::from("test.lock"));
+        read_package_list(&mut lockfile, contents);
+        lockfile
+    }
+
+    #[test]
+    fn reads_cargo_lock() {
+        let lockfile = read(LockfileKind::Cargo, CARGO_LOCK);
+        for (name, expected) in [
+            ("deputy", &["0.7.0"][..]),
+            ("syn", &["1.0.109", "2.0.87"]),
+            ("serde", &[]),
+        ] {
+            assert_eq!(lockfile.locked_versions(name), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn reads_python_lockfile_with_normalized_names() {
+        let lockfile = read(LockfileKind::Python, UV_LOCK);
+        for (name, expected) in [
+            ("typing_extensions", &["4.12.2"][..]),
+            ("Typing.Extensions", &["4.12.2"]),
+            ("flask-sqlalchemy", &["3.1.1"]),
+        ] {
+            assert_eq!(lockfile.locked_versions(name), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn ignores_invalid_lockfile() {
+        assert!(read(LockfileKind::Cargo, "[[package]\nname =").is_empty());
+        assert!(read(LockfileKind::Cargo, "version = 4").is_empty());
+    }
+}
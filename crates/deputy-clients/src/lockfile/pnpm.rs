@@ -0,0 +1,138 @@
+use super::models::Lockfile;
+use super::npm::is_registry_version;
+
+/**
+    Reads packages from a `pnpm-lock.yaml` file.
+
+    Only the keys of the `packages` and `snapshots` sections are read, which
+    look like `/name@1.2.3(peer@4.5.6)` - or `/name/1.2.3_peer@4.5.6` in
+    lockfiles before version 6 - so we do not need a full YAML parser.
+*/
+pub(super) fn read_pnpm_lockfile(lockfile: &mut Lockfile, contents: &str) {
+    let mut is_legacy = false;
+    let mut in_packages = false;
+    for line in contents.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(' ') {
+            let line = line.trim_end();
+            if let Some(version) = line.strip_prefix("lockfileVersion:") {
+                is_legacy = version.trim().trim_matches(['\'', '"']).starts_with('5');
+            }
+            in_packages = matches!(line, "packages:" | "snapshots:");
+            continue;
+        }
+
+        // Package keys are indented by exactly two spaces
+        let Some(key) = line.strip_prefix("  ") else {
+            continue;
+        };
+        if !in_packages || key.starts_with(' ') {
+            continue;
+        }
+        let Some(key) = key.trim_end().strip_suffix(':') else {
+            continue;
+        };
+
+        let key = key.trim_matches(['\'', '"']).trim_start_matches('/');
+        let key = key.split_once('(').map_or(key, |(key, _)| key);
+        let name_and_version = if is_legacy {
+            key.rsplit_once('/')
+                .map(|(name, version)| (name, version.split_once('_').map_or(version, |(v, _)| v)))
+        } else {
+            // Scoped package names also start with an `@`, so skip past it
+            key.get(1..)
+                .and_then(|rest| rest.find('@'))
+                .map(|index| (&key[..=index], &key[index + 2..]))
+        };
+
+        if let Some((name, version)) = name_and_version
+            && is_registry_version(version)
+        {
+            lockfile.insert(name, version);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::super::models::LockfileKind;
+    use super::*;
+
+    const PNPM_LOCK_V9: &str = "\
+lockfileVersion: '9.0'
+
+importers:
+
+  .:
+    dependencies:
+      lodash:
+        specifier: ^4.17.0
+        version: 4.17.21
+
+packages:
+
+  '@types/node@20.11.5':
+    resolution: {integrity: sha512-abc}
+
+  lodash@4.17.21:
+    resolution: {integrity: sha512-def}
+
+  local@file:../local:
+    resolution: {directory: ../local, type: directory}
+
+snapshots:
+
+  react-dom@18.2.0(react@18.2.0):
+    dependencies:
+      react: 18.2.0
+";
+
+    const PNPM_LOCK_V6: &str = "\
+lockfileVersion: '6.0'
+
+packages:
+
+  /@types/node@20.11.5:
+    resolution: {integrity: sha512-abc}
+    dev: true
+
+  /react-dom@18.2.0(react@18.2.0):
+    resolution: {integrity: sha512-ghi}
+";
+
+    const PNPM_LOCK_V5: &str = "\
+lockfileVersion: 5.4
+
+packages:
+
+  /@types/node/20.11.5:
+    resolution: {integrity: sha512-abc}
+
+  /react-dom/18.2.0_react@18.2.0:
+    resolution: {integrity: sha512-ghi}
+";
+
+    #[test]
+    fn reads_pnpm_lockfiles() {
+        for (contents, name, expected) in [
+            (PNPM_LOCK_V9, "@types/node", &["20.11.5"][..]),
+            (PNPM_LOCK_V9, "lodash", &["4.17.21"]),
+            (PNPM_LOCK_V9, "react-dom", &["18.2.0"]),
+            (PNPM_LOCK_V9, "local", &[]),
+            (PNPM_LOCK_V9, ".", &[]),
+            (PNPM_LOCK_V6, "@types/node", &["20.11.5"]),
+            (PNPM_LOCK_V6, "react-dom", &["18.2.0"]),
+            (PNPM_LOCK_V5, "@types/node", &["20.11.5"]),
+            (PNPM_LOCK_V5, "react-dom", &["18.2.0"]),
+            (PNPM_LOCK_V5, "react", &[]),
+        ] {
+            let mut lockfile = Lockfile::new(LockfileKind::Npm, PathBuf::from("pnpm-lock.yaml"));
+            read_pnpm_lockfile(&mut lockfile, contents);
+            assert_eq!(lockfile.locked_versions(name), expected, "{name}");
+        }
+    }
+}
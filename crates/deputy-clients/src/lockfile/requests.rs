@@ -0,0 +1,73 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tracing::debug;
+
+use super::LockfileClient;
+use super::gosum::read_go_sum;
+use super::models::{Lockfile, LockfileKind};
+use super::npm::read_package_lock;
+use super::package_list::read_package_list;
+use super::pnpm::read_pnpm_lockfile;
+use super::yarn::read_yarn_lockfile;
+
+impl LockfileClient {
+    /**
+        Finds and reads the lockfile for a manifest in the given directory.
+
+        Lockfiles are searched for in the manifest directory, and then in each of its
+        ancestors, since workspaces usually share a single lockfile at their root.
+
+        Returns `None` if no lockfile was found, or if it could not be read.
+    */
+    pub async fn get_lockfile(
+        &self,
+        kind: LockfileKind,
+        manifest_dir: &Path,
+    ) -> Option<Arc<Lockfile>> {
+        let cache_key = format!("{kind:?}|{}", manifest_dir.display());
+
+        let manifest_dir = manifest_dir.to_path_buf();
+        let fut = async {
+            let path = find_lockfile(kind, &manifest_dir).await?;
+            debug!("Reading lockfile at '{}'", path.display());
+
+            let contents = tokio::fs::read_to_string(&path).await.ok()?;
+            let file_name = path.file_name()?.to_string_lossy().to_string();
+
+            let mut lockfile = Lockfile::new(kind, path);
+            match file_name.as_str() {
+                "package-lock.json" | "npm-shrinkwrap.json" => {
+                    read_package_lock(&mut lockfile, &contents);
+                }
+                "pnpm-lock.yaml" => read_pnpm_lockfile(&mut lockfile, &contents),
+                "yarn.lock" => read_yarn_lockfile(&mut lockfile, &contents),
+                "go.sum" => read_go_sum(&mut lockfile, &contents),
+                _ => read_package_list(&mut lockfile, &contents),
+            }
+
+            Some(Arc::new(lockfile))
+        };
+
+        self.cache.lockfiles.with_caching(cache_key, fut).await
+    }
+}
+
+async fn find_lockfile(kind: LockfileKind, manifest_dir: &Path) -> Option<PathBuf> {
+    let dirs = if kind.searches_ancestors() {
+        manifest_dir.ancestors().collect::<Vec<_>>()
+    } else {
+        vec![manifest_dir]
+    };
+    for dir in dirs {
+        for file_name in kind.file_names() {
+            let path = dir.join(file_name);
+            if tokio::fs::try_exists(&path).await.unwrap_or_default() {
+                return Some(path);
+            }
+        }
+    }
+    None
+}
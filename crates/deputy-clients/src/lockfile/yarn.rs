@@ -0,0 +1,132 @@
+use super::models::Lockfile;
+use super::npm::is_registry_version;
+
+/**
+    Reads packages from a `yarn.lock` file, in either the classic format
+    or the YAML-based format used by Yarn 2 and newer, which both look like:
+
+    ```text
+    "name@^1.0.0", "name@^1.2.0":
+      version "1.2.3"
+    ```
+*/
+pub(super) fn read_yarn_lockfile(lockfile: &mut Lockfile, contents: &str) {
+    let mut names = Vec::new();
+    for line in contents.lines() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // Unindented lines list all the descriptors that resolve to the entry below
+        if !line.starts_with(' ') {
+            names = line
+                .trim_end()
+                .trim_end_matches(':')
+                .split(',')
+                .filter_map(descriptor_name)
+                .collect::<Vec<_>>();
+            names.dedup();
+            continue;
+        }
+
+        let trimmed = line.trim();
+        let Some(version) = trimmed
+            .strip_prefix("version:")
+            .or_else(|| trimmed.strip_prefix("version "))
+        else {
+            continue;
+        };
+        let version = version.trim().trim_matches('"');
+        if is_registry_version(version) {
+            for name in names.drain(..) {
+                lockfile.insert(name, version);
+            }
+        }
+    }
+}
+
+/**
+    Extracts the package name from a descriptor such as `name@^1.0.0` or `@scope/name@npm:^1.0.0`.
+*/
+fn descriptor_name(descriptor: &str) -> Option<&str> {
+    let descriptor = descriptor.trim().trim_matches('"');
+    let index = descriptor.get(1..)?.find('@')?;
+    Some(&descriptor[..=index])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::super::models::LockfileKind;
+    use super::*;
+
+    const YARN_LOCK_CLASSIC: &str = r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@babel/core@^7.0.0", "@babel/core@^7.1.0":
+  version "7.24.0"
+  resolved "https://registry.yarnpkg.com/@babel/core/-/core-7.24.0.tgz"
+
+lodash@^4.17.0:
+  version "4.17.21"
+  resolved "https://registry.yarnpkg.com/lodash/-/lodash-4.17.21.tgz"
+
+lodash@^3.0.0:
+  version "3.10.1"
+
+"forked@github:user/forked":
+  version "https://codeload.github.com/user/forked/tar.gz/abc123"
+"#;
+
+    const YARN_LOCK_BERRY: &str = r#"# This file is generated by running "yarn install" inside your project.
+
+__metadata:
+  version: 8
+  cacheKey: 10c0
+
+"@babel/core@npm:^7.0.0, @babel/core@npm:^7.1.0":
+  version: 7.24.0
+  resolution: "@babel/core@npm:7.24.0"
+  dependencies:
+    debug: "npm:^4.1.0"
+
+"lodash@npm:^4.17.0":
+  version: 4.17.21
+  resolution: "lodash@npm:4.17.21"
+"#;
+
+    #[test]
+    fn reads_yarn_lockfiles() {
+        for (contents, name, expected) in [
+            (YARN_LOCK_CLASSIC, "@babel/core", &["7.24.0"][..]),
+            (YARN_LOCK_CLASSIC, "lodash", &["4.17.21", "3.10.1"]),
+            (YARN_LOCK_CLASSIC, "forked", &[]),
+            (YARN_LOCK_BERRY, "@babel/core", &["7.24.0"]),
+            (YARN_LOCK_BERRY, "lodash", &["4.17.21"]),
+            (YARN_LOCK_BERRY, "debug", &[]),
+            (YARN_LOCK_BERRY, "__metadata", &[]),
+        ] {
+            let mut lockfile = Lockfile::new(LockfileKind::Npm, PathBuf::from("yarn.lock"));
+            read_yarn_lockfile(&mut lockfile, contents);
+            assert_eq!(lockfile.locked_versions(name), expected, "{name}");
+        }
+    }
+
+    // descriptor_name
+
+    #[test]
+    fn descriptor_names() {
+        for (descriptor, expected) in [
+            ("lodash@^4.17.0", Some("lodash")),
+            (" \"lodash@npm:^4.17.0\"", Some("lodash")),
+            ("\"@babel/core@^7.0.0\"", Some("@babel/core")),
+            ("@babel/core@npm:^7.0.0", Some("@babel/core")),
+            ("__metadata", None),
+            ("", None),
+        ] {
+            assert_eq!(descriptor_name(descriptor), expected, "{descriptor}");
+        }
+    }
+}
@@ -1,16 +1,27 @@
+use std::time::Duration;
+
 use crate::shared::{RequestCacheMap, RequestResult};
 
-use super::models::RegistryMetadata;
+use super::config::NpmConfig;
+use super::models::{NpmWorkspace, RegistryMetadata};
 
 #[derive(Debug, Clone)]
 pub(super) struct NpmCache {
     pub registry_metadatas: RequestCacheMap<RequestResult<RegistryMetadata>>,
+    pub configs: RequestCacheMap<NpmConfig>,
+    pub workspaces: RequestCacheMap<Option<NpmWorkspace>>,
 }
 
 impl NpmCache {
     pub fn new() -> Self {
         Self {
             registry_metadatas: RequestCacheMap::new_mins(60, 15),
+            configs: RequestCacheMap::new_secs(30, 30),
+            workspaces: RequestCacheMap::new_secs(30, 30),
         }
     }
+
+    pub fn set_time_to_live(&self, time_to_live: Option<Duration>) {
+        self.registry_metadatas.set_time_to_live(time_to_live);
+    }
 }
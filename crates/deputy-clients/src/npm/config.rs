@@ -0,0 +1,336 @@
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+};
+
+use tracing::debug;
+
+use super::NpmClient;
+use super::consts::BASE_URL_REGISTRY;
+use super::models::NpmRegistry;
+
+impl NpmClient {
+    /**
+        Resolves the registry that the given package should be
+        fetched from, for a `package.json` in the given directory.
+
+        Reads `.npmrc` files the same way npm does - the project config, in the
+        package directory or the root of its workspace, followed by the user config
+        in `~/.npmrc`, and the global config in `$PREFIX/etc/npmrc`. Config files in
+        other ancestor directories are not used. The `NPM_CONFIG_REGISTRY` environment
+        variable overrides the default registry from any config file.
+
+        Supports scoped registries (`@scope:registry=...`), as well as
+        credentials for registries (`//host/path/:_authToken=...`).
+    */
+    pub async fn get_registry(&self, project_dir: &Path, name: &str) -> NpmRegistry {
+        let cache_key = project_dir.to_string_lossy().to_string();
+
+        let project_dir = project_dir.to_path_buf();
+        let fut = async {
+            debug!("Reading npm config for '{}'", project_dir.display());
+            let workspace = self.get_workspace(&project_dir).await;
+            let workspace_dir = workspace.as_ref().map(|w| w.root_dir.as_path());
+            NpmConfig::read(&config_paths(&project_dir, workspace_dir)).await
+        };
+
+        let config = self.cache.configs.with_caching(cache_key, fut).await;
+        config.resolve(name, self.registry_override().as_deref())
+    }
+}
+
+/**
+    Merged key-value pairs from all `.npmrc` files that apply to a project.
+*/
+#[derive(Debug, Clone, Default)]
+pub(super) struct NpmConfig {
+    values: HashMap<String, String>,
+}
+
+impl NpmConfig {
+    async fn read(paths: &[PathBuf]) -> Self {
+        let mut config = Self::default();
+
+        if let Some(registry) = env_var("NPM_CONFIG_REGISTRY") {
+            config.values.insert(String::from("registry"), registry);
+        }
+
+        for path in paths {
+            if let Ok(contents) = tokio::fs::read_to_string(&path).await {
+                config.merge(&contents);
+            }
+        }
+
+        config
+    }
+
+    fn merge(&mut self, contents: &str) {
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(['#', ';']) {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches(['"', '\'']);
+            self.values
+                .entry(key.trim().to_string())
+                .or_insert_with(|| expand_env_vars(value));
+        }
+    }
+
+    fn resolve(&self, name: &str, registry_override: Option<&str>) -> NpmRegistry {
+        let scope = name
+            .split_once('/')
+            .map(|(scope, _)| scope)
+            .filter(|scope| scope.starts_with('@'));
+
+        let url = scope
+            .and_then(|scope| self.values.get(&format!("{scope}:registry")))
+            .or_else(|| self.values.get("registry"))
+            .map_or(
+                registry_override.unwrap_or(BASE_URL_REGISTRY),
+                String::as_str,
+            )
+            .trim_end_matches('/')
+            .to_string();
+
+        let authorization = self.authorization_for(&url);
+        NpmRegistry { url, authorization }
+    }
+
+    /**
+        Finds credentials for the given registry url, if any.
+
+        Credentials are keyed by the registry url without its protocol,
+        and may apply to any path in the registry, so we check the
+        full path first and then each of its parents, in order.
+    */
+    fn authorization_for(&self, url: &str) -> Option<String> {
+        let without_protocol = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let mut prefix = format!("//{without_protocol}/");
+        loop {
+            if let Some(token) = self.values.get(&format!("{prefix}:_authToken")) {
+                return Some(format!("Bearer {token}"));
+            }
+            if let Some(auth) = self.values.get(&format!("{prefix}:_auth")) {
+                return Some(format!("Basic {auth}"));
+            }
+
+            // Strip the last path segment, keeping the trailing slash
+            let trimmed = prefix.trim_end_matches('/');
+            match trimmed.rfind('/') {
+                Some(index) if index > 1 => prefix.truncate(index + 1),
+                _ => return None,
+            }
+        }
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    env::var(name)
+        .or_else(|_| env::var(name.to_ascii_lowercase()))
+        .ok()
+        .filter(|value| !value.is_empty())
+}
+
+/**
+    Gets the paths of all `.npmrc` files that apply to a project, in priority order.
+
+    Config files with higher priority are read first, and only fill in values
+    that have not yet been set - the project config comes first, followed by
+    the user config, and finally the global config.
+*/
+fn config_paths(project_dir: &Path, workspace_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut paths = vec![project_dir.join(".npmrc")];
+    if let Some(workspace_dir) = workspace_dir {
+        paths.push(workspace_dir.join(".npmrc"));
+    }
+    paths.extend(user_config_path());
+    paths.extend(global_config_path());
+    paths.dedup();
+    paths
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    env_var("NPM_CONFIG_USERCONFIG")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".npmrc")))
+}
+
+fn global_config_path() -> Option<PathBuf> {
+    if let Some(path) = env_var("NPM_CONFIG_GLOBALCONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let prefix = env_var("NPM_CONFIG_PREFIX")
+        .map(PathBuf::from)
+        .or_else(node_prefix)?;
+    Some(prefix.join("etc").join("npmrc"))
+}
+
+/**
+    Finds the default npm prefix, which is the directory that node is installed
+    in on Windows, and the parent of the directory that node is installed in on
+    any other platform - such as `/usr/local` for `/usr/local/bin/node`.
+*/
+fn node_prefix() -> Option<PathBuf> {
+    let file_name = if cfg!(windows) { "node.exe" } else { "node" };
+    let node = env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(file_name))
+        .find(|path| path.is_file())?;
+    let node = std::fs::canonicalize(&node).unwrap_or(node);
+    let bin_dir = node.parent()?;
+    if cfg!(windows) {
+        Some(bin_dir.to_path_buf())
+    } else {
+        bin_dir.parent().map(Path::to_path_buf)
+    }
+}
+
+/**
+    Expands environment variables in the form of `${NAME}`,
+    which is commonly used to keep tokens out of `.npmrc` files.
+*/
+fn expand_env_vars(value: &str) -> String {
+    let mut result = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let name = &rest[start + 2..start + end];
+        result.push_str(&env::var(name.trim_end_matches('?')).unwrap_or_default());
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(files: &[&str]) -> NpmConfig {
+        let mut config = NpmConfig::default();
+        for file in files {
+            config.merge(file);
+        }
+        config
+    }
+
+    // config_paths
+
+    #[test]
+    fn reads_project_configs_only() {
+        let project_dir = Path::new("/repo/packages/app");
+        let paths = config_paths(project_dir, Some(Path::new("/repo")));
+        assert_eq!(paths[0], project_dir.join(".npmrc"));
+        assert_eq!(paths[1], Path::new("/repo").join(".npmrc"));
+        assert!(!paths.contains(&Path::new("/repo/packages").join(".npmrc")));
+
+        let paths = config_paths(project_dir, Some(project_dir));
+        assert_eq!(paths[0], project_dir.join(".npmrc"));
+        assert_ne!(paths.get(1), Some(&project_dir.join(".npmrc")));
+    }
+
+    // merge
+
+    #[test]
+    fn merges_config_files() {
+        let config = config(&[
+            "; comment\n# comment\nregistry = \"https://near.example.com/\"\n\ninvalid line\n",
+            "registry=https://far.example.com/\n@scope:registry=https://scoped.example.com\n",
+        ]);
+        assert_eq!(
+            config.values.get("registry").map(String::as_str),
+            Some("https://near.example.com/")
+        );
+        assert_eq!(
+            config.values.get("@scope:registry").map(String::as_str),
+            Some("https://scoped.example.com")
+        );
+        assert_eq!(config.values.len(), 2);
+    }
+
+    // resolve
+
+    #[test]
+    fn resolves_default_registry() {
+        let config = config(&[]);
+        assert_eq!(config.resolve("pkg", None), NpmRegistry::npmjs());
+        assert_eq!(
+            config.resolve("pkg", Some("https://mirror.example.com/")),
+            NpmRegistry {
+                url: String::from("https://mirror.example.com"),
+                authorization: None,
+            }
+        );
+    }
+
+    #[test]
+    fn resolves_scoped_registries() {
+        let config = config(&[
+            "registry=https://registry.example.com/\n@scope:registry=https://scoped.example.com/npm/\n",
+        ]);
+        assert_eq!(
+            config.resolve("@scope/pkg", None).url,
+            "https://scoped.example.com/npm"
+        );
+        assert_eq!(
+            config.resolve("@other/pkg", None).url,
+            "https://registry.example.com"
+        );
+        assert_eq!(
+            config
+                .resolve("pkg", Some("https://mirror.example.com"))
+                .url,
+            "https://registry.example.com"
+        );
+    }
+
+    // authorization_for
+
+    #[test]
+    fn finds_registry_credentials() {
+        let config = config(&[concat!(
+            "//registry.example.com/npm/private/:_authToken=private-token\n",
+            "//registry.example.com/:_authToken=host-token\n",
+            "//basic.example.com/:_auth=dXNlcjpwYXNz\n",
+        )]);
+        for (url, expected) in [
+            (
+                "https://registry.example.com/npm/private",
+                Some("Bearer private-token"),
+            ),
+            (
+                "https://registry.example.com/npm/public",
+                Some("Bearer host-token"),
+            ),
+            ("https://basic.example.com", Some("Basic dXNlcjpwYXNz")),
+            ("https://other.example.com/npm", None),
+            ("https://registry.example.com.evil.com", None),
+        ] {
+            assert_eq!(config.authorization_for(url).as_deref(), expected, "{url}");
+        }
+    }
+
+    // expand_env_vars
+
+    #[test]
+    fn expands_env_vars() {
+        let path = env::var("PATH").unwrap_or_default();
+        for (value, expected) in [
+            ("plain", String::from("plain")),
+            ("${PATH}", path.clone()),
+            ("a${PATH}b", format!("a{path}b")),
+            ("${PATH?}", path.clone()),
+            ("${DEPUTY_TEST_UNSET_VARIABLE}", String::new()),
+            ("${unterminated", String::from("${unterminated")),
+        ] {
+            assert_eq!(expand_env_vars(value), expected, "{value}");
+        }
+    }
+}
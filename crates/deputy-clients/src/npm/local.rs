@@ -0,0 +1,40 @@
+use std::path::{Path, PathBuf};
+
+use super::NpmClient;
+
+impl NpmClient {
+    /**
+        Finds the `package.json` of an installed package, the same way Node resolves
+        packages - looking in `node_modules` of the project directory, and all its ancestors.
+    */
+    pub async fn find_installed_package(&self, project_dir: &Path, name: &str) -> Option<PathBuf> {
+        for dir in project_dir.ancestors() {
+            let package_json = dir.join("node_modules").join(name).join("package.json");
+            if tokio::fs::try_exists(&package_json)
+                .await
+                .unwrap_or_default()
+            {
+                return Some(package_json);
+            }
+        }
+        None
+    }
+
+    /**
+        Finds a package that is referenced by a local path, such as in a `file:` dependency -
+        either a directory with a `package.json`, in which case that file is returned, or a tarball.
+    */
+    pub async fn find_local_package(&self, path: &Path) -> Option<PathBuf> {
+        let package_json = path.join("package.json");
+        if tokio::fs::try_exists(&package_json)
+            .await
+            .unwrap_or_default()
+        {
+            return Some(package_json);
+        }
+        tokio::fs::metadata(path)
+            .await
+            .is_ok_and(|meta| meta.is_file())
+            .then(|| path.to_path_buf())
+    }
+}
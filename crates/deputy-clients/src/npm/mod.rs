@@ -1,10 +1,18 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
 use tracing::error;
 
-use crate::shared::{Request, RequestResult};
+use crate::shared::{Request, RequestContext, RequestResult};
 
 mod cache;
+mod config;
 mod consts;
+mod local;
 mod requests;
+mod workspace;
 
 pub mod models;
 
@@ -13,18 +21,56 @@ use self::cache::NpmCache;
 #[derive(Debug, Clone)]
 pub struct NpmClient {
     cache: NpmCache,
+    registry_override: Arc<Mutex<Option<String>>>,
+    context: RequestContext,
 }
 
 impl NpmClient {
     #[must_use]
-    pub fn new() -> Self {
+    pub(crate) fn new(context: RequestContext) -> Self {
         Self {
             cache: NpmCache::new(),
+            registry_override: Arc::new(Mutex::new(None)),
+            context,
         }
     }
 
-    async fn request_get(&self, url: impl Into<String>) -> RequestResult<Vec<u8>> {
-        Request::get(url).send().await
+    async fn request_get(
+        &self,
+        url: impl Into<String>,
+        authorization: Option<&str>,
+    ) -> RequestResult<Vec<u8>> {
+        Request::get(url)
+            .with_header_opt("Authorization", authorization)
+            .send(&self.context)
+            .await
+    }
+
+    /**
+        Overrides how long registry data is cached for,
+        or resets it to the default if `None` is given.
+    */
+    pub fn set_cache_ttl(&self, ttl: Option<Duration>) {
+        self.cache.set_time_to_live(ttl);
+    }
+
+    /**
+        Overrides the registry url that is used instead of the public npm
+        registry, or resets it if `None` is given. Registries configured
+        in `.npmrc` files still take precedence.
+    */
+    pub fn set_registry_override(&self, url: Option<String>) {
+        *self
+            .registry_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = url;
+    }
+
+    fn registry_override(&self) -> Option<String> {
+        self.registry_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
     }
 
     fn emit_result<T>(result: &RequestResult<T>) {
@@ -36,6 +82,6 @@ impl NpmClient {
 
 impl Default for NpmClient {
     fn default() -> Self {
-        Self::new()
+        Self::new(RequestContext::default())
     }
 }
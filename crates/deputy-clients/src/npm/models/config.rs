@@ -0,0 +1,37 @@
+use super::super::consts::BASE_URL_REGISTRY;
+
+/**
+    A registry that npm packages can be fetched from.
+
+    Defaults to the public npm registry, but may also be a
+    custom or scoped registry configured in an `.npmrc` file.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpmRegistry {
+    pub url: String,
+    pub authorization: Option<String>,
+}
+
+impl NpmRegistry {
+    #[must_use]
+    pub fn npmjs() -> Self {
+        Self {
+            url: BASE_URL_REGISTRY.trim_end_matches('/').to_string(),
+            authorization: None,
+        }
+    }
+
+    /**
+        Checks if this is the public npm registry, regardless of any credentials sent to it.
+    */
+    #[must_use]
+    pub fn is_npmjs(&self) -> bool {
+        self.url == BASE_URL_REGISTRY.trim_end_matches('/')
+    }
+}
+
+impl Default for NpmRegistry {
+    fn default() -> Self {
+        Self::npmjs()
+    }
+}
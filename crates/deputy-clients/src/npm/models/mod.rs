@@ -1,3 +1,7 @@
+mod config;
 mod registry;
+mod workspace;
 
+pub use self::config::*;
 pub use self::registry::*;
+pub use self::workspace::*;
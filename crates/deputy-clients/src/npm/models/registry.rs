@@ -8,6 +8,8 @@ use deputy_versioning::Versioned;
 pub struct RegistryMetadata {
     #[serde(flatten)]
     pub current_version: RegistryMetadataVersion,
+    #[serde(default, rename = "dist-tags")]
+    pub dist_tags: HashMap<String, String>,
     #[serde(default, rename = "time")]
     pub timestamps: HashMap<String, String>,
     #[serde(default)]
@@ -109,6 +111,16 @@ impl RegistryMetadata {
     pub fn try_from_json(s: &str) -> Result<Self, serde_json::Error> {
         serde_json::from_str(s)
     }
+
+    /**
+        Gets the version that the given dist-tag, such as `latest` or `next`, points to.
+    */
+    #[must_use]
+    pub fn dist_tag_version(&self, tag: &str) -> Option<&RegistryMetadataVersion> {
+        self.dist_tags
+            .get(tag)
+            .and_then(|version| self.versions.get(version))
+    }
 }
 
 impl RegistryMetadataRepositoryVariant {
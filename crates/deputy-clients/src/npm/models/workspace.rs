@@ -0,0 +1,34 @@
+use std::{collections::HashMap, path::PathBuf};
+
+/**
+    An npm, yarn or pnpm workspace, along with all of its member packages.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpmWorkspace {
+    pub root_dir: PathBuf,
+    pub packages: HashMap<String, NpmWorkspacePackage>,
+}
+
+/**
+    A single package that is a member of an npm workspace.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpmWorkspacePackage {
+    pub name: String,
+    pub version: Option<String>,
+    pub dir: PathBuf,
+}
+
+impl NpmWorkspace {
+    #[must_use]
+    pub fn package(&self, name: &str) -> Option<&NpmWorkspacePackage> {
+        self.packages.get(name)
+    }
+}
+
+impl NpmWorkspacePackage {
+    #[must_use]
+    pub fn manifest_path(&self) -> PathBuf {
+        self.dir.join("package.json")
+    }
+}
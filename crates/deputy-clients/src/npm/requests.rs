@@ -1,22 +1,42 @@
 use tracing::debug;
 
-use super::consts::BASE_URL_REGISTRY;
-use super::models::RegistryMetadata;
+use super::models::{NpmRegistry, RegistryMetadata};
 use super::{NpmClient, RequestResult};
 
 impl NpmClient {
     #[allow(clippy::missing_errors_doc)]
     pub async fn get_registry_metadata(&self, name: &str) -> RequestResult<RegistryMetadata> {
-        let name_low = name.to_ascii_lowercase();
-        let registry_url = format!("{BASE_URL_REGISTRY}/{name_low}");
+        self.get_registry_metadata_from(&NpmRegistry::npmjs(), name)
+            .await
+    }
+
+    /**
+        Fetches package metadata from the given registry.
+
+        See [`NpmClient::get_registry`] for resolving custom and scoped registries.
+    */
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn get_registry_metadata_from(
+        &self,
+        registry: &NpmRegistry,
+        name: &str,
+    ) -> RequestResult<RegistryMetadata> {
+        // Scoped packages must have their slash encoded, for most registries
+        let name_low = name.to_ascii_lowercase().replace('/', "%2f");
+        let registry_url = format!("{}/{name_low}", registry.url);
 
         let fut = async {
-            debug!("Fetching npm package registry metadatas for '{name}'");
+            debug!(
+                "Fetching npm package registry metadatas for '{name}' from '{}'",
+                registry.url
+            );
 
             // NOTE: We make this inner scope so that
             // we can catch and emit all errors at once
             let inner = async {
-                let bytes = self.request_get(&registry_url).await?;
+                let bytes = self
+                    .request_get(&registry_url, registry.authorization.as_deref())
+                    .await?;
                 let text = String::from_utf8(bytes.clone())?;
 
                 let mut meta = RegistryMetadata::try_from_json(&text)?;
@@ -35,7 +55,7 @@ impl NpmClient {
 
         self.cache
             .registry_metadatas
-            .with_caching(registry_url.clone(), fut)
+            .with_revalidation(registry_url.clone(), fut)
             .await
     }
 }
@@ -0,0 +1,325 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use tracing::debug;
+
+use super::NpmClient;
+use super::models::{NpmWorkspace, NpmWorkspacePackage};
+
+impl NpmClient {
+    /**
+        Finds the workspace that a project in the given directory belongs to,
+        along with all of its member packages.
+
+        Workspaces are declared using the `workspaces` field of a `package.json`,
+        for npm and yarn, or in a `pnpm-workspace.yaml` file, for pnpm - the
+        closest ancestor directory that declares a workspace is used.
+    */
+    pub async fn get_workspace(&self, project_dir: &Path) -> Option<NpmWorkspace> {
+        let cache_key = project_dir.to_string_lossy().to_string();
+
+        let project_dir = project_dir.to_path_buf();
+        let fut = async {
+            for dir in project_dir.ancestors() {
+                if let Some(patterns) = read_workspace_patterns(dir).await {
+                    debug!("Reading npm workspace at '{}'", dir.display());
+                    return Some(read_workspace(dir, &patterns).await);
+                }
+            }
+            None
+        };
+
+        self.cache.workspaces.with_caching(cache_key, fut).await
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PackageFile {
+    name: Option<String>,
+    version: Option<String>,
+    workspaces: Option<PackageWorkspaces>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PackageWorkspaces {
+    Patterns(Vec<String>),
+    Config {
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+async fn read_package_file(dir: &Path) -> Option<PackageFile> {
+    let contents = tokio::fs::read_to_string(dir.join("package.json"))
+        .await
+        .ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+async fn read_workspace_patterns(dir: &Path) -> Option<Vec<String>> {
+    if let Ok(contents) = tokio::fs::read_to_string(dir.join("pnpm-workspace.yaml")).await {
+        return Some(parse_pnpm_workspace_patterns(&contents));
+    }
+    match read_package_file(dir).await?.workspaces? {
+        PackageWorkspaces::Patterns(patterns)
+        | PackageWorkspaces::Config { packages: patterns } => Some(patterns),
+    }
+}
+
+/**
+    Parses the package patterns from a `pnpm-workspace.yaml` file.
+
+    Only the `packages` list is read, which is all we need,
+    instead of pulling in a full YAML parser.
+*/
+fn parse_pnpm_workspace_patterns(contents: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) && !trimmed.starts_with('-') {
+            in_packages = trimmed.trim_end_matches(':').trim() == "packages";
+            continue;
+        }
+        if in_packages && let Some(item) = trimmed.strip_prefix('-') {
+            let item = item.split(" #").next().unwrap_or_default().trim();
+            let item = item.trim_matches(|c| c == '\'' || c == '"');
+            if !item.is_empty() {
+                patterns.push(item.to_string());
+            }
+        }
+    }
+    patterns
+}
+
+async fn read_workspace(root_dir: &Path, patterns: &[String]) -> NpmWorkspace {
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(negated) => excluded.extend(expand_pattern(root_dir, negated).await),
+            None => included.extend(expand_pattern(root_dir, pattern).await),
+        }
+    }
+
+    let mut packages = HashMap::new();
+    for dir in included {
+        if excluded.contains(&dir) {
+            continue;
+        }
+        let Some(PackageFile {
+            name: Some(name),
+            version,
+            ..
+        }) = read_package_file(&dir).await
+        else {
+            continue;
+        };
+        packages
+            .entry(name.clone())
+            .or_insert(NpmWorkspacePackage { name, version, dir });
+    }
+
+    NpmWorkspace {
+        root_dir: root_dir.to_path_buf(),
+        packages,
+    }
+}
+
+/**
+    Expands a workspace pattern, which may contain `*` and `**` wildcards,
+    into all of the directories that it matches, relative to the root.
+
+    Dependency directories (`node_modules`) and hidden directories are never matched.
+*/
+async fn expand_pattern(root_dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![root_dir.to_path_buf()];
+    for segment in pattern.trim_start_matches("./").split('/') {
+        let mut matched = Vec::new();
+        match segment {
+            "" | "." => continue,
+            "**" => {
+                let mut stack = dirs;
+                while let Some(dir) = stack.pop() {
+                    stack.extend(read_subdirs(&dir).await);
+                    matched.push(dir);
+                }
+            }
+            segment if segment.contains('*') => {
+                for dir in &dirs {
+                    matched.extend(read_subdirs(dir).await.into_iter().filter(|sub| {
+                        sub.file_name()
+                            .and_then(|name| name.to_str())
+                            .is_some_and(|name| matches_wildcard(segment, name))
+                    }));
+                }
+            }
+            segment => matched.extend(dirs.iter().map(|dir| dir.join(segment))),
+        }
+        dirs = matched;
+    }
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+async fn read_subdirs(dir: &Path) -> Vec<PathBuf> {
+    let mut subdirs = Vec::new();
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return subdirs;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name == "node_modules" || name.starts_with('.') {
+            continue;
+        }
+        if entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+            subdirs.push(entry.path());
+        }
+    }
+    subdirs
+}
+
+/**
+    Matches a single path segment against a pattern, where `*` matches any number of characters.
+*/
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return true;
+    };
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    for (index, part) in parts.iter().enumerate() {
+        if index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // parse_pnpm_workspace_patterns
+
+    #[test]
+    fn parses_pnpm_workspace_patterns() {
+        let contents = r#"
+# Workspace packages
+packages:
+  - packages/*
+  - 'apps/**'
+  - "tools/cli" # the command line tool
+  - '!**/test/**'
+
+catalog:
+  - not-a-package
+"#;
+        assert_eq!(
+            parse_pnpm_workspace_patterns(contents),
+            ["packages/*", "apps/**", "tools/cli", "!**/test/**"]
+        );
+    }
+
+    #[test]
+    fn parses_unindented_pnpm_workspace_patterns() {
+        let contents = "onlyBuiltDependencies:\n  - esbuild\npackages:\n- packages/*\n- docs\n";
+        assert_eq!(
+            parse_pnpm_workspace_patterns(contents),
+            ["packages/*", "docs"]
+        );
+    }
+
+    #[test]
+    fn parses_missing_pnpm_workspace_patterns() {
+        assert!(parse_pnpm_workspace_patterns("").is_empty());
+        assert!(parse_pnpm_workspace_patterns("catalog:\n  react: ^18\n").is_empty());
+    }
+
+    // matches_wildcard
+
+    #[test]
+    fn matches_wildcards() {
+        for (pattern, name, expected) in [
+            ("*", "anything", true),
+            ("*", "", true),
+            ("pkg-*", "pkg-a", true),
+            ("pkg-*", "pkg-", true),
+            ("pkg-*", "other", false),
+            ("*-plugin", "eslint-plugin", true),
+            ("*-plugin", "eslint-plugins", false),
+            ("a*b*c", "a-b-c", true),
+            ("a*b*c", "abc", true),
+            ("a*b*c", "acb", false),
+            ("ab*ba", "aba", false),
+            ("exact", "exact", true),
+            ("exact", "exactly", false),
+        ] {
+            assert_eq!(
+                matches_wildcard(pattern, name),
+                expected,
+                "{pattern} {name}"
+            );
+        }
+    }
+
+    // expand_pattern
+
+    #[tokio::test]
+    async fn expands_patterns() {
+        let root =
+            std::env::temp_dir().join(format!("deputy-npm-workspace-{}", std::process::id()));
+        for dir in [
+            "packages/a",
+            "packages/b",
+            "packages/node_modules/c",
+            "packages/.hidden",
+            "apps/web/nested",
+        ] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("packages/file.txt"), "").unwrap();
+
+        let expand = |pattern: &'static str| {
+            let root = root.clone();
+            async move {
+                expand_pattern(&root, pattern)
+                    .await
+                    .into_iter()
+                    .map(|dir| {
+                        dir.strip_prefix(&root)
+                            .unwrap()
+                            .to_string_lossy()
+                            .replace('\\', "/")
+                    })
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(expand("packages/*").await, ["packages/a", "packages/b"]);
+        assert_eq!(expand("./packages/a").await, ["packages/a"]);
+        assert_eq!(expand("packages/b*").await, ["packages/b"]);
+        assert_eq!(
+            expand("apps/**").await,
+            ["apps", "apps/web", "apps/web/nested"]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
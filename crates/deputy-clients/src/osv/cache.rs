@@ -0,0 +1,25 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::shared::{RequestCacheMap, RequestResult};
+
+use super::database::OsvDatabase;
+use super::models::Vulnerability;
+
+#[derive(Debug, Clone)]
+pub(super) struct OsvCache {
+    pub vulnerabilities: RequestCacheMap<RequestResult<Vec<Vulnerability>>>,
+    pub databases: RequestCacheMap<Arc<OsvDatabase>>,
+}
+
+impl OsvCache {
+    pub fn new() -> Self {
+        Self {
+            vulnerabilities: RequestCacheMap::new_mins(60, 15),
+            databases: RequestCacheMap::new_mins(10, 10),
+        }
+    }
+
+    pub fn set_time_to_live(&self, time_to_live: Option<Duration>) {
+        self.vulnerabilities.set_time_to_live(time_to_live);
+    }
+}
@@ -0,0 +1,2 @@
+pub const BASE_URL_QUERY: &str = "https://api.osv.dev/v1/query";
+pub const BASE_URL_VULNERABILITY: &str = "https://osv.dev/vulnerability/";
@@ -0,0 +1,145 @@
+use std::{collections::HashMap, path::Path};
+
+use tracing::{debug, warn};
+
+use super::models::{AdvisoryEcosystem, Vulnerability};
+
+/**
+    Advisories read from a local directory of OSV records,
+    indexed by the ecosystem and name of affected packages.
+*/
+#[derive(Debug, Default)]
+pub(super) struct OsvDatabase {
+    vulnerabilities: HashMap<(AdvisoryEcosystem, String), Vec<Vulnerability>>,
+}
+
+impl OsvDatabase {
+    /**
+        Reads all `.json` files in the given directory and its subdirectories,
+        skipping hidden directories such as `.git`, and any files that are
+        not valid OSV records - advisory databases often contain other files.
+
+        Only OSV JSON exports are supported - the Markdown advisories in the
+        main branch of the `RustSec` advisory database are skipped with a warning,
+        pointing to its `osv` branch, which has the same advisories as OSV records.
+    */
+    pub async fn read(root: &Path) -> Self {
+        let mut database = Self::default();
+        let mut unsupported = 0;
+        let mut dirs = vec![root.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+                debug!("Failed to read advisory directory '{}'", dir.display());
+                continue;
+            };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let path = entry.path();
+                let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+                match entry.file_type().await {
+                    Ok(t) if t.is_dir() && !is_hidden => dirs.push(path),
+                    Ok(t) if t.is_file() && is_json_file(&path) => {
+                        if let Some(vuln) = read_vulnerability(&path).await {
+                            database.insert(&vuln);
+                        }
+                    }
+                    Ok(t) if t.is_file() && is_rustsec_advisory(&path) => unsupported += 1,
+                    _ => {}
+                }
+            }
+        }
+        if unsupported > 0 {
+            warn!(
+                "Skipped {unsupported} RustSec advisories in '{}', which are not OSV records - \
+                use the `osv` branch of the advisory database instead",
+                root.display()
+            );
+        }
+        database
+    }
+
+    fn insert(&mut self, vuln: &Vulnerability) {
+        for affected in &vuln.affected {
+            let Some(package) = &affected.package else {
+                continue;
+            };
+            let Some(ecosystem) = AdvisoryEcosystem::from_name(&package.ecosystem) else {
+                continue;
+            };
+            let key = (ecosystem, ecosystem.normalize_package_name(&package.name));
+            let vulns = self.vulnerabilities.entry(key).or_default();
+            if !vulns.iter().any(|v| v.id == vuln.id) {
+                vulns.push(vuln.clone());
+            }
+        }
+    }
+
+    pub fn get(&self, ecosystem: AdvisoryEcosystem, name: &str) -> Vec<Vulnerability> {
+        let key = (ecosystem, ecosystem.normalize_package_name(name));
+        self.vulnerabilities.get(&key).cloned().unwrap_or_default()
+    }
+}
+
+fn is_json_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+// Advisories in the RustSec format are Markdown files with TOML front matter
+fn is_rustsec_advisory(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md")
+        && path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with("RUSTSEC-"))
+}
+
+async fn read_vulnerability(path: &Path) -> Option<Vulnerability> {
+    let contents = tokio::fs::read_to_string(path).await.ok()?;
+    match Vulnerability::try_from_json(&contents) {
+        Ok(vuln) => Some(vuln),
+        Err(e) => {
+            debug!("Skipping advisory file at '{}' - {e}", path.display());
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // is_rustsec_advisory
+
+    #[test]
+    fn detects_rustsec_advisories() {
+        for (path, expected) in [
+            ("crates/hyper/RUSTSEC-2021-0078.md", true),
+            ("crates/hyper/RUSTSEC-2021-0078.json", false),
+            ("crates/hyper/README.md", false),
+            ("README.md", false),
+        ] {
+            assert_eq!(is_rustsec_advisory(Path::new(path)), expected, "{path}");
+        }
+    }
+
+    // read
+
+    #[tokio::test]
+    async fn skips_rustsec_advisories() {
+        let dir = std::env::temp_dir().join(format!("deputy-osv-rustsec-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("crates/hyper")).unwrap();
+        std::fs::write(
+            dir.join("crates/hyper/RUSTSEC-2021-0078.md"),
+            "```toml\n[advisory]\nid = \"RUSTSEC-2021-0078\"\npackage = \"hyper\"\n```\n",
+        )
+        .unwrap();
+
+        let database = OsvDatabase::read(&dir).await;
+        assert!(
+            database
+                .get(AdvisoryEcosystem::CratesIo, "hyper")
+                .is_empty()
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,85 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use tracing::error;
+
+use crate::shared::{Request, RequestContext, RequestResult};
+
+mod cache;
+mod consts;
+mod database;
+mod requests;
+
+pub mod models;
+
+use self::cache::OsvCache;
+use self::models::AdvisorySource;
+
+/**
+    A client for security advisories in the [OSV](https://ossf.github.io/osv-schema/) format.
+
+    Advisories are fetched from the public OSV query endpoint by default, but may also be
+    read from a local directory of OSV records, or from a different endpoint.
+*/
+#[derive(Debug, Clone)]
+pub struct OsvClient {
+    cache: OsvCache,
+    source: Arc<Mutex<Option<AdvisorySource>>>,
+    context: RequestContext,
+}
+
+impl OsvClient {
+    #[must_use]
+    pub(crate) fn new(context: RequestContext) -> Self {
+        Self {
+            cache: OsvCache::new(),
+            source: Arc::new(Mutex::new(Some(AdvisorySource::default()))),
+            context,
+        }
+    }
+
+    async fn request_query(&self, url: &str, body: String) -> RequestResult<Vec<u8>> {
+        Request::post(url)
+            .with_header("Content-Type", "application/json")
+            .with_body(body)
+            .send(&self.context)
+            .await
+    }
+
+    /**
+        Overrides how long advisories are cached for,
+        or resets it to the default if `None` is given.
+    */
+    pub fn set_cache_ttl(&self, ttl: Option<Duration>) {
+        self.cache.set_time_to_live(ttl);
+    }
+
+    /**
+        Sets where advisories should be read from, or
+        disables advisories entirely if `None` is given.
+    */
+    pub fn set_source(&self, source: Option<AdvisorySource>) {
+        *self.source.lock().unwrap_or_else(PoisonError::into_inner) = source;
+    }
+
+    fn source(&self) -> Option<AdvisorySource> {
+        self.source
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn emit_result<T>(result: &RequestResult<T>) {
+        if let Err(e) = &result {
+            error!("OSV error: {e}");
+        }
+    }
+}
+
+impl Default for OsvClient {
+    fn default() -> Self {
+        Self::new(RequestContext::default())
+    }
+}
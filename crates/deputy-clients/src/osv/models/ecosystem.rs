@@ -0,0 +1,77 @@
+use std::{cmp::Ordering, fmt};
+
+use deputy_versioning::{PepVersion, Version};
+
+use crate::pypi::util::normalize_name;
+
+/**
+    An ecosystem that advisories may be checked for.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AdvisoryEcosystem {
+    CratesIo,
+    Npm,
+    PyPi,
+    Go,
+}
+
+impl AdvisoryEcosystem {
+    /**
+        The name of the ecosystem, as used in OSV records.
+    */
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::CratesIo => "crates.io",
+            Self::Npm => "npm",
+            Self::PyPi => "PyPI",
+            Self::Go => "Go",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::CratesIo, Self::Npm, Self::PyPi, Self::Go]
+            .into_iter()
+            .find(|ecosystem| ecosystem.name() == name)
+    }
+
+    /**
+        Normalizes a package name so that it can be compared
+        with other names, following the rules of the ecosystem.
+    */
+    #[must_use]
+    pub fn normalize_package_name(self, name: &str) -> String {
+        match self {
+            Self::PyPi => normalize_name(name),
+            Self::CratesIo | Self::Npm | Self::Go => name.to_string(),
+        }
+    }
+
+    /**
+        Compares two versions following the versioning scheme of the ecosystem.
+
+        Returns `None` if either version could not be parsed.
+    */
+    #[must_use]
+    pub fn compare_versions(self, a: &str, b: &str) -> Option<Ordering> {
+        match self {
+            Self::PyPi => {
+                let a = a.parse::<PepVersion>().ok()?;
+                let b = b.parse::<PepVersion>().ok()?;
+                Some(a.cmp(&b))
+            }
+            Self::CratesIo | Self::Npm | Self::Go => {
+                let a = Version::parse(a.trim_start_matches('v')).ok()?;
+                let b = Version::parse(b.trim_start_matches('v')).ok()?;
+                Some(a.cmp(&b))
+            }
+        }
+    }
+}
+
+impl fmt::Display for AdvisoryEcosystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
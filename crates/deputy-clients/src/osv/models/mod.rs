@@ -0,0 +1,7 @@
+mod ecosystem;
+mod source;
+mod vulnerability;
+
+pub use self::ecosystem::*;
+pub use self::source::*;
+pub use self::vulnerability::*;
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+use super::super::consts::BASE_URL_QUERY;
+
+/**
+    Where advisories should be read from.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdvisorySource {
+    /// An OSV query endpoint, such as the public one at `api.osv.dev`
+    Endpoint(String),
+    /// A local directory containing OSV records as `.json` files, in any subdirectory
+    Directory(PathBuf),
+}
+
+impl Default for AdvisorySource {
+    fn default() -> Self {
+        Self::Endpoint(BASE_URL_QUERY.to_string())
+    }
+}
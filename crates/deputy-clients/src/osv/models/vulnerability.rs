@@ -0,0 +1,393 @@
+use std::cmp::Ordering;
+
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use super::super::consts::BASE_URL_VULNERABILITY;
+use super::AdvisoryEcosystem;
+
+/**
+    A single advisory, in the OSV format.
+
+    See <https://ossf.github.io/osv-schema/> for the full schema,
+    of which only the parts that we need are deserialized here.
+*/
+#[derive(Debug, Clone, Deserialize)]
+pub struct Vulnerability {
+    pub id: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub details: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub withdrawn: Option<String>,
+    #[serde(default)]
+    pub affected: Vec<VulnerabilityAffected>,
+    #[serde(default)]
+    pub database_specific: Option<JsonValue>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VulnerabilityAffected {
+    #[serde(default)]
+    pub package: Option<VulnerabilityPackage>,
+    #[serde(default)]
+    pub ranges: Vec<VulnerabilityRange>,
+    #[serde(default)]
+    pub versions: Vec<String>,
+    #[serde(default)]
+    pub database_specific: Option<JsonValue>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VulnerabilityPackage {
+    pub ecosystem: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VulnerabilityRange {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub events: Vec<VulnerabilityEvent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct VulnerabilityEvent {
+    #[serde(default)]
+    pub introduced: Option<String>,
+    #[serde(default)]
+    pub fixed: Option<String>,
+    #[serde(default)]
+    pub last_affected: Option<String>,
+}
+
+impl Vulnerability {
+    #[allow(clippy::missing_errors_doc)]
+    pub fn try_from_json(s: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    /**
+        A link to the advisory on `osv.dev`.
+    */
+    #[must_use]
+    pub fn url(&self) -> String {
+        format!("{BASE_URL_VULNERABILITY}{}", self.id)
+    }
+
+    /**
+        Checks if this advisory is severe, meaning that
+        it has been given a `HIGH` or `CRITICAL` severity.
+    */
+    #[must_use]
+    pub fn is_severe(&self) -> bool {
+        let severity = self
+            .database_specific
+            .as_ref()
+            .and_then(|d| d.get("severity"))
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default();
+        severity.eq_ignore_ascii_case("high") || severity.eq_ignore_ascii_case("critical")
+    }
+
+    /**
+        The kind of informational advisory this is, such as `unmaintained`
+        or `unsound` for `RustSec` advisories, if this is not a vulnerability.
+    */
+    #[must_use]
+    pub fn informational(&self) -> Option<&str> {
+        self.affected.iter().find_map(|affected| {
+            affected
+                .database_specific
+                .as_ref()
+                .and_then(|d| d.get("informational"))
+                .and_then(JsonValue::as_str)
+        })
+    }
+
+    /**
+        Checks if this advisory applies to the given package, in any version.
+    */
+    #[must_use]
+    pub fn is_for_package(&self, ecosystem: AdvisoryEcosystem, name: &str) -> bool {
+        self.affected_for(ecosystem, name).next().is_some()
+    }
+
+    /**
+        Checks if the given version of a package is affected by this advisory.
+    */
+    #[must_use]
+    pub fn affects(&self, ecosystem: AdvisoryEcosystem, name: &str, version: &str) -> bool {
+        self.affected_for(ecosystem, name)
+            .any(|affected| affected.affects(ecosystem, version))
+    }
+
+    /**
+        Finds the first version of a package, newer than the given
+        version, that has been patched and is no longer affected.
+    */
+    #[must_use]
+    pub fn first_patched_version(
+        &self,
+        ecosystem: AdvisoryEcosystem,
+        name: &str,
+        version: &str,
+    ) -> Option<String> {
+        self.affected_for(ecosystem, name)
+            .flat_map(|affected| &affected.ranges)
+            .filter(|range| range.affects(ecosystem, version))
+            .flat_map(|range| &range.events)
+            .filter_map(|event| event.fixed.as_deref())
+            .filter(|fixed| ecosystem.compare_versions(fixed, version) == Some(Ordering::Greater))
+            .min_by(|a, b| ecosystem.compare_versions(a, b).unwrap_or(Ordering::Equal))
+            .map(ToString::to_string)
+    }
+
+    fn affected_for(
+        &self,
+        ecosystem: AdvisoryEcosystem,
+        name: &str,
+    ) -> impl Iterator<Item = &VulnerabilityAffected> {
+        let name = ecosystem.normalize_package_name(name);
+        self.affected.iter().filter(move |affected| {
+            affected.package.as_ref().is_some_and(|package| {
+                package.ecosystem == ecosystem.name()
+                    && ecosystem.normalize_package_name(&package.name) == name
+            })
+        })
+    }
+}
+
+impl VulnerabilityAffected {
+    fn affects(&self, ecosystem: AdvisoryEcosystem, version: &str) -> bool {
+        self.versions.iter().any(|v| {
+            v == version || ecosystem.compare_versions(v, version) == Some(Ordering::Equal)
+        }) || self
+            .ranges
+            .iter()
+            .any(|range| range.affects(ecosystem, version))
+    }
+}
+
+impl VulnerabilityRange {
+    /**
+        Evaluates the events of this range in version order, as described
+        in the OSV schema - ranges using git commits are not supported.
+    */
+    fn affects(&self, ecosystem: AdvisoryEcosystem, version: &str) -> bool {
+        if !matches!(self.kind.as_str(), "SEMVER" | "ECOSYSTEM") {
+            return false;
+        }
+
+        let compare = |a: &str, b: &str| match (a, b) {
+            ("0", "0") => Some(Ordering::Equal),
+            ("0", _) => Some(Ordering::Less),
+            (_, "0") => Some(Ordering::Greater),
+            _ => ecosystem.compare_versions(a, b),
+        };
+
+        let mut events = self
+            .events
+            .iter()
+            .filter_map(|event| {
+                let v = event
+                    .introduced
+                    .as_deref()
+                    .or(event.fixed.as_deref())
+                    .or(event.last_affected.as_deref())?;
+                compare(v, v).map(|_| (event, v))
+            })
+            .collect::<Vec<_>>();
+        events.sort_by(|(_, a), (_, b)| compare(a, b).unwrap_or(Ordering::Equal));
+
+        let mut affected = false;
+        for (event, event_version) in events {
+            let Some(ordering) = compare(version, event_version) else {
+                return false;
+            };
+            if event.introduced.is_some() {
+                if ordering.is_ge() {
+                    affected = true;
+                }
+            } else if event.fixed.is_some() {
+                if ordering.is_ge() {
+                    affected = false;
+                }
+            } else if ordering.is_gt() {
+                affected = false;
+            }
+        }
+        affected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ECOSYSTEM: AdvisoryEcosystem = AdvisoryEcosystem::CratesIo;
+
+    fn vulnerability(ranges: &str, versions: &str) -> Vulnerability {
+        Vulnerability::try_from_json(&format!(
+            r#"{{
+                "id": "RUSTSEC-0000-0000",
+                "affected": [{{
+                    "package": {{ "ecosystem": "crates.io", "name": "hyper" }},
+                    "ranges": {ranges},
+                    "versions": {versions}
+                }}]
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    fn semver(events: &str) -> Vulnerability {
+        vulnerability(
+            &format!(r#"[{{ "type": "SEMVER", "events": {events} }}]"#),
+            "[]",
+        )
+    }
+
+    // affects
+
+    #[test]
+    fn affects_versions_between_introduced_and_fixed() {
+        let vuln = semver(r#"[{ "introduced": "0.14.0" }, { "fixed": "0.14.10" }]"#);
+        for (version, expected) in [
+            ("0.13.9", false),
+            ("0.14.0", true),
+            ("0.14.9", true),
+            ("0.14.10", false),
+            ("1.0.0", false),
+        ] {
+            assert_eq!(
+                vuln.affects(ECOSYSTEM, "hyper", version),
+                expected,
+                "{version}"
+            );
+        }
+    }
+
+    #[test]
+    fn affects_all_versions_introduced_at_zero() {
+        let vuln = semver(r#"[{ "introduced": "0" }, { "fixed": "1.2.0" }]"#);
+        assert!(vuln.affects(ECOSYSTEM, "hyper", "0.0.1"));
+        assert!(vuln.affects(ECOSYSTEM, "hyper", "1.1.9"));
+        assert!(!vuln.affects(ECOSYSTEM, "hyper", "1.2.0"));
+    }
+
+    #[test]
+    fn affects_versions_up_to_last_affected() {
+        let vuln = semver(r#"[{ "introduced": "1.0.0" }, { "last_affected": "1.4.0" }]"#);
+        assert!(vuln.affects(ECOSYSTEM, "hyper", "1.4.0"));
+        assert!(!vuln.affects(ECOSYSTEM, "hyper", "1.4.1"));
+    }
+
+    #[test]
+    fn affects_multiple_introduced_ranges() {
+        let vuln = semver(
+            r#"[
+                { "introduced": "0.1.0" }, { "fixed": "0.2.0" },
+                { "introduced": "1.0.0" }, { "fixed": "1.0.5" }
+            ]"#,
+        );
+        for (version, expected) in [
+            ("0.1.5", true),
+            ("0.3.0", false),
+            ("1.0.4", true),
+            ("1.0.5", false),
+        ] {
+            assert_eq!(
+                vuln.affects(ECOSYSTEM, "hyper", version),
+                expected,
+                "{version}"
+            );
+        }
+    }
+
+    #[test]
+    fn affects_listed_versions() {
+        let vuln = vulnerability("[]", r#"["0.14.3"]"#);
+        assert!(vuln.affects(ECOSYSTEM, "hyper", "0.14.3"));
+        assert!(!vuln.affects(ECOSYSTEM, "hyper", "0.14.4"));
+    }
+
+    #[test]
+    fn never_affects_git_ranges() {
+        let vuln = vulnerability(
+            r#"[{ "type": "GIT", "events": [{ "introduced": "0" }] }]"#,
+            "[]",
+        );
+        assert!(!vuln.affects(ECOSYSTEM, "hyper", "1.0.0"));
+    }
+
+    #[test]
+    fn never_affects_other_packages() {
+        let vuln = semver(r#"[{ "introduced": "0" }]"#);
+        assert!(vuln.affects(ECOSYSTEM, "hyper", "1.0.0"));
+        assert!(!vuln.affects(ECOSYSTEM, "hyper-util", "1.0.0"));
+        assert!(!vuln.affects(AdvisoryEcosystem::Npm, "hyper", "1.0.0"));
+    }
+
+    #[test]
+    fn never_affects_unparseable_versions() {
+        let vuln = semver(r#"[{ "introduced": "0" }, { "fixed": "1.0.0" }]"#);
+        assert!(!vuln.affects(ECOSYSTEM, "hyper", "not a version"));
+    }
+
+    // first_patched_version
+
+    #[test]
+    fn first_patched_version_of_affected_range() {
+        let vuln = semver(
+            r#"[
+                { "introduced": "0.1.0" }, { "fixed": "0.2.0" },
+                { "introduced": "1.0.0" }, { "fixed": "1.0.5" }
+            ]"#,
+        );
+        assert_eq!(
+            vuln.first_patched_version(ECOSYSTEM, "hyper", "0.1.5")
+                .as_deref(),
+            Some("0.2.0")
+        );
+        assert_eq!(
+            vuln.first_patched_version(ECOSYSTEM, "hyper", "1.0.1")
+                .as_deref(),
+            Some("1.0.5")
+        );
+    }
+
+    #[test]
+    fn first_patched_version_of_separate_ranges() {
+        let vuln = vulnerability(
+            r#"[
+                { "type": "SEMVER", "events": [{ "introduced": "0" }, { "fixed": "2.1.0" }] },
+                { "type": "SEMVER", "events": [{ "introduced": "0" }, { "fixed": "1.8.3" }] }
+            ]"#,
+            "[]",
+        );
+        assert_eq!(
+            vuln.first_patched_version(ECOSYSTEM, "hyper", "1.5.0")
+                .as_deref(),
+            Some("1.8.3")
+        );
+    }
+
+    #[test]
+    fn no_patched_version_without_fix() {
+        let vuln = semver(r#"[{ "introduced": "1.0.0" }, { "last_affected": "1.4.0" }]"#);
+        assert_eq!(
+            vuln.first_patched_version(ECOSYSTEM, "hyper", "1.2.0"),
+            None
+        );
+        let vuln = semver(r#"[{ "introduced": "0" }]"#);
+        assert_eq!(
+            vuln.first_patched_version(ECOSYSTEM, "hyper", "1.2.0"),
+            None
+        );
+    }
+}
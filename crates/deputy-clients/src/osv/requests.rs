@@ -0,0 +1,111 @@
+use std::{path::Path, sync::Arc};
+
+use serde::Deserialize;
+use serde_json::json;
+use tracing::debug;
+
+use super::database::OsvDatabase;
+use super::models::{AdvisoryEcosystem, AdvisorySource, Vulnerability};
+use super::{OsvClient, RequestResult};
+
+#[derive(Debug, Default, Deserialize)]
+struct QueryResponse {
+    #[serde(default)]
+    vulns: Vec<Vulnerability>,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+impl OsvClient {
+    /**
+        Fetches all advisories for the package with the given name,
+        regardless of which of its versions are affected.
+
+        Withdrawn advisories are never returned, and if
+        advisories have been disabled, this returns nothing.
+    */
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn get_vulnerabilities(
+        &self,
+        ecosystem: AdvisoryEcosystem,
+        name: &str,
+    ) -> RequestResult<Vec<Vulnerability>> {
+        let vulns = match self.source() {
+            None => return Ok(Vec::new()),
+            Some(AdvisorySource::Directory(dir)) => {
+                self.get_database(&dir).await.get(ecosystem, name)
+            }
+            Some(AdvisorySource::Endpoint(url)) => {
+                self.query_vulnerabilities(&url, ecosystem, name).await?
+            }
+        };
+        Ok(vulns
+            .into_iter()
+            .filter(|vuln| vuln.withdrawn.is_none() && vuln.is_for_package(ecosystem, name))
+            .collect())
+    }
+
+    async fn get_database(&self, dir: &Path) -> Arc<OsvDatabase> {
+        let cache_key = dir.to_string_lossy().to_string();
+
+        let dir = dir.to_path_buf();
+        let fut = async {
+            debug!("Reading advisory database at '{}'", dir.display());
+            Arc::new(OsvDatabase::read(&dir).await)
+        };
+
+        self.cache.databases.with_caching(cache_key, fut).await
+    }
+
+    async fn query_vulnerabilities(
+        &self,
+        url: &str,
+        ecosystem: AdvisoryEcosystem,
+        name: &str,
+    ) -> RequestResult<Vec<Vulnerability>> {
+        let cache_key = format!("{url}|{ecosystem}|{name}");
+
+        let fut = async {
+            debug!("Fetching {ecosystem} advisories for '{name}' from '{url}'");
+
+            // NOTE: We make this inner scope so that
+            // we can catch and emit all errors at once
+            let inner = async {
+                let mut vulns = Vec::new();
+                let mut page_token = None;
+                loop {
+                    let mut body = json!({
+                        "package": {
+                            "name": name,
+                            "ecosystem": ecosystem.name(),
+                        }
+                    });
+                    if let Some(token) = page_token.take() {
+                        body["page_token"] = json!(token);
+                    }
+
+                    let bytes = self.request_query(url, body.to_string()).await?;
+                    let text = String::from_utf8(bytes)?;
+                    let response = serde_json::from_str::<QueryResponse>(&text)?;
+
+                    vulns.extend(response.vulns);
+                    match response.next_page_token {
+                        Some(token) if !token.is_empty() => page_token = Some(token),
+                        _ => break,
+                    }
+                }
+                Ok(vulns)
+            }
+            .await;
+
+            OsvClient::emit_result(&inner);
+
+            inner
+        };
+
+        self.cache
+            .vulnerabilities
+            .with_revalidation(cache_key, fut)
+            .await
+    }
+}
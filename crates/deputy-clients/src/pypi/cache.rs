@@ -1,11 +1,15 @@
+use std::time::Duration;
+
 use crate::shared::{RequestCacheMap, RequestResult};
 
+use super::config::PyPiConfig;
 use super::models::{RegistryMetadata, SimpleMetadata};
 
 #[derive(Debug, Clone)]
 pub(super) struct PyPiCache {
     pub simple_metadatas: RequestCacheMap<RequestResult<SimpleMetadata>>,
     pub registry_metadatas: RequestCacheMap<RequestResult<RegistryMetadata>>,
+    pub configs: RequestCacheMap<PyPiConfig>,
 }
 
 impl PyPiCache {
@@ -13,6 +17,19 @@ impl PyPiCache {
         Self {
             simple_metadatas: RequestCacheMap::new_mins(60, 15),
             registry_metadatas: RequestCacheMap::new_mins(240, 120),
+            configs: RequestCacheMap::new_secs(30, 30),
         }
     }
+
+    /**
+        Invalidates all data fetched from the `PyPI` JSON API, but not from the simple index.
+    */
+    pub fn invalidate_api(&self) {
+        self.registry_metadatas.invalidate();
+    }
+
+    pub fn set_time_to_live(&self, time_to_live: Option<Duration>) {
+        self.simple_metadatas.set_time_to_live(time_to_live);
+        self.registry_metadatas.set_time_to_live(time_to_live);
+    }
 }
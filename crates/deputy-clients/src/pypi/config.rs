@@ -0,0 +1,536 @@
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, de::DeserializeOwned};
+use tracing::debug;
+
+use super::PyPiClient;
+use super::models::PyPiIndex;
+
+impl PyPiClient {
+    /**
+        Resolves the package indexes that packages should be fetched
+        from, for a `pyproject.toml` in the given directory.
+
+        Indexes configured for the project using uv (`[[tool.uv.index]]`
+        or `uv.toml`) or Poetry (`[[tool.poetry.source]]`) are preferred,
+        since neither of those tools read any pip configuration. Otherwise,
+        indexes are read from `PIP_INDEX_URL` and `PIP_EXTRA_INDEX_URL`,
+        or from `pip.conf` files, the same way pip does.
+
+        Indexes are returned in priority order, and there is always at least one.
+    */
+    pub async fn get_indexes(&self, project_dir: &Path) -> Vec<PyPiIndex> {
+        let cache_key = project_dir.to_string_lossy().to_string();
+
+        let project_dir = project_dir.to_path_buf();
+        let fut = async {
+            debug!(
+                "Reading python index config for '{}'",
+                project_dir.display()
+            );
+            PyPiConfig::read(&project_dir).await
+        };
+
+        let config = self.cache.configs.with_caching(cache_key, fut).await;
+        match self.registry_override() {
+            Some(url) => config
+                .indexes
+                .into_iter()
+                .map(|index| {
+                    if index.is_pypi() {
+                        PyPiIndex::new(&url)
+                    } else {
+                        index
+                    }
+                })
+                .collect(),
+            None => config.indexes,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PyProjectFile {
+    #[serde(default)]
+    tool: PyProjectTools,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PyProjectTools {
+    uv: Option<UvConfig>,
+    poetry: Option<PoetryConfig>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct UvConfig {
+    #[serde(default)]
+    index: Vec<UvIndex>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct UvIndex {
+    url: Option<String>,
+    #[serde(default)]
+    default: bool,
+    #[serde(default)]
+    explicit: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PoetryConfig {
+    #[serde(default)]
+    source: Vec<PoetrySource>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PoetrySource {
+    #[serde(default)]
+    name: String,
+    url: Option<String>,
+    priority: Option<String>,
+}
+
+/**
+    The package indexes that apply to a project, in priority order.
+*/
+#[derive(Debug, Clone, Default)]
+pub(super) struct PyPiConfig {
+    indexes: Vec<PyPiIndex>,
+}
+
+impl PyPiConfig {
+    async fn read(project_dir: &Path) -> Self {
+        let indexes = match read_project_indexes(project_dir).await {
+            Some(indexes) => indexes,
+            None => read_pip_indexes().await,
+        };
+        Self {
+            indexes: dedup_indexes(indexes),
+        }
+    }
+}
+
+impl UvConfig {
+    /**
+        Indexes are used in the order they are defined, with the default
+        index - `PyPI`, unless replaced - always having the lowest priority.
+
+        Explicit indexes are only used for packages that are pinned to them,
+        and are skipped here since we do not know which packages those are.
+    */
+    fn indexes(&self) -> Vec<PyPiIndex> {
+        let mut indexes = Vec::new();
+        let mut default = None;
+        for index in self.index.iter().filter(|index| !index.explicit) {
+            let Some(url) = &index.url else {
+                continue;
+            };
+            if index.default {
+                default.get_or_insert_with(|| PyPiIndex::new(url));
+            } else {
+                indexes.push(PyPiIndex::new(url));
+            }
+        }
+        indexes.push(default.unwrap_or_else(PyPiIndex::pypi));
+        indexes
+    }
+}
+
+impl PoetryConfig {
+    /**
+        Primary sources are used first, followed by supplemental sources.
+
+        `PyPI` is only used implicitly when there are no primary sources,
+        but may also be added explicitly as a source without any url.
+    */
+    fn indexes(&self) -> Vec<PyPiIndex> {
+        let mut primary = Vec::new();
+        let mut supplemental = Vec::new();
+        for source in &self.source {
+            let index = match &source.url {
+                Some(url) => PyPiIndex::new(url),
+                None if source.name.eq_ignore_ascii_case("pypi") => PyPiIndex::pypi(),
+                None => continue,
+            };
+            match source.priority.as_deref().unwrap_or("primary") {
+                "explicit" => {}
+                "supplemental" | "secondary" => supplemental.push(index),
+                "default" => primary.insert(0, index),
+                _ => primary.push(index),
+            }
+        }
+        if primary.is_empty() && !supplemental.iter().any(PyPiIndex::is_pypi) {
+            primary.push(PyPiIndex::pypi());
+        }
+        primary.extend(supplemental);
+        primary
+    }
+}
+
+async fn read_project_indexes(project_dir: &Path) -> Option<Vec<PyPiIndex>> {
+    let pyproject = read_toml_file::<PyProjectFile>(&project_dir.join("pyproject.toml"))
+        .await
+        .unwrap_or_default();
+
+    // Settings in uv.toml take precedence over those in pyproject.toml
+    let uv = read_toml_file::<UvConfig>(&project_dir.join("uv.toml"))
+        .await
+        .or(pyproject.tool.uv);
+    if let Some(uv) = uv
+        && !uv.index.is_empty()
+    {
+        return Some(uv.indexes());
+    }
+
+    if let Some(poetry) = pyproject.tool.poetry
+        && !poetry.source.is_empty()
+    {
+        return Some(poetry.indexes());
+    }
+
+    None
+}
+
+async fn read_pip_indexes() -> Vec<PyPiIndex> {
+    let mut values = HashMap::new();
+    for (var, key) in [
+        ("PIP_INDEX_URL", "index-url"),
+        ("PIP_EXTRA_INDEX_URL", "extra-index-url"),
+    ] {
+        if let Some(value) = env_var(var) {
+            values.insert(key.to_string(), value);
+        }
+    }
+
+    // Config files with higher priority are read first, and
+    // we only fill in values that have not yet been set
+    for path in pip_config_paths() {
+        if let Ok(contents) = tokio::fs::read_to_string(&path).await {
+            merge_pip_config(&mut values, &contents);
+        }
+    }
+
+    let mut indexes = vec![
+        values
+            .get("index-url")
+            .map_or_else(PyPiIndex::pypi, PyPiIndex::new),
+    ];
+    if let Some(extra) = values.get("extra-index-url") {
+        indexes.extend(extra.split_whitespace().map(PyPiIndex::new));
+    }
+    indexes
+}
+
+/**
+    Merges index settings from the contents of a `pip.conf` file into the
+    given values. Settings for the `install` command take precedence over
+    `global` settings, and values may continue on indented lines.
+*/
+fn merge_pip_config(values: &mut HashMap<String, String>, contents: &str) {
+    let mut sections = HashMap::<(String, String), String>::new();
+    let mut section = String::new();
+    let mut last_key = None;
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with(['#', ';']) {
+            continue;
+        }
+        if line.starts_with(char::is_whitespace)
+            && let Some(key) = &last_key
+            && let Some(value) = sections.get_mut(key)
+        {
+            value.push(' ');
+            value.push_str(trimmed);
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            section = name.trim().to_ascii_lowercase();
+            last_key = None;
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once(['=', ':']) else {
+            continue;
+        };
+        let key = (
+            section.clone(),
+            key.trim().to_ascii_lowercase().replace('_', "-"),
+        );
+        sections.insert(key.clone(), value.trim().to_string());
+        last_key = Some(key);
+    }
+
+    for key in ["index-url", "extra-index-url"] {
+        let value = ["install", "global"]
+            .into_iter()
+            .find_map(|section| sections.get(&(section.to_string(), key.to_string())));
+        if let Some(value) = value {
+            values
+                .entry(key.to_string())
+                .or_insert_with(|| value.clone());
+        }
+    }
+}
+
+fn pip_config_paths() -> Vec<PathBuf> {
+    let file_name = if cfg!(windows) { "pip.ini" } else { "pip.conf" };
+
+    let mut paths = Vec::new();
+    if let Some(path) = env_var("PIP_CONFIG_FILE") {
+        paths.push(PathBuf::from(path));
+    }
+    if let Some(venv) = env_var("VIRTUAL_ENV") {
+        paths.push(Path::new(&venv).join(file_name));
+    }
+    if let Some(config) = dirs::config_dir() {
+        paths.push(config.join("pip").join(file_name));
+    }
+    if let Some(home) = dirs::home_dir() {
+        paths.push(home.join(".config").join("pip").join(file_name));
+        paths.push(home.join(".pip").join(file_name));
+    }
+    if cfg!(unix) {
+        paths.push(PathBuf::from("/etc/pip.conf"));
+        paths.push(PathBuf::from("/etc/xdg/pip/pip.conf"));
+    }
+
+    paths.dedup();
+    paths
+}
+
+async fn read_toml_file<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let contents = tokio::fs::read_to_string(path).await.ok()?;
+    match toml::from_str(&contents) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            debug!(
+                "Failed to parse python config at '{}' - {e}",
+                path.display()
+            );
+            None
+        }
+    }
+}
+
+fn dedup_indexes(indexes: Vec<PyPiIndex>) -> Vec<PyPiIndex> {
+    let mut unique = Vec::with_capacity(indexes.len());
+    for index in indexes {
+        if !unique.contains(&index) {
+            unique.push(index);
+        }
+    }
+    unique
+}
+
+fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(indexes: &[PyPiIndex]) -> Vec<&str> {
+        indexes.iter().map(|index| index.url.as_str()).collect()
+    }
+
+    // UvConfig::indexes
+
+    #[test]
+    fn orders_uv_indexes() {
+        let uv = toml::from_str::<UvConfig>(
+            r#"
+            [[index]]
+            url = "https://first.example.com/simple/"
+
+            [[index]]
+            url = "https://explicit.example.com/simple"
+            explicit = true
+
+            [[index]]
+            url = "https://second.example.com/simple"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            urls(&uv.indexes()),
+            [
+                "https://first.example.com/simple",
+                "https://second.example.com/simple",
+                PyPiIndex::pypi().url.as_str(),
+            ]
+        );
+    }
+
+    #[test]
+    fn replaces_default_uv_index() {
+        let uv = toml::from_str::<UvConfig>(
+            r#"
+            [[index]]
+            url = "https://mirror.example.com/simple"
+            default = true
+
+            [[index]]
+            url = "https://extra.example.com/simple"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            urls(&uv.indexes()),
+            [
+                "https://extra.example.com/simple",
+                "https://mirror.example.com/simple",
+            ]
+        );
+    }
+
+    // PoetryConfig::indexes
+
+    #[test]
+    fn orders_poetry_sources() {
+        for (sources, expected) in [
+            (
+                r#"
+                [[source]]
+                name = "extra"
+                url = "https://extra.example.com/simple"
+                priority = "supplemental"
+                "#,
+                vec![
+                    PyPiIndex::pypi().url,
+                    String::from("https://extra.example.com/simple"),
+                ],
+            ),
+            (
+                r#"
+                [[source]]
+                name = "primary"
+                url = "https://primary.example.com/simple"
+
+                [[source]]
+                name = "default"
+                url = "https://default.example.com/simple"
+                priority = "default"
+
+                [[source]]
+                name = "explicit"
+                url = "https://explicit.example.com/simple"
+                priority = "explicit"
+                "#,
+                vec![
+                    String::from("https://default.example.com/simple"),
+                    String::from("https://primary.example.com/simple"),
+                ],
+            ),
+            (
+                r#"
+                [[source]]
+                name = "private"
+                url = "https://private.example.com/simple"
+
+                [[source]]
+                name = "PyPI"
+                priority = "supplemental"
+                "#,
+                vec![
+                    String::from("https://private.example.com/simple"),
+                    PyPiIndex::pypi().url,
+                ],
+            ),
+        ] {
+            let poetry = toml::from_str::<PoetryConfig>(sources).unwrap();
+            assert_eq!(urls(&poetry.indexes()), expected, "{sources}");
+        }
+    }
+
+    // merge_pip_config
+
+    #[test]
+    fn merges_pip_config() {
+        let mut values = HashMap::new();
+        merge_pip_config(
+            &mut values,
+            concat!(
+                "# comment\n",
+                "[global]\n",
+                "index_url = https://global.example.com/simple\n",
+                "extra-index-url =\n",
+                "    https://first.example.com/simple\n",
+                "    https://second.example.com/simple\n",
+                "\n",
+                "[install]\n",
+                "index-url: https://install.example.com/simple\n",
+            ),
+        );
+        merge_pip_config(
+            &mut values,
+            "[global]\nindex-url = https://lower.example.com/simple\n",
+        );
+        assert_eq!(
+            values.get("index-url").map(String::as_str),
+            Some("https://install.example.com/simple")
+        );
+        assert_eq!(
+            values
+                .get("extra-index-url")
+                .map(|v| v.split_whitespace().collect::<Vec<_>>()),
+            Some(vec![
+                "https://first.example.com/simple",
+                "https://second.example.com/simple",
+            ])
+        );
+    }
+
+    // read_project_indexes
+
+    #[tokio::test]
+    async fn prefers_uv_toml_over_pyproject() {
+        let dir = std::env::temp_dir().join(format!("deputy-pypi-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(read_project_indexes(&dir).await, None);
+
+        std::fs::write(
+            dir.join("pyproject.toml"),
+            "[[tool.uv.index]]\nurl = \"https://pyproject.example.com/simple\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            urls(&read_project_indexes(&dir).await.unwrap())[0],
+            "https://pyproject.example.com/simple"
+        );
+
+        std::fs::write(
+            dir.join("uv.toml"),
+            "[[index]]\nurl = \"https://uv.example.com/simple\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            urls(&read_project_indexes(&dir).await.unwrap())[0],
+            "https://uv.example.com/simple"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // dedup_indexes
+
+    #[test]
+    fn dedups_indexes_keeping_order() {
+        let indexes = dedup_indexes(vec![
+            PyPiIndex::new("https://b.example.com/simple/"),
+            PyPiIndex::pypi(),
+            PyPiIndex::new("https://b.example.com/simple"),
+        ]);
+        assert_eq!(
+            urls(&indexes),
+            [
+                "https://b.example.com/simple",
+                PyPiIndex::pypi().url.as_str()
+            ]
+        );
+    }
+}
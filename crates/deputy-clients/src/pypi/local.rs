@@ -0,0 +1,79 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use super::PyPiClient;
+use super::util::normalize_name;
+
+const VIRTUAL_ENV_DIRS: [&str; 3] = [".venv", "venv", "env"];
+
+impl PyPiClient {
+    /**
+        Finds the `METADATA` file of an installed distribution, inside of the
+        `.dist-info` directory in the `site-packages` of a virtual environment.
+
+        Uses the active virtual environment from `VIRTUAL_ENV` if there is one,
+        otherwise looks for a `.venv`, `venv`, or `env` directory in the
+        project directory, and all its ancestors.
+    */
+    pub async fn find_installed_distribution(
+        &self,
+        project_dir: &Path,
+        name: &str,
+    ) -> Option<PathBuf> {
+        let name = normalize_name(name);
+        for site_packages in site_packages_dirs(project_dir).await {
+            let Ok(mut entries) = tokio::fs::read_dir(&site_packages).await else {
+                continue;
+            };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let file_name = entry.file_name();
+                let Some((dist_name, _version)) = file_name
+                    .to_str()
+                    .and_then(|s| s.strip_suffix(".dist-info"))
+                    .and_then(|s| s.rsplit_once('-'))
+                else {
+                    continue;
+                };
+                if normalize_name(dist_name) == name {
+                    return Some(entry.path().join("METADATA"));
+                }
+            }
+        }
+        None
+    }
+}
+
+async fn site_packages_dirs(project_dir: &Path) -> Vec<PathBuf> {
+    let mut envs = Vec::new();
+    if let Some(venv) = env::var_os("VIRTUAL_ENV").filter(|venv| !venv.is_empty()) {
+        envs.push(PathBuf::from(venv));
+    }
+    for dir in project_dir.ancestors() {
+        for name in VIRTUAL_ENV_DIRS {
+            envs.push(dir.join(name));
+        }
+    }
+
+    let mut dirs = Vec::new();
+    for env_dir in envs {
+        // Windows uses Lib/site-packages, others use lib/pythonX.Y/site-packages
+        let windows_dir = env_dir.join("Lib").join("site-packages");
+        if tokio::fs::try_exists(&windows_dir)
+            .await
+            .unwrap_or_default()
+        {
+            dirs.push(windows_dir);
+        }
+        let Ok(mut entries) = tokio::fs::read_dir(env_dir.join("lib")).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if entry.file_name().to_string_lossy().starts_with("python") {
+                dirs.push(entry.path().join("site-packages"));
+            }
+        }
+    }
+    dirs
+}
@@ -1,11 +1,18 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
 use tracing::error;
 
-use crate::shared::{Request, RequestResult};
+use crate::shared::{Request, RequestContext, RequestResult};
 
 mod cache;
+mod config;
 mod consts;
+mod local;
 mod requests;
-mod util;
+pub(crate) mod util;
 
 pub mod models;
 
@@ -14,18 +21,71 @@ use self::cache::PyPiCache;
 #[derive(Debug, Clone)]
 pub struct PyPiClient {
     cache: PyPiCache,
+    registry_override: Arc<Mutex<Option<String>>>,
+    api_url_override: Arc<Mutex<Option<String>>>,
+    context: RequestContext,
 }
 
 impl PyPiClient {
     #[must_use]
-    pub fn new() -> Self {
+    pub(crate) fn new(context: RequestContext) -> Self {
         Self {
             cache: PyPiCache::new(),
+            registry_override: Arc::new(Mutex::new(None)),
+            api_url_override: Arc::new(Mutex::new(None)),
+            context,
         }
     }
 
     async fn request_get(&self, url: impl Into<String>) -> RequestResult<Vec<u8>> {
-        Request::get(url).send().await
+        Request::get(url).send(&self.context).await
+    }
+
+    /**
+        Overrides how long registry data is cached for,
+        or resets it to the default if `None` is given.
+    */
+    pub fn set_cache_ttl(&self, ttl: Option<Duration>) {
+        self.cache.set_time_to_live(ttl);
+    }
+
+    /**
+        Overrides the index url that is used instead of the public `PyPI`
+        index, or resets it if `None` is given. Any other indexes configured
+        for the project or for pip are used as before.
+    */
+    pub fn set_registry_override(&self, url: Option<String>) {
+        *self
+            .registry_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = url;
+    }
+
+    fn registry_override(&self) -> Option<String> {
+        self.registry_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /**
+        Overrides the url of the `PyPI` JSON API, which is used for
+        package metadata, or resets it if `None` is given.
+    */
+    pub fn set_api_url_override(&self, url: Option<String>) {
+        *self
+            .api_url_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = url;
+        self.cache.invalidate_api();
+    }
+
+    fn api_url(&self) -> String {
+        self.api_url_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .unwrap_or_else(|| consts::BASE_URL_REGISTRY.to_string())
     }
 
     fn emit_result<T>(result: &RequestResult<T>) {
@@ -37,6 +97,47 @@ impl PyPiClient {
 
 impl Default for PyPiClient {
     fn default() -> Self {
-        Self::new()
+        Self::new(RequestContext::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use crate::shared::RequestError;
+
+    use super::*;
+
+    // set_api_url_override
+
+    #[tokio::test]
+    async fn api_url_override_invalidates_cache() {
+        let client = PyPiClient::default();
+        let runs = AtomicU32::new(0);
+        let fetch = || async {
+            runs.fetch_add(1, Ordering::SeqCst);
+            Err(RequestError::Unknown)
+        };
+
+        let _ = client
+            .cache
+            .registry_metadatas
+            .with_caching("key", fetch())
+            .await;
+        let _ = client
+            .cache
+            .registry_metadatas
+            .with_caching("key", fetch())
+            .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        client.set_api_url_override(Some(String::from("https://mirror.example.com/api")));
+        let _ = client
+            .cache
+            .registry_metadatas
+            .with_caching("key", fetch())
+            .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
     }
 }
@@ -1,5 +1,7 @@
+mod index;
 mod registry;
 mod simple;
 
+pub use self::index::*;
 pub use self::registry::*;
 pub use self::simple::*;
@@ -1,17 +1,63 @@
 use tracing::debug;
 
-use crate::shared::Request;
+use crate::shared::{Request, RequestError};
 
-use super::consts::{BASE_URL_REGISTRY, BASE_URL_SIMPLE, SIMPLE_CONTENT_TYPE};
-use super::models::{RegistryMetadata, SimpleMetadata};
+use super::consts::SIMPLE_CONTENT_TYPE;
+use super::models::{PyPiIndex, RegistryMetadata, SimpleMetadata};
 use super::util::normalize_name;
 use super::{PyPiClient, RequestResult};
 
 impl PyPiClient {
     #[allow(clippy::missing_errors_doc)]
     pub async fn get_simple_metadata(&self, name: &str) -> RequestResult<SimpleMetadata> {
+        self.get_simple_metadata_from(&[PyPiIndex::pypi()], name)
+            .await
+    }
+
+    /**
+        Fetches simple metadata for a package from the first of the given
+        indexes that has it, trying each index in order of priority.
+
+        A not found error is only returned if none of the indexes have the
+        package - any other error, such as an index rejecting our credentials,
+        takes precedence over it, so that packages are not wrongly reported
+        as nonexistent.
+
+        See [`PyPiClient::get_indexes`] for resolving configured indexes.
+    */
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn get_simple_metadata_from(
+        &self,
+        indexes: &[PyPiIndex],
+        name: &str,
+    ) -> RequestResult<SimpleMetadata> {
+        let mut error: Option<RequestError> = None;
+        for index in indexes {
+            match self.get_index_simple_metadata(index, name).await {
+                Ok(meta) => return Ok(meta),
+                Err(e) => {
+                    if error.as_ref().is_none_or(RequestError::is_not_found_error) {
+                        error = Some(e);
+                    }
+                }
+            }
+        }
+        match error {
+            Some(e) => Err(e),
+            None => {
+                self.get_index_simple_metadata(&PyPiIndex::pypi(), name)
+                    .await
+            }
+        }
+    }
+
+    async fn get_index_simple_metadata(
+        &self,
+        index: &PyPiIndex,
+        name: &str,
+    ) -> RequestResult<SimpleMetadata> {
         let normalized = normalize_name(name);
-        let simple_url = format!("{BASE_URL_SIMPLE}/{normalized}/");
+        let simple_url = format!("{}/{normalized}/", index.url);
 
         let fut = async {
             debug!("Fetching PyPI simple metadata for '{name}'");
@@ -21,7 +67,7 @@ impl PyPiClient {
             let inner = async {
                 let bytes = Request::get(&simple_url)
                     .with_header("Accept", SIMPLE_CONTENT_TYPE)
-                    .send()
+                    .send(&self.context)
                     .await?;
                 let text = String::from_utf8(bytes)?;
                 Ok(SimpleMetadata::try_from_json(&text)?)
@@ -35,14 +81,14 @@ impl PyPiClient {
 
         self.cache
             .simple_metadatas
-            .with_caching(simple_url.clone(), fut)
+            .with_revalidation(simple_url.clone(), fut)
             .await
     }
 
     #[allow(clippy::missing_errors_doc)]
     pub async fn get_registry_metadata(&self, name: &str) -> RequestResult<RegistryMetadata> {
         let normalized = normalize_name(name);
-        let registry_url = format!("{BASE_URL_REGISTRY}/{normalized}/json");
+        let registry_url = format!("{}/{normalized}/json", self.api_url());
 
         let fut = async {
             debug!("Fetching PyPI registry metadata for '{name}'");
@@ -63,7 +109,7 @@ impl PyPiClient {
 
         self.cache
             .registry_metadatas
-            .with_caching(registry_url.clone(), fut)
+            .with_revalidation(registry_url.clone(), fut)
             .await
     }
 }
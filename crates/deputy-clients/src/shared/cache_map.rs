@@ -1,22 +1,54 @@
-use std::{future::Future, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeSet,
+    future::Future,
+    sync::{Arc, PoisonError, RwLock},
+    time::Duration,
+};
 
 use dashmap::DashMap;
 use moka::future::Cache;
 use tokio::sync::Semaphore;
-use tracing::trace;
+use tracing::{debug, trace};
+
+use super::{RequestResult, record_used_hosts, track_used_hosts};
+
+// How long the previous result for a key is kept around after it was last
+// used, so that it can be used again if revalidating it fails
+const STALE_TIME_TO_IDLE: Duration = Duration::from_hours(24);
 
 type CacheMap<T> = Cache<String, T>;
 type Semaphores = Arc<DashMap<String, Arc<Semaphore>>>;
 
+// A cached result, along with the hosts that requests were sent to while fetching it
+#[derive(Debug, Clone)]
+struct Cached<T> {
+    value: T,
+    hosts: Arc<BTreeSet<String>>,
+}
+
 /**
     Generic cache map for web requests.
 
     This cache map is both thread-safe and concurrency-safe.
+
+    Once cached data expires, it is revalidated on next use rather than downloaded
+    again - the requests made by [`Request::send`] are conditional, and reuse the
+    previous response if the server says that it has not been modified. Results
+    cached using [`RequestCacheMap::with_revalidation`] are also kept after expiring,
+    and used again if revalidating them fails, such as when a registry is down.
+
+    Hosts that requests were sent to while fetching a result are cached along with it,
+    and recorded again whenever the cached result is used, see [`track_used_hosts`].
+
+    [`Request::send`]: super::Request::send
 */
 #[derive(Debug, Clone)]
 pub struct RequestCacheMap<T: Clone + Send + Sync + 'static> {
-    map: CacheMap<T>,
+    map: Arc<RwLock<CacheMap<Cached<T>>>>,
+    stale: CacheMap<T>,
     sems: Semaphores,
+    seconds_to_live: u64,
+    seconds_to_idle: u64,
 }
 
 impl<T: Clone + Send + Sync + 'static> RequestCacheMap<T> {
@@ -41,25 +73,48 @@ impl<T: Clone + Send + Sync + 'static> RequestCacheMap<T> {
           before a cached piece of data gets removed from the cache
     */
     pub fn new_secs(seconds_to_live: u64, seconds_to_idle: u64) -> Self {
-        let map = Cache::builder()
-            .max_capacity(64)
-            .time_to_live(Duration::from_secs(seconds_to_live))
-            .time_to_idle(Duration::from_secs(seconds_to_idle))
-            .build();
         RequestCacheMap {
-            map,
+            map: Arc::new(RwLock::new(build_map(seconds_to_live, seconds_to_idle))),
+            stale: Cache::builder()
+                .max_capacity(64)
+                .time_to_idle(STALE_TIME_TO_IDLE)
+                .build(),
             sems: Arc::new(DashMap::new()),
+            seconds_to_live,
+            seconds_to_idle,
         }
     }
 
+    /**
+        Overrides how long cached data lives for, or resets it
+        to the default that this cache map was created with.
+
+        Changing the time to live clears out all of the data from the cache.
+    */
+    pub fn set_time_to_live(&self, time_to_live: Option<Duration>) {
+        let map = match time_to_live {
+            Some(ttl) => build_map(ttl.as_secs(), ttl.as_secs()),
+            None => build_map(self.seconds_to_live, self.seconds_to_idle),
+        };
+        *self.map.write().unwrap_or_else(PoisonError::into_inner) = map;
+    }
+
+    fn current_map(&self) -> CacheMap<Cached<T>> {
+        self.map
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
     /**
         Invalidates the cache map.
 
-        This will clear out all of the data from the cache and force
-        any new requests using `with_caching` to fetch new data.
+        This will clear out all of the data from the cache, including any previous
+        results kept for revalidation, and force any new requests to fetch new data.
     */
     pub fn invalidate(&self) {
-        self.map.invalidate_all();
+        self.current_map().invalidate_all();
+        self.stale.invalidate_all();
     }
 
     /**
@@ -99,11 +154,13 @@ impl<T: Clone + Send + Sync + 'static> RequestCacheMap<T> {
         F: Future<Output = T>,
     {
         let key = key.into();
+        let map = self.current_map();
 
         // Return cached value right away if possible
-        if let Some(cached) = self.map.get(&key).await {
+        if let Some(cached) = map.get(&key).await {
             trace!("Cache hit (1): {key}");
-            return cached.clone();
+            record_used_hosts(cached.hosts.iter());
+            return cached.value;
         }
 
         // Wait for permission to try to perform the request -
@@ -119,15 +176,204 @@ impl<T: Clone + Send + Sync + 'static> RequestCacheMap<T> {
         let _guard = sem.acquire().await;
 
         // We have permission, but the cache may have been updated, check again
-        if let Some(cached) = self.map.get(&key).await {
+        if let Some(cached) = map.get(&key).await {
             trace!("Cache hit (2): {key}");
-            return cached.clone();
+            record_used_hosts(cached.hosts.iter());
+            return cached.value;
         }
 
         // Not cached, and we have permission, so perform the request
         trace!("Performing cached request: {key}");
-        let result = f.await;
-        self.map.insert(key.clone(), result.clone()).await;
-        result
+        let (value, hosts) = track_used_hosts(f).await;
+        record_used_hosts(hosts.iter());
+        let cached = Cached {
+            value: value.clone(),
+            hosts: Arc::new(hosts),
+        };
+        map.insert(key.clone(), cached).await;
+        value
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> RequestCacheMap<RequestResult<T>> {
+    /**
+        Run a request with caching and single concurrency limit, same as
+        [`RequestCacheMap::with_caching`], revalidating its result once it expires.
+
+        The last successful result is kept after expiring, and if running the request
+        again fails because of an error that is likely temporary - a network error,
+        an outage, a rate limit, a proxy rejecting it, or being offline - the previous
+        result is used instead, until it has gone unused for a day.
+    */
+    pub async fn with_revalidation<F>(&self, key: impl Into<String>, f: F) -> RequestResult<T>
+    where
+        F: Future<Output = RequestResult<T>>,
+    {
+        let key = key.into();
+        let fut = async {
+            let error = match f.await {
+                Ok(value) => {
+                    self.stale.insert(key.clone(), Ok(value.clone())).await;
+                    return Ok(value);
+                }
+                Err(e) => e,
+            };
+
+            let revalidation_failed = error.is_transient_error()
+                || error.is_rate_limit_error()
+                || error.is_proxy_error()
+                || error.is_offline_error();
+            match self.stale.get(&key).await {
+                Some(previous) if revalidation_failed => {
+                    debug!("Failed to revalidate '{key}', using previous result - {error}");
+                    previous
+                }
+                _ => Err(error),
+            }
+        };
+        self.with_caching(key.clone(), fut).await
+    }
+}
+
+fn build_map<T: Clone + Send + Sync + 'static>(
+    seconds_to_live: u64,
+    seconds_to_idle: u64,
+) -> CacheMap<T> {
+    Cache::builder()
+        .max_capacity(64)
+        .time_to_live(Duration::from_secs(seconds_to_live))
+        .time_to_idle(Duration::from_secs(seconds_to_idle))
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use reqwest::StatusCode;
+
+    use super::super::{RequestError, ResponseError};
+    use super::*;
+
+    fn not_found() -> RequestError {
+        ResponseError::from_status_and_string(StatusCode::NOT_FOUND, "not found").into()
+    }
+
+    fn unavailable() -> RequestError {
+        RequestError::Unavailable(String::from("registry.npmjs.org"))
+    }
+
+    // with_caching
+
+    #[tokio::test]
+    async fn runs_future_once_per_key() {
+        let map = RequestCacheMap::<u32>::new_mins(1, 1);
+        let runs = AtomicU32::new(0);
+        let run = || async {
+            runs.fetch_add(1, Ordering::SeqCst);
+            1
+        };
+
+        assert_eq!(map.with_caching("a", run()).await, 1);
+        assert_eq!(map.with_caching("a", run()).await, 1);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        assert_eq!(map.with_caching("b", run()).await, 1);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn runs_future_again_after_invalidating() {
+        let map = RequestCacheMap::<u32>::new_mins(1, 1);
+        assert_eq!(map.with_caching("a", async { 1 }).await, 1);
+        map.invalidate();
+        assert_eq!(map.with_caching("a", async { 2 }).await, 2);
+    }
+
+    #[tokio::test]
+    async fn runs_future_again_after_changing_time_to_live() {
+        let map = RequestCacheMap::<u32>::new_mins(1, 1);
+        assert_eq!(map.with_caching("a", async { 1 }).await, 1);
+        map.set_time_to_live(Some(Duration::from_secs(30)));
+        assert_eq!(map.with_caching("a", async { 2 }).await, 2);
+    }
+
+    #[tokio::test]
+    async fn cache_hits_record_used_hosts() {
+        let map = RequestCacheMap::<u32>::new_mins(1, 1);
+        let fetch = async {
+            record_used_hosts([&String::from("index.crates.io")]);
+            1
+        };
+
+        let (_, fetched) = track_used_hosts(map.with_caching("a", fetch)).await;
+        let (_, cached) = track_used_hosts(map.with_caching("a", async { 2 })).await;
+        assert_eq!(fetched, cached);
+        assert!(cached.contains("index.crates.io"));
+    }
+
+    // with_revalidation
+
+    #[tokio::test]
+    async fn revalidation_replaces_previous_result() {
+        let map = RequestCacheMap::<RequestResult<u32>>::new_mins(1, 1);
+        assert_eq!(
+            map.with_revalidation("a", async { Ok(1) }).await.unwrap(),
+            1
+        );
+        map.set_time_to_live(None);
+        assert_eq!(
+            map.with_revalidation("a", async { Ok(2) }).await.unwrap(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn revalidation_failure_uses_previous_result() {
+        let map = RequestCacheMap::<RequestResult<u32>>::new_mins(1, 1);
+        assert_eq!(
+            map.with_revalidation("a", async { Ok(1) }).await.unwrap(),
+            1
+        );
+        map.set_time_to_live(None);
+        let result = map
+            .with_revalidation("a", async { Err(unavailable()) })
+            .await;
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn revalidation_failure_without_previous_result() {
+        let map = RequestCacheMap::<RequestResult<u32>>::new_mins(1, 1);
+        let result = map
+            .with_revalidation("a", async { Err(unavailable()) })
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn revalidation_keeps_permanent_errors() {
+        let map = RequestCacheMap::<RequestResult<u32>>::new_mins(1, 1);
+        assert_eq!(
+            map.with_revalidation("a", async { Ok(1) }).await.unwrap(),
+            1
+        );
+        map.set_time_to_live(None);
+        let result = map.with_revalidation("a", async { Err(not_found()) }).await;
+        assert!(result.unwrap_err().is_not_found_error());
+    }
+
+    #[tokio::test]
+    async fn invalidating_forgets_previous_result() {
+        let map = RequestCacheMap::<RequestResult<u32>>::new_mins(1, 1);
+        assert_eq!(
+            map.with_revalidation("a", async { Ok(1) }).await.unwrap(),
+            1
+        );
+        map.invalidate();
+        let result = map
+            .with_revalidation("a", async { Err(unavailable()) })
+            .await;
+        assert!(result.is_err());
     }
 }
@@ -0,0 +1,302 @@
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use tokio::sync::broadcast;
+use tracing::{debug, warn};
+
+// How many requests in a row must fail before a host is considered down
+const FAILURE_THRESHOLD: u32 = 5;
+// How long to stop sending requests to a host that is down, before trying again
+const OPEN_DURATION: Duration = Duration::from_mins(1);
+
+/**
+    An outage of a registry or API, reported once when requests to its host keep
+    failing, or a host that requests are being rejected for by a proxy.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryOutage {
+    pub host: String,
+    pub reason: String,
+    /// How long requests to the host are paused for, before trying again -
+    /// `None` for hosts rejected by a proxy, which are tried on every request
+    pub paused_for: Option<Duration>,
+}
+
+/**
+    Circuit breakers for each host that requests are sent to.
+
+    Once enough requests in a row to a host have failed, because of network errors
+    or server errors, no more requests are sent to it for a while - they fail right away,
+    which prevents waiting on retries for a registry that is down. Once the pause is over,
+    a single request is let through to check if the host has recovered, and the host is
+    paused again if that request fails too. The outage is reported once, and reported
+    again only if the host recovers and then goes down again.
+
+    Requests that are rejected by a proxy are tracked separately, since they are not going
+    to succeed by waiting, and are instead shown to the user until a request succeeds.
+*/
+#[derive(Debug)]
+pub(crate) struct CircuitBreakers {
+    hosts: DashMap<String, HostCircuit>,
+    outages: broadcast::Sender<RegistryOutage>,
+    open_duration: Duration,
+}
+
+#[derive(Debug, Default)]
+struct HostCircuit {
+    failures: u32,
+    open_until: Option<Instant>,
+    // When the single request checking if the host has recovered was let through
+    probing_since: Option<Instant>,
+    reported: bool,
+    rejected: Option<String>,
+}
+
+impl CircuitBreakers {
+    pub fn new() -> Self {
+        Self {
+            hosts: DashMap::new(),
+            outages: broadcast::channel(16).0,
+            open_duration: OPEN_DURATION,
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<RegistryOutage> {
+        self.outages.subscribe()
+    }
+
+    /**
+        Checks if a request may be sent to the given host.
+
+        While the host is paused, no requests are allowed. Once the pause is over,
+        only a single request is allowed, until it either succeeds or fails - unless
+        it never finishes, in which case another one is allowed after another pause.
+    */
+    pub fn allow_request(&self, host: &str) -> bool {
+        let Some(mut circuit) = self.hosts.get_mut(host) else {
+            return true;
+        };
+        let Some(open_until) = circuit.open_until else {
+            return true;
+        };
+
+        let now = Instant::now();
+        if now < open_until {
+            return false;
+        }
+        if circuit
+            .probing_since
+            .is_some_and(|since| now < since + self.open_duration)
+        {
+            return false;
+        }
+
+        debug!("Checking if '{host}' has recovered");
+        circuit.probing_since = Some(now);
+        true
+    }
+
+    pub fn record_success(&self, host: &str) {
+        self.hosts.remove(host);
+    }
+
+    pub fn record_rejection(&self, host: &str, reason: &str) {
+        let mut circuit = self.hosts.entry(host.to_string()).or_default();
+        if circuit.rejected.is_none() {
+            warn!("Requests to '{host}' are being rejected - {reason}");
+        }
+        circuit.rejected = Some(reason.to_string());
+        circuit.probing_since = None;
+    }
+
+    /**
+        Gets all hosts that requests are currently being rejected for by a proxy.
+    */
+    pub fn rejections(&self) -> Vec<RegistryOutage> {
+        let mut rejections = self
+            .hosts
+            .iter()
+            .filter_map(|entry| {
+                let reason = entry.value().rejected.clone()?;
+                Some(RegistryOutage {
+                    host: entry.key().clone(),
+                    reason,
+                    paused_for: None,
+                })
+            })
+            .collect::<Vec<_>>();
+        rejections.sort_by(|a, b| a.host.cmp(&b.host));
+        rejections
+    }
+
+    pub fn record_failure(&self, host: &str, reason: &str) {
+        let mut circuit = self.hosts.entry(host.to_string()).or_default();
+
+        // The host was already paused, and is still down - pause it again
+        if circuit.open_until.is_some() {
+            circuit.open_until = Some(Instant::now() + self.open_duration);
+            circuit.probing_since = None;
+            return;
+        }
+
+        circuit.failures += 1;
+        if circuit.failures < FAILURE_THRESHOLD {
+            return;
+        }
+
+        circuit.open_until = Some(Instant::now() + self.open_duration);
+        if !circuit.reported {
+            circuit.reported = true;
+            warn!("Requests to '{host}' keep failing, pausing requests - {reason}");
+            // Nobody may be listening for outages, which is fine
+            let _ = self.outages.send(RegistryOutage {
+                host: host.to_string(),
+                reason: reason.to_string(),
+                paused_for: Some(self.open_duration),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOST: &str = "index.crates.io";
+
+    fn fail(circuits: &CircuitBreakers, times: u32) {
+        for _ in 0..times {
+            circuits.record_failure(HOST, "connection refused");
+        }
+    }
+
+    fn end_pause(circuits: &CircuitBreakers) {
+        circuits.hosts.get_mut(HOST).unwrap().open_until = Some(Instant::now());
+    }
+
+    fn without_pause() -> CircuitBreakers {
+        CircuitBreakers {
+            open_duration: Duration::ZERO,
+            ..CircuitBreakers::new()
+        }
+    }
+
+    // allow_request
+
+    #[test]
+    fn allows_unknown_hosts() {
+        let circuits = CircuitBreakers::new();
+        assert!(circuits.allow_request(HOST));
+    }
+
+    #[test]
+    fn allows_requests_below_threshold() {
+        let circuits = CircuitBreakers::new();
+        fail(&circuits, FAILURE_THRESHOLD - 1);
+        assert!(circuits.allow_request(HOST));
+        assert!(circuits.allow_request("registry.npmjs.org"));
+    }
+
+    #[test]
+    fn pauses_requests_at_threshold() {
+        let circuits = CircuitBreakers::new();
+        fail(&circuits, FAILURE_THRESHOLD);
+        assert!(!circuits.allow_request(HOST));
+        assert!(circuits.allow_request("registry.npmjs.org"));
+    }
+
+    #[test]
+    fn success_resets_failures() {
+        let circuits = CircuitBreakers::new();
+        fail(&circuits, FAILURE_THRESHOLD - 1);
+        circuits.record_success(HOST);
+        fail(&circuits, FAILURE_THRESHOLD - 1);
+        assert!(circuits.allow_request(HOST));
+    }
+
+    #[test]
+    fn allows_single_probe_after_pause() {
+        let circuits = CircuitBreakers::new();
+        fail(&circuits, FAILURE_THRESHOLD);
+        end_pause(&circuits);
+        assert!(circuits.allow_request(HOST));
+        assert!(!circuits.allow_request(HOST));
+        assert!(!circuits.allow_request(HOST));
+    }
+
+    #[test]
+    fn allows_new_probe_if_probe_never_finishes() {
+        let circuits = without_pause();
+        fail(&circuits, FAILURE_THRESHOLD);
+        assert!(circuits.allow_request(HOST));
+        assert!(circuits.allow_request(HOST));
+    }
+
+    #[test]
+    fn closes_after_successful_probe() {
+        let circuits = without_pause();
+        fail(&circuits, FAILURE_THRESHOLD);
+        assert!(circuits.allow_request(HOST));
+        circuits.record_success(HOST);
+        assert!(circuits.allow_request(HOST));
+        assert!(circuits.allow_request(HOST));
+    }
+
+    #[test]
+    fn pauses_again_after_failed_probe() {
+        let circuits = CircuitBreakers::new();
+        fail(&circuits, FAILURE_THRESHOLD);
+        end_pause(&circuits);
+        assert!(circuits.allow_request(HOST));
+        fail(&circuits, 1);
+        assert!(!circuits.allow_request(HOST));
+    }
+
+    // outages
+
+    #[test]
+    fn reports_outage_once() {
+        let circuits = without_pause();
+        let mut outages = circuits.subscribe();
+        fail(&circuits, FAILURE_THRESHOLD);
+        let outage = outages.try_recv().unwrap();
+        assert_eq!(outage.host, HOST);
+        assert_eq!(outage.reason, "connection refused");
+        assert_eq!(outage.paused_for, Some(Duration::ZERO));
+
+        // A failed probe is the same outage
+        assert!(circuits.allow_request(HOST));
+        fail(&circuits, 1);
+        assert!(outages.try_recv().is_err());
+    }
+
+    #[test]
+    fn reports_outage_again_after_recovery() {
+        let circuits = CircuitBreakers::new();
+        let mut outages = circuits.subscribe();
+        fail(&circuits, FAILURE_THRESHOLD);
+        circuits.record_success(HOST);
+        fail(&circuits, FAILURE_THRESHOLD);
+        assert!(outages.try_recv().is_ok());
+        assert!(outages.try_recv().is_ok());
+    }
+
+    // rejections
+
+    #[test]
+    fn lists_rejections_until_success() {
+        let circuits = CircuitBreakers::new();
+        circuits.record_rejection("registry.npmjs.org", "tunnel error");
+        circuits.record_rejection(HOST, "untrusted certificate");
+        circuits.record_failure("pypi.org", "connection refused");
+
+        let rejections = circuits.rejections();
+        assert_eq!(rejections.len(), 2);
+        assert_eq!(rejections[0].host, HOST);
+        assert_eq!(rejections[0].paused_for, None);
+        assert_eq!(rejections[1].host, "registry.npmjs.org");
+
+        circuits.record_success(HOST);
+        assert_eq!(circuits.rejections().len(), 1);
+    }
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use super::{CircuitBreakers, HttpTransport, ReqwestTransport, ResponseCache};
+
+/**
+    Everything that requests are sent with - the transport, along with the response
+    cache and circuit breakers, which are owned by each [`Clients`](crate::Clients)
+    and shared by all of its clients, but never by separate instances.
+*/
+#[derive(Debug, Clone)]
+pub(crate) struct RequestContext {
+    pub transport: Arc<dyn HttpTransport>,
+    pub cache: Arc<ResponseCache>,
+    pub circuits: Arc<CircuitBreakers>,
+}
+
+impl RequestContext {
+    pub fn new(transport: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            transport,
+            cache: Arc::new(ResponseCache::new()),
+            circuits: Arc::new(CircuitBreakers::new()),
+        }
+    }
+}
+
+impl Default for RequestContext {
+    fn default() -> Self {
+        Self::new(Arc::new(ReqwestTransport::new()))
+    }
+}
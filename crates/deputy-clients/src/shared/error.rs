@@ -1,4 +1,4 @@
-use std::{fmt, string::FromUtf8Error};
+use std::{error::Error as _, fmt, string::FromUtf8Error, time::Duration};
 
 use reqwest::StatusCode;
 use thiserror::Error;
@@ -9,6 +9,8 @@ pub type RequestResult<T, E = RequestError> = Result<T, E>;
 pub struct ResponseError {
     pub(super) status: StatusCode,
     pub(super) bytes: Vec<u8>,
+    /// How long the server asked us to wait before retrying, if it did
+    pub(super) retry_after: Option<Duration>,
 }
 
 impl ResponseError {
@@ -16,8 +18,14 @@ impl ResponseError {
         Self {
             status,
             bytes: string.as_ref().as_bytes().to_vec(),
+            retry_after: None,
         }
     }
+
+    #[must_use]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
 }
 
 impl fmt::Debug for ResponseError {
@@ -26,11 +34,13 @@ impl fmt::Debug for ResponseError {
             f.debug_struct("ResponseError")
                 .field("status", &self.status)
                 .field("bytes", &s)
+                .field("retry_after", &self.retry_after)
                 .finish()
         } else {
             f.debug_struct("ResponseError")
                 .field("status", &self.status)
                 .field("bytes", &"Vec<u8>")
+                .field("retry_after", &self.retry_after)
                 .finish()
         }
     }
@@ -56,14 +66,25 @@ pub enum RequestError {
     UrlParse(#[from] url::ParseError),
     #[error("client error - {0}")]
     Client(String),
+    #[error("network error - {0}")]
+    Network(String),
+    #[error("rejected by proxy - {0}")]
+    Proxy(String),
+    #[error("untrusted certificate - {0}")]
+    Certificate(String),
     #[error("json error - {0}")]
     Json(String),
+    #[error("offline, and no cached response exists for '{0}'")]
+    Offline(String),
+    #[error("'{0}' is unavailable, and no cached response exists")]
+    Unavailable(String),
     #[error("unknown error")]
     #[default]
     Unknown,
 }
 
 impl RequestError {
+    #[must_use]
     pub fn is_not_found_error(&self) -> bool {
         if let RequestError::Response(e) = self {
             e.status == StatusCode::NOT_FOUND
@@ -72,6 +93,52 @@ impl RequestError {
         }
     }
 
+    /**
+        Checks if this error means that the resource does not exist, or no longer exists -
+        Go module proxies use `410 Gone` for modules that they refuse to serve.
+    */
+    #[must_use]
+    pub fn is_not_found_or_gone_error(&self) -> bool {
+        if let RequestError::Response(e) = self {
+            e.status == StatusCode::NOT_FOUND || e.status == StatusCode::GONE
+        } else {
+            false
+        }
+    }
+
+    /**
+        Checks if this error is likely to be temporary - a network error, a server
+        error or a rate limit - meaning that the same request may succeed later on.
+    */
+    #[must_use]
+    pub fn is_transient_error(&self) -> bool {
+        match self {
+            RequestError::Network(_) | RequestError::Unavailable(_) => true,
+            RequestError::Response(e) => {
+                e.status == StatusCode::TOO_MANY_REQUESTS
+                    || e.status == StatusCode::REQUEST_TIMEOUT
+                    || e.status.is_server_error()
+            }
+            _ => false,
+        }
+    }
+
+    /**
+        Checks if this error means that requests are being rejected by a proxy -
+        either directly, or because a TLS-intercepting proxy presented a certificate
+        that is not trusted. Such errors are not transient, and need configuration to fix.
+    */
+    #[must_use]
+    pub fn is_proxy_error(&self) -> bool {
+        matches!(self, RequestError::Proxy(_) | RequestError::Certificate(_))
+    }
+
+    #[must_use]
+    pub fn is_offline_error(&self) -> bool {
+        matches!(self, RequestError::Offline(_))
+    }
+
+    #[must_use]
     pub fn is_rate_limit_error(&self) -> bool {
         if let RequestError::Response(e) = self {
             if e.status == StatusCode::TOO_MANY_REQUESTS {
@@ -90,7 +157,42 @@ impl RequestError {
 
 impl From<reqwest::Error> for RequestError {
     fn from(value: reqwest::Error) -> Self {
-        Self::Client(value.to_string())
+        // The top-level message is usually just "error sending request for url",
+        // the actual cause is only found further down the chain of sources
+        let mut causes = Vec::new();
+        let mut source = value.source();
+        while let Some(cause) = source {
+            causes.push(cause.to_string());
+            source = cause.source();
+        }
+        // Only failures to connect, send, or receive may succeed when retried,
+        // anything else such as an invalid request is a permanent client error
+        let network =
+            value.is_connect() || value.is_timeout() || value.is_request() || value.is_body();
+        Self::from_causes(value.to_string(), &causes, network)
+    }
+}
+
+impl RequestError {
+    /**
+        Classifies an error from sending a request, using the messages of its causes -
+        the errors for proxy tunnels and certificates are not public, and
+        can not be matched on, so their messages are matched instead.
+    */
+    fn from_causes(message: String, causes: &[String], network: bool) -> Self {
+        let full_message = || format!("{message}: {}", causes.join(": "));
+        if causes.iter().any(|cause| cause.starts_with("tunnel error")) {
+            Self::Proxy(full_message())
+        } else if causes
+            .iter()
+            .any(|cause| cause.contains("invalid peer certificate"))
+        {
+            Self::Certificate(full_message())
+        } else if network {
+            Self::Network(message)
+        } else {
+            Self::Client(message)
+        }
     }
 }
 
@@ -99,3 +201,83 @@ impl From<serde_json::Error> for RequestError {
         Self::Json(value.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, io::Write, net::TcpListener, thread};
+
+    use super::*;
+
+    const SENDING: &str = "error sending request for url (https://index.crates.io/se/rd/serde)";
+
+    fn causes(causes: &[&str]) -> Vec<String> {
+        causes.iter().map(ToString::to_string).collect()
+    }
+
+    // from_causes
+
+    #[test]
+    fn classifies_tunnel_errors_as_proxy() {
+        let causes = causes(&["client error (Connect)", "tunnel error: unsuccessful"]);
+        let error = RequestError::from_causes(SENDING.to_string(), &causes, true);
+        assert!(matches!(error, RequestError::Proxy(_)));
+        assert!(error.to_string().contains("tunnel error: unsuccessful"));
+    }
+
+    #[test]
+    fn classifies_invalid_certificates() {
+        let causes = causes(&[
+            "client error (Connect)",
+            "invalid peer certificate: UnknownIssuer",
+        ]);
+        let error = RequestError::from_causes(SENDING.to_string(), &causes, true);
+        assert!(matches!(error, RequestError::Certificate(_)));
+        assert!(error.is_proxy_error());
+        assert!(!error.is_transient_error());
+    }
+
+    #[test]
+    fn classifies_connection_errors_as_network() {
+        let causes = causes(&[
+            "client error (Connect)",
+            "Connection refused (os error 111)",
+        ]);
+        let error = RequestError::from_causes(SENDING.to_string(), &causes, true);
+        assert!(matches!(&error, RequestError::Network(message) if message == SENDING));
+        assert!(error.is_transient_error());
+    }
+
+    #[test]
+    fn classifies_other_errors_as_client() {
+        let error = RequestError::from_causes(String::from("builder error"), &[], false);
+        assert!(matches!(&error, RequestError::Client(_)));
+        assert!(!error.is_transient_error());
+    }
+
+    // From<reqwest::Error>
+
+    #[tokio::test]
+    async fn classifies_rejected_tunnel_as_proxy() {
+        // A proxy that rejects every tunnel, same as one that requires credentials
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.read(&mut [0; 1024]).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+                .unwrap();
+        });
+
+        let client = reqwest::Client::builder()
+            .proxy(reqwest::Proxy::all(proxy).unwrap())
+            .build()
+            .unwrap();
+        let error = client
+            .get("https://index.crates.io/config.json")
+            .send()
+            .await
+            .unwrap_err();
+        assert!(matches!(RequestError::from(error), RequestError::Proxy(_)));
+    }
+}
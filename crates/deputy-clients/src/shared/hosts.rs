@@ -0,0 +1,58 @@
+use std::{
+    collections::BTreeSet,
+    future::Future,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+tokio::task_local! {
+    static USED_HOSTS: Arc<Mutex<BTreeSet<String>>>;
+}
+
+/**
+    Runs the given future, collecting the hosts of all requests that it sends.
+
+    Results that are cached in a [`RequestCacheMap`] remember the hosts that were
+    used to fetch them, so a future that only uses cached results still collects
+    the same hosts as the future that originally sent the requests.
+
+    [`RequestCacheMap`]: super::RequestCacheMap
+*/
+pub async fn track_used_hosts<F: Future>(f: F) -> (F::Output, BTreeSet<String>) {
+    let hosts = Arc::new(Mutex::new(BTreeSet::new()));
+    let output = USED_HOSTS.scope(hosts.clone(), f).await;
+    let hosts = std::mem::take(&mut *hosts.lock().unwrap_or_else(PoisonError::into_inner));
+    (output, hosts)
+}
+
+/**
+    Records that a request was sent to the given host, if hosts are currently being tracked.
+*/
+pub(crate) fn record_used_hosts<'a>(hosts: impl IntoIterator<Item = &'a String>) {
+    let _ = USED_HOSTS.try_with(|used| {
+        used.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(hosts.into_iter().cloned());
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn tracks_hosts_within_scope() {
+        record_used_hosts(&[String::from("ignored.example.com")]);
+
+        let ((), hosts) = track_used_hosts(async {
+            record_used_hosts(&[String::from("pypi.org")]);
+            let ((), inner) = track_used_hosts(async {
+                record_used_hosts(&[String::from("index.crates.io")]);
+            })
+            .await;
+            assert_eq!(inner.into_iter().collect::<Vec<_>>(), ["index.crates.io"]);
+        })
+        .await;
+
+        assert_eq!(hosts.into_iter().collect::<Vec<_>>(), ["pypi.org"]);
+    }
+}
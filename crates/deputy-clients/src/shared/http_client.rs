@@ -0,0 +1,217 @@
+use std::{
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::{PoisonError, RwLock},
+    time::Duration,
+};
+
+use reqwest::{Certificate, Client, Identity, NoProxy, Proxy};
+use tracing::{debug, warn};
+
+use super::{RequestError, RequestResult};
+
+/**
+    Configuration for how requests are sent, such as through a proxy or with custom certificates.
+
+    Proxies from the `HTTPS_PROXY`, `HTTP_PROXY`, `ALL_PROXY` and `NO_PROXY` environment
+    variables are always used unless a proxy is given here, and root certificates
+    from `SSL_CERT_FILE` are always trusted, in addition to the system certificates.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpConfig {
+    /// Proxy to send all requests through, instead of any proxy from the environment
+    pub proxy: Option<String>,
+    /// Comma-separated hosts that should not be proxied, instead of `NO_PROXY`
+    pub no_proxy: Option<String>,
+    /// PEM file with extra root certificates to trust, such as for a TLS-intercepting proxy
+    pub ca_file: Option<PathBuf>,
+    /// PEM file with a client certificate, which may also contain its private key
+    pub client_certificate: Option<PathBuf>,
+    /// PEM file with the private key for the client certificate, if it is not in the same file
+    pub client_key: Option<PathBuf>,
+    pub connect_timeout: Option<Duration>,
+    pub read_timeout: Option<Duration>,
+}
+
+/**
+    The HTTP client of a [`ReqwestTransport`](super::ReqwestTransport),
+    which is rebuilt whenever its configuration changes.
+*/
+#[derive(Debug)]
+pub(crate) struct HttpClient {
+    current: RwLock<(HttpConfig, Client)>,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        let config = HttpConfig::default();
+        let client = build_client(&config).unwrap_or_else(|e| {
+            warn!("Failed to configure HTTP client, using defaults - {e}");
+            Client::new()
+        });
+        Self {
+            current: RwLock::new((config, client)),
+        }
+    }
+}
+
+impl HttpClient {
+    /**
+        Gets the current client - clients are cheap to clone, and share connections.
+    */
+    pub fn get(&self) -> Client {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .1
+            .clone()
+    }
+
+    /**
+        Rebuilds the client using the given configuration, unless it is unchanged.
+
+        If the configuration is invalid, the current client is kept.
+    */
+    pub fn configure(&self, config: HttpConfig) -> RequestResult<()> {
+        if self
+            .current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .0
+            == config
+        {
+            return Ok(());
+        }
+
+        let client = build_client(&config)?;
+        debug!("Configured HTTP client - {config:?}");
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = (config, client);
+        Ok(())
+    }
+}
+
+fn build_client(config: &HttpConfig) -> RequestResult<Client> {
+    let mut builder = Client::builder();
+
+    if let Some(url) = &config.proxy {
+        let no_proxy = match &config.no_proxy {
+            Some(hosts) => NoProxy::from_string(hosts),
+            None => NoProxy::from_env(),
+        };
+        let proxy = Proxy::all(url.as_str())
+            .map_err(|e| RequestError::Client(format!("invalid proxy '{url}' - {e}")))?;
+        builder = builder.proxy(proxy.no_proxy(no_proxy));
+    }
+
+    if let Some(certs) = env_certificates(env::var_os("SSL_CERT_FILE")) {
+        builder = builder.tls_certs_merge(certs);
+    }
+    if let Some(path) = &config.ca_file {
+        builder = builder.tls_certs_merge(read_certificates(path)?);
+    }
+
+    if let Some(path) = &config.client_certificate {
+        let mut pem = read_pem(path)?;
+        if let Some(key_path) = &config.client_key {
+            pem.push(b'\n');
+            pem.extend(read_pem(key_path)?);
+        }
+        let identity = Identity::from_pem(&pem).map_err(|e| {
+            RequestError::Client(format!(
+                "invalid client certificate '{}' - {e}",
+                path.display()
+            ))
+        })?;
+        builder = builder.identity(identity);
+    }
+
+    if let Some(timeout) = config.connect_timeout {
+        builder = builder.connect_timeout(timeout);
+    }
+    if let Some(timeout) = config.read_timeout {
+        builder = builder.read_timeout(timeout);
+    }
+
+    Ok(builder.build()?)
+}
+
+/**
+    Reads the certificates from the file given by `SSL_CERT_FILE`, if any.
+
+    Unlike a file that was explicitly configured, a file that can not be read is
+    only warned about and skipped, since the variable may be left over in the
+    environment, and should not prevent all requests from being sent.
+*/
+fn env_certificates(path: Option<OsString>) -> Option<Vec<Certificate>> {
+    let path = PathBuf::from(path.filter(|path| !path.is_empty())?);
+    read_certificates(&path)
+        .inspect_err(|e| warn!("Ignoring certificates from SSL_CERT_FILE - {e}"))
+        .ok()
+}
+
+fn read_certificates(path: &Path) -> RequestResult<Vec<Certificate>> {
+    Certificate::from_pem_bundle(&read_pem(path)?).map_err(|e| {
+        RequestError::Client(format!(
+            "invalid certificates in '{}' - {e}",
+            path.display()
+        ))
+    })
+}
+
+fn read_pem(path: &Path) -> RequestResult<Vec<u8>> {
+    std::fs::read(path)
+        .map_err(|e| RequestError::Client(format!("failed to read '{}' - {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MISSING_FILE: &str = "/nonexistent/deputy/certificates.pem";
+
+    #[test]
+    fn skips_unreadable_env_certificates() {
+        assert!(env_certificates(None).is_none());
+        assert!(env_certificates(Some(OsString::new())).is_none());
+        assert!(env_certificates(Some(OsString::from(MISSING_FILE))).is_none());
+    }
+
+    #[test]
+    fn rejects_unreadable_configured_certificates() {
+        let config = HttpConfig {
+            ca_file: Some(PathBuf::from(MISSING_FILE)),
+            ..Default::default()
+        };
+        let error = build_client(&config).unwrap_err();
+        assert!(matches!(error, RequestError::Client(_)));
+        assert!(error.to_string().contains(MISSING_FILE));
+    }
+
+    #[test]
+    fn configures_each_client_separately() {
+        let configured = HttpClient::default();
+        let other = HttpClient::default();
+        let config = HttpConfig {
+            connect_timeout: Some(Duration::from_secs(3)),
+            ..Default::default()
+        };
+        configured.configure(config.clone()).unwrap();
+
+        let current = |client: &HttpClient| client.current.read().unwrap().0.clone();
+        assert_eq!(current(&configured), config);
+        assert_eq!(current(&other), HttpConfig::default());
+    }
+
+    #[test]
+    fn rejects_invalid_proxy() {
+        let config = HttpConfig {
+            proxy: Some(String::from("not a url")),
+            ..Default::default()
+        };
+        assert!(matches!(
+            build_client(&config),
+            Err(RequestError::Client(_))
+        ));
+    }
+}
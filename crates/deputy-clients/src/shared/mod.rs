@@ -1,7 +1,28 @@
 mod cache_map;
+mod circuit;
+mod context;
+mod disk_cache;
 mod error;
+mod hosts;
+mod http_client;
+mod recording;
+mod redact;
 mod request;
+mod response_cache;
+mod transport;
 
 pub use self::cache_map::*;
+pub(crate) use self::circuit::CircuitBreakers;
+pub use self::circuit::RegistryOutage;
+pub(crate) use self::context::RequestContext;
+pub(crate) use self::disk_cache::*;
 pub use self::error::*;
+pub(crate) use self::hosts::record_used_hosts;
+pub use self::hosts::track_used_hosts;
+pub(crate) use self::http_client::HttpClient;
+pub use self::http_client::HttpConfig;
+pub use self::recording::*;
+pub(crate) use self::redact::*;
 pub use self::request::*;
+pub(crate) use self::response_cache::*;
+pub use self::transport::*;
@@ -1,15 +1,27 @@
 #![allow(dead_code)]
 
-use std::{collections::HashMap, str::FromStr, sync::LazyLock};
-
-use reqwest::{
-    Client, Method,
-    header::{HeaderName, HeaderValue, USER_AGENT},
+use std::{
+    collections::{HashMap, hash_map::RandomState},
+    hash::BuildHasher,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use tracing::trace;
+
+use reqwest::{Method, StatusCode};
+use tracing::{debug, trace};
 use url::Url;
 
-use super::{RequestError, RequestResult, ResponseError};
+use super::{
+    CachedResponse, HttpTransport, RequestContext, RequestError, RequestResult, ResponseError,
+    TransportRequest, TransportResponse, has_url_credentials, is_sensitive_header, parse_header,
+    record_used_hosts, response_cache_key,
+};
+
+// How many times to retry idempotent requests that failed because of transient errors
+const MAX_RETRIES: u32 = 3;
+// The delay before the first retry, which doubles for each subsequent retry
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+// Servers asking us to wait for longer than this are not retried at all
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(10);
 
 const USER_AGENT_VALUE: &str = concat!(
     env!("CARGO_PKG_NAME"),
@@ -20,8 +32,6 @@ const USER_AGENT_VALUE: &str = concat!(
     " )"
 );
 
-static CLIENT: LazyLock<Client> = LazyLock::new(Client::new);
-
 #[derive(Clone, Debug)]
 pub struct Request {
     method: Method,
@@ -44,6 +54,10 @@ impl Request {
         Self::new(Method::GET, url)
     }
 
+    pub fn post(url: impl Into<String>) -> Self {
+        Self::new(Method::POST, url)
+    }
+
     pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
         self.body = body.into();
         self
@@ -71,50 +85,652 @@ impl Request {
     pub fn with_headers(mut self, pairs: &[(&str, &str)]) -> Self {
         for (name, value) in pairs {
             self.headers
-                .insert((*name).to_string(), (*value).to_string());
+                .insert(name.to_ascii_lowercase(), (*value).to_string());
         }
         self
     }
 
-    pub async fn send(self) -> RequestResult<Vec<u8>> {
-        let mut request = reqwest::Request::new(self.method, Url::parse(&self.url)?);
+    /**
+        Sends the request using the transport of the given context, returning the body of the response.
+
+        Responses are cached, and requests for cached responses are sent as conditional
+        requests (`If-None-Match` / `If-Modified-Since`), reusing the cached body if the server
+        responds with `304 Not Modified`. Cached responses are also used when the request fails
+        because of a network error or a proxy rejecting it, and for all requests when offline.
+    */
+    pub(crate) async fn send(self, context: &RequestContext) -> RequestResult<Vec<u8>> {
+        let headers = self.headers.iter().map(|(n, v)| (n.as_str(), v.as_str()));
+        let cache_key = response_cache_key(self.method.as_str(), &self.url, headers, &self.body);
+        let authenticated = self.headers.keys().any(|name| is_sensitive_header(name))
+            || has_url_credentials(&self.url);
+        let cached = context.cache.read(&cache_key).await;
+
+        if context.cache.is_offline() {
+            return match cached {
+                Some(cached) => Ok(cached.body),
+                None => Err(RequestError::Offline(self.url)),
+            };
+        }
+
+        let url = self.url.clone();
+        let mut request = self;
+        if let Some(cached) = &cached
+            && !request.headers.contains_key("if-none-match")
+            && !request.headers.contains_key("if-modified-since")
+        {
+            request = request
+                .with_header_opt("If-None-Match", cached.etag.as_deref())
+                .with_header_opt("If-Modified-Since", cached.last_modified.as_deref());
+        }
+
+        match (request.execute_with_retries(context).await, cached) {
+            (Ok(response), Some(cached)) if response.status == StatusCode::NOT_MODIFIED => {
+                trace!("Revalidated cached response for '{url}'");
+                let cached = context
+                    .cache
+                    .write_revalidated(&cache_key, cached, authenticated)
+                    .await;
+                Ok(cached.body)
+            }
+            (Ok(response), _) => {
+                let mut cached = CachedResponse::new(&url, response.body);
+                cached.etag = response.etag;
+                cached.last_modified = response.last_modified;
+                context
+                    .cache
+                    .write(&cache_key, &cached, authenticated)
+                    .await;
+                Ok(cached.body)
+            }
+            (Err(e), Some(cached)) if e.is_transient_error() || e.is_proxy_error() => {
+                debug!(
+                    "Request to '{url}' failed, using response cached {}s ago - {e}",
+                    cached.age_secs()
+                );
+                Ok(cached.body)
+            }
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    /**
+        Executes the request, retrying it with jittered exponential backoff if it
+        fails because of a transient error, and it is safe to retry (`GET` / `HEAD`).
+
+        Requests to hosts that keep failing are not sent at all, see [`CircuitBreakers`].
+
+        [`CircuitBreakers`]: super::CircuitBreakers
+    */
+    async fn execute_with_retries(self, context: &RequestContext) -> RequestResult<Response> {
+        let host = Url::parse(&self.url)?
+            .host_str()
+            .unwrap_or_default()
+            .to_string();
+        record_used_hosts([&host]);
+
+        // Headers may come from user files such as `.npmrc`, and requests with
+        // invalid headers never succeed, so they are not retried or counted as failures
+        for (key, value) in &self.headers {
+            parse_header(key, value)?;
+        }
+
+        let circuits = &context.circuits;
+        if !circuits.allow_request(&host) {
+            return Err(RequestError::Unavailable(host));
+        }
 
-        // Set headers
-        let headers = request.headers_mut();
-        for (key, value) in self.headers {
-            headers.insert(
-                HeaderName::from_str(&key).expect("Passed invalid header name"),
-                HeaderValue::from_str(&value).expect("Passed invalid header value"),
+        let retries = if matches!(self.method, Method::GET | Method::HEAD) {
+            MAX_RETRIES
+        } else {
+            0
+        };
+        let mut attempt = 0;
+        let result = loop {
+            let result = self.clone().execute(context.transport.as_ref()).await;
+            let Err(e) = &result else {
+                break result;
+            };
+            let Some(delay) = retry_delay(e, attempt).filter(|_| attempt < retries) else {
+                break result;
+            };
+            debug!(
+                "Request to '{}' failed, retrying in {}ms - {e}",
+                self.url,
+                delay.as_millis()
             );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        };
+
+        match &result {
+            Err(e) if e.is_proxy_error() => circuits.record_rejection(&host, &e.to_string()),
+            Err(e) if e.is_transient_error() && !e.is_rate_limit_error() => {
+                circuits.record_failure(&host, &e.to_string());
+            }
+            _ => circuits.record_success(&host),
         }
+        result
+    }
+
+    async fn execute(self, transport: &dyn HttpTransport) -> RequestResult<Response> {
+        let mut headers = self
+            .headers
+            .into_iter()
+            .map(|(key, value)| (key.to_ascii_lowercase(), value))
+            .filter(|(key, _)| key != "user-agent")
+            .collect::<Vec<_>>();
 
         // Force user agent
-        headers.insert(
-            USER_AGENT,
-            HeaderValue::from_bytes(USER_AGENT_VALUE.as_bytes())
-                .expect("User agent header is invalid"),
-        );
+        headers.push((String::from("user-agent"), USER_AGENT_VALUE.to_string()));
 
         // Send request
+        let request = TransportRequest {
+            method: self.method.to_string(),
+            url: self.url,
+            headers,
+            body: self.body,
+        };
         trace!("Sending request:\n{request:#?}");
-        let response = CLIENT.execute(request).await?;
-        trace!("Got response:\n{response:#?}");
-        let status = response.status();
-        let body = response
-            .bytes()
-            .await
-            .map(|b| b.to_vec())
-            .map_err(|e| RequestError::Client(e.to_string()))?;
+        let response = transport.execute(request).await?;
+        trace!(
+            "Got response: {} with headers {:#?}",
+            response.status, response.headers
+        );
+        let status = StatusCode::from_u16(response.status)
+            .map_err(|e| RequestError::Client(format!("invalid status code - {e}")))?;
+        let header = |name: &str| response.header(name).map(ToString::to_string);
+        let etag = header("etag");
+        let last_modified = header("last-modified");
+        let retry_after = parse_retry_after(&response, SystemTime::now());
+        let body = response.body;
 
         // Return Err enum if the request has a non-200 status code
+        if status == StatusCode::PROXY_AUTHENTICATION_REQUIRED {
+            return Err(RequestError::Proxy(format!(
+                "{status} - the proxy requires credentials, which may be given in its url"
+            )));
+        }
         if status.is_client_error() || status.is_server_error() {
             let e = ResponseError {
                 status,
                 bytes: body,
+                retry_after,
             };
             return Err(e.into());
         }
 
-        Ok(body)
+        Ok(Response {
+            status,
+            body,
+            etag,
+            last_modified,
+        })
+    }
+}
+
+/**
+    A successful response, along with any validators that it was sent with.
+*/
+struct Response {
+    status: StatusCode,
+    body: Vec<u8>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/**
+    Gets how long to wait before retrying a request that failed with the given error,
+    or `None` if it should not be retried - either because the error is not transient,
+    or because the server asked us to wait for longer than we are willing to.
+*/
+fn retry_delay(error: &RequestError, attempt: u32) -> Option<Duration> {
+    if !error.is_transient_error() && !error.is_rate_limit_error() {
+        return None;
+    }
+    if let RequestError::Response(e) = error
+        && let Some(retry_after) = e.retry_after
+    {
+        return (retry_after <= RETRY_MAX_DELAY).then_some(retry_after);
+    }
+    if !error.is_transient_error() {
+        return None;
+    }
+
+    // Full jitter - a random delay between zero and the exponential backoff,
+    // which spreads out retries from many concurrent requests to the same host
+    let backoff = RETRY_BASE_DELAY * 2u32.pow(attempt);
+    let random = RandomState::new().hash_one(attempt);
+    let jitter = u32::try_from(random % 1000).unwrap_or_default();
+    Some(backoff * jitter / 1000 + Duration::from_millis(50))
+}
+
+/**
+    Gets how long the server asked us to wait before retrying, using either the
+    `Retry-After` header, given in seconds or as an HTTP date, or the time at which
+    the rate limit resets, which GitHub sends in its `x-ratelimit-reset` header instead.
+*/
+fn parse_retry_after(response: &TransportResponse, now: SystemTime) -> Option<Duration> {
+    if let Some(value) = response.header("retry-after") {
+        let value = value.trim();
+        if let Ok(secs) = value.parse::<u64>() {
+            return Some(Duration::from_secs(secs));
+        }
+        let date = parse_http_date(value)?;
+        return Some(date.duration_since(now).unwrap_or_default());
+    }
+
+    let remaining = response.header("x-ratelimit-remaining")?;
+    let reset = response.header("x-ratelimit-reset")?;
+    let reset = UNIX_EPOCH + Duration::from_secs(reset.trim().parse().ok()?);
+    (remaining.trim() == "0").then(|| reset.duration_since(now).unwrap_or_default())
+}
+
+/**
+    Parses an HTTP date in the preferred format, such as `Sun, 06 Nov 1994 08:49:37 GMT`.
+
+    The obsolete RFC 850 and asctime formats are not supported, since they are not
+    sent by any registry - such dates are ignored, same as any other invalid date.
+*/
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let mut parts = value.split_ascii_whitespace();
+    let _weekday = parts.next()?.strip_suffix(',')?;
+    let day = parts.next()?.parse::<u64>().ok()?;
+    let month = parts.next()?;
+    let month = MONTHS.iter().position(|m| *m == month)? as u64 + 1;
+    let year = parts.next()?.parse::<u64>().ok()?;
+    let mut time = parts
+        .next()?
+        .split(':')
+        .map(|part| part.parse::<u64>().ok());
+    let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+    if parts.next()? != "GMT" || parts.next().is_some() || time.next().is_some() {
+        return None;
+    }
+    if !(1..=31).contains(&day) || year < 1970 || hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    // Days since the unix epoch, using a proleptic Gregorian calendar
+    // with years starting in March, so that leap days come last
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let days = year * 365 + year / 4 - year / 100 + year / 400 + day_of_year - 719_468;
+
+    let secs = days * 86_400 + hours * 3_600 + minutes * 60 + seconds;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        sync::{Arc, Mutex},
+    };
+
+    use super::super::TransportFuture;
+    use super::*;
+
+    const URL: &str = "https://registry.npmjs.org/serde";
+
+    /**
+        A transport that returns the given responses in order, and remembers all requests.
+    */
+    #[derive(Debug, Default)]
+    struct MockTransport {
+        responses: Mutex<VecDeque<TransportResponse>>,
+        requests: Mutex<Vec<TransportRequest>>,
+    }
+
+    impl MockTransport {
+        fn respond(&self, response: TransportResponse) {
+            self.responses.lock().unwrap().push_back(response);
+        }
+
+        fn requests(&self) -> Vec<TransportRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+
+        fn last_header(&self, name: &str) -> Option<String> {
+            let requests = self.requests();
+            let (_, value) = requests
+                .last()?
+                .headers
+                .iter()
+                .find(|(key, _)| key == name)?;
+            Some(value.clone())
+        }
+    }
+
+    impl HttpTransport for MockTransport {
+        fn execute(&self, request: TransportRequest) -> TransportFuture<'_> {
+            self.requests.lock().unwrap().push(request);
+            let response = self.responses.lock().unwrap().pop_front();
+            Box::pin(async move { Ok(response.expect("no response left in mock transport")) })
+        }
+    }
+
+    fn context() -> (Arc<MockTransport>, RequestContext) {
+        let transport = Arc::new(MockTransport::default());
+        let context = RequestContext::new(transport.clone());
+        context.cache.set_disk_enabled(false, None);
+        (transport, context)
+    }
+
+    fn ok_with_etag(body: &str, etag: &str) -> TransportResponse {
+        TransportResponse {
+            status: 200,
+            headers: vec![
+                (String::from("etag"), etag.to_string()),
+                (
+                    String::from("last-modified"),
+                    String::from("Sun, 06 Nov 1994 08:49:37 GMT"),
+                ),
+            ],
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    // send
+
+    #[tokio::test]
+    async fn first_request_is_not_conditional() {
+        let (transport, context) = context();
+        transport.respond(ok_with_etag("v1", "\"1\""));
+
+        let body = Request::get(URL).send(&context).await.unwrap();
+        assert_eq!(body, b"v1");
+        assert_eq!(transport.last_header("if-none-match"), None);
+        assert_eq!(transport.last_header("if-modified-since"), None);
+        assert_eq!(
+            transport.last_header("user-agent").as_deref(),
+            Some(USER_AGENT_VALUE)
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_validators_of_cached_response() {
+        let (transport, context) = context();
+        transport.respond(ok_with_etag("v1", "\"1\""));
+        transport.respond(TransportResponse::new(304, ""));
+
+        Request::get(URL).send(&context).await.unwrap();
+        Request::get(URL).send(&context).await.unwrap();
+        assert_eq!(
+            transport.last_header("if-none-match").as_deref(),
+            Some("\"1\"")
+        );
+        assert_eq!(
+            transport.last_header("if-modified-since").as_deref(),
+            Some("Sun, 06 Nov 1994 08:49:37 GMT")
+        );
+    }
+
+    #[tokio::test]
+    async fn reuses_cached_body_when_not_modified() {
+        let (transport, context) = context();
+        transport.respond(ok_with_etag("v1", "\"1\""));
+        transport.respond(TransportResponse::new(304, ""));
+        transport.respond(TransportResponse::new(304, ""));
+
+        assert_eq!(Request::get(URL).send(&context).await.unwrap(), b"v1");
+        assert_eq!(Request::get(URL).send(&context).await.unwrap(), b"v1");
+        assert_eq!(Request::get(URL).send(&context).await.unwrap(), b"v1");
+        assert_eq!(
+            transport.last_header("if-none-match").as_deref(),
+            Some("\"1\"")
+        );
+    }
+
+    #[tokio::test]
+    async fn replaces_cached_body_when_modified() {
+        let (transport, context) = context();
+        transport.respond(ok_with_etag("v1", "\"1\""));
+        transport.respond(ok_with_etag("v2", "\"2\""));
+        transport.respond(TransportResponse::new(304, ""));
+
+        assert_eq!(Request::get(URL).send(&context).await.unwrap(), b"v1");
+        assert_eq!(Request::get(URL).send(&context).await.unwrap(), b"v2");
+        assert_eq!(Request::get(URL).send(&context).await.unwrap(), b"v2");
+        assert_eq!(
+            transport.last_header("if-none-match").as_deref(),
+            Some("\"2\"")
+        );
+    }
+
+    #[tokio::test]
+    async fn caches_requests_with_different_bodies_separately() {
+        let (transport, context) = context();
+        transport.respond(ok_with_etag("a", "\"a\""));
+        transport.respond(ok_with_etag("b", "\"b\""));
+
+        let a = Request::post(URL).with_body("a").send(&context).await;
+        let b = Request::post(URL).with_body("b").send(&context).await;
+        assert_eq!(a.unwrap(), b"a");
+        assert_eq!(b.unwrap(), b"b");
+        assert_eq!(transport.last_header("if-none-match"), None);
+    }
+
+    #[tokio::test]
+    async fn uses_cached_response_when_rejected_by_proxy() {
+        let (transport, context) = context();
+        transport.respond(ok_with_etag("v1", "\"1\""));
+        transport.respond(TransportResponse::new(407, ""));
+
+        assert_eq!(Request::get(URL).send(&context).await.unwrap(), b"v1");
+        assert_eq!(Request::get(URL).send(&context).await.unwrap(), b"v1");
+        assert_eq!(context.circuits.rejections().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_use_cached_response_for_permanent_errors() {
+        let (transport, context) = context();
+        transport.respond(ok_with_etag("v1", "\"1\""));
+        transport.respond(TransportResponse::new(404, "gone"));
+
+        Request::get(URL).send(&context).await.unwrap();
+        let error = Request::get(URL).send(&context).await.unwrap_err();
+        assert!(error.is_not_found_error());
+    }
+
+    #[tokio::test]
+    async fn invalid_headers_are_not_sent() {
+        let (transport, context) = context();
+        let error = Request::get(URL)
+            .with_header("Authorization", "Bearer token\n")
+            .send(&context)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, RequestError::Client(_)));
+        assert!(transport.requests().is_empty());
+        assert!(context.circuits.allow_request("registry.npmjs.org"));
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_malformed_responses() {
+        let (transport, context) = context();
+        transport.respond(TransportResponse::new(1000, ""));
+
+        let error = Request::get(URL).send(&context).await.unwrap_err();
+        assert!(matches!(error, RequestError::Client(_)));
+        assert!(!error.is_transient_error());
+        assert_eq!(transport.requests().len(), 1);
+        assert!(context.circuits.allow_request("registry.npmjs.org"));
+    }
+
+    #[tokio::test]
+    async fn offline_uses_cached_responses_only() {
+        let (transport, context) = context();
+        transport.respond(ok_with_etag("v1", "\"1\""));
+        Request::get(URL).send(&context).await.unwrap();
+
+        context.cache.set_offline(true);
+        assert_eq!(Request::get(URL).send(&context).await.unwrap(), b"v1");
+        let error = Request::get("https://registry.npmjs.org/tokio")
+            .send(&context)
+            .await
+            .unwrap_err();
+        assert!(error.is_offline_error());
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    fn response_with_headers(headers: &[(&str, &str)]) -> TransportResponse {
+        TransportResponse {
+            status: 429,
+            headers: headers
+                .iter()
+                .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+                .collect(),
+            body: Vec::new(),
+        }
+    }
+
+    fn response_error(status: u16, body: &str, retry_after: Option<Duration>) -> RequestError {
+        ResponseError {
+            status: StatusCode::from_u16(status).unwrap(),
+            bytes: body.as_bytes().to_vec(),
+            retry_after,
+        }
+        .into()
+    }
+
+    // Sun, 06 Nov 1994 08:49:37 GMT
+    const DATE_SECS: u64 = 784_111_777;
+
+    // retry_delay
+
+    #[test]
+    fn does_not_retry_permanent_errors() {
+        assert_eq!(retry_delay(&response_error(404, "", None), 0), None);
+        assert_eq!(retry_delay(&RequestError::Proxy(String::new()), 0), None);
+        assert_eq!(retry_delay(&RequestError::Json(String::new()), 0), None);
+    }
+
+    #[test]
+    fn retries_transient_errors_with_jittered_backoff() {
+        let error = RequestError::Network(String::from("connection reset"));
+        for attempt in 0..MAX_RETRIES {
+            let delay = retry_delay(&error, attempt).unwrap();
+            let max = RETRY_BASE_DELAY * 2u32.pow(attempt) + Duration::from_millis(50);
+            assert!(delay >= Duration::from_millis(50));
+            assert!(delay <= max, "{delay:?} > {max:?} for attempt {attempt}");
+        }
+    }
+
+    #[test]
+    fn retries_after_requested_delay() {
+        let error = response_error(503, "", Some(Duration::from_secs(2)));
+        assert_eq!(retry_delay(&error, 0), Some(Duration::from_secs(2)));
+        assert_eq!(retry_delay(&error, 2), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn does_not_retry_after_long_requested_delay() {
+        let error = response_error(429, "", Some(RETRY_MAX_DELAY + Duration::from_secs(1)));
+        assert_eq!(retry_delay(&error, 0), None);
+    }
+
+    #[test]
+    fn retries_rate_limits_only_with_requested_delay() {
+        let body = "API rate limit exceeded for 127.0.0.1";
+        let error = response_error(403, body, Some(Duration::from_secs(1)));
+        assert_eq!(retry_delay(&error, 0), Some(Duration::from_secs(1)));
+        assert_eq!(retry_delay(&response_error(403, body, None), 0), None);
+    }
+
+    // parse_retry_after
+
+    #[test]
+    fn retry_after_in_seconds() {
+        let response = response_with_headers(&[("retry-after", " 120 ")]);
+        assert_eq!(
+            parse_retry_after(&response, SystemTime::now()),
+            Some(Duration::from_mins(2))
+        );
+    }
+
+    #[test]
+    fn retry_after_as_http_date() {
+        let response = response_with_headers(&[("retry-after", "Sun, 06 Nov 1994 08:49:37 GMT")]);
+        let now = UNIX_EPOCH + Duration::from_secs(DATE_SECS - 30);
+        assert_eq!(
+            parse_retry_after(&response, now),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn retry_after_as_past_http_date() {
+        let response = response_with_headers(&[("retry-after", "Sun, 06 Nov 1994 08:49:37 GMT")]);
+        let now = UNIX_EPOCH + Duration::from_secs(DATE_SECS + 30);
+        assert_eq!(parse_retry_after(&response, now), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_invalid() {
+        let response = response_with_headers(&[("retry-after", "soon")]);
+        assert_eq!(parse_retry_after(&response, SystemTime::now()), None);
+    }
+
+    #[test]
+    fn retry_after_from_github_rate_limit_reset() {
+        let reset = (DATE_SECS + 45).to_string();
+        let now = UNIX_EPOCH + Duration::from_secs(DATE_SECS);
+        let exhausted = response_with_headers(&[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", &reset),
+        ]);
+        assert_eq!(
+            parse_retry_after(&exhausted, now),
+            Some(Duration::from_secs(45))
+        );
+
+        let remaining = response_with_headers(&[
+            ("x-ratelimit-remaining", "12"),
+            ("x-ratelimit-reset", &reset),
+        ]);
+        assert_eq!(parse_retry_after(&remaining, now), None);
+    }
+
+    #[test]
+    fn retry_after_missing() {
+        let response = response_with_headers(&[]);
+        assert_eq!(parse_retry_after(&response, SystemTime::now()), None);
+    }
+
+    // parse_http_date
+
+    #[test]
+    fn http_date() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(DATE_SECS))
+        );
+        assert_eq!(
+            parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT"),
+            Some(UNIX_EPOCH)
+        );
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 12:00:01 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_208_001))
+        );
+    }
+
+    #[test]
+    fn http_date_invalid() {
+        assert_eq!(parse_http_date(""), None);
+        assert_eq!(parse_http_date("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 25:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 PST"), None);
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun Nov  6 08:49:37 1994"), None);
     }
 }
@@ -27,7 +27,7 @@ impl WallyClient {
 
         self.cache
             .index_configs
-            .with_caching(format!("{owner}/{repo}"), fut)
+            .with_revalidation(format!("{owner}/{repo}"), fut)
             .await
     }
 
@@ -114,6 +114,49 @@ pub fn find_all_dependencies(doc: &Document) -> Vec<TsNode<'_>> {
     deps
 }
 
+/**
+    Finds all dependencies in `[workspace.dependencies]`, either as pairs
+    in the table itself, or as their own `[workspace.dependencies.name]` tables.
+*/
+#[must_use]
+pub fn find_workspace_dependencies(doc: &Document) -> Vec<TsNode<'_>> {
+    find_all_dependencies(doc)
+        .into_iter()
+        .filter(|dep| {
+            let table = if dep.kind() == "pair" {
+                dep.parent()
+            } else {
+                Some(*dep)
+            };
+            table.is_some_and(|t| {
+                table_key_parts(doc, t)
+                    .first()
+                    .is_some_and(|p| p == "workspace")
+            })
+        })
+        .collect()
+}
+
+/**
+    Finds all tables that contain dependencies, such as `[dependencies]` or
+    `[target."xx-yy-zz".dev-dependencies]`, as well as tables that contain
+    a single dependency, such as `[dependencies.name]`.
+*/
+#[must_use]
+pub fn find_all_dependency_sections(doc: &Document) -> Vec<TsNode<'_>> {
+    let Some(root) = doc.node_at_root() else {
+        return Vec::new();
+    };
+
+    let mut cursor = root.walk();
+    root.children(&mut cursor)
+        .filter(|top_level| {
+            check_dependencies_table_multi(doc, *top_level).is_some()
+                || check_dependencies_table_single(doc, *top_level).is_some()
+        })
+        .collect()
+}
+
 #[must_use]
 pub fn find_dependency_at(doc: &Document, pos: Position) -> Option<TsNode<'_>> {
     let node = doc.node_at_position(pos)?; // either the key or value
@@ -143,12 +186,40 @@ pub fn parse_dependency<'tree>(
         let mut name = pair_or_table.named_child(0)?;
         let value = pair_or_table.named_child(1)?;
 
+        // serde.workspace = true
+        if name.kind() == "dotted_key" {
+            let key = name.named_child(0)?;
+            let field = name.named_child(1)?;
+            if !matches!(key.kind(), "bare_key" | "quoted_key")
+                || unquote(doc.node_text(field)) != "workspace"
+            {
+                return None;
+            }
+            return Some(CargoDependency {
+                name: key,
+                version: None,
+                features: None,
+                path: None,
+                git: None,
+                branch: None,
+                tag: None,
+                rev: None,
+                registry: None,
+                workspace: Some(value),
+            });
+        }
+
         // version is either `name = "version"` or `name = { version = "version" }`
         let mut version = None;
         let mut features = None;
         let mut package = None;
         let mut path = None;
         let mut git = None;
+        let mut branch = None;
+        let mut tag = None;
+        let mut rev = None;
+        let mut registry = None;
+        let mut workspace = None;
         if value.kind() == "string" {
             version = Some(value);
         } else if value.kind() == "inline_table" {
@@ -166,6 +237,11 @@ pub fn parse_dependency<'tree>(
             package = pairs.remove("package");
             path = pairs.remove("path");
             git = pairs.remove("git");
+            branch = pairs.remove("branch");
+            tag = pairs.remove("tag");
+            rev = pairs.remove("rev");
+            registry = pairs.remove("registry");
+            workspace = pairs.remove("workspace");
         }
 
         // aliased_serde = { package = "serde" }
@@ -173,7 +249,7 @@ pub fn parse_dependency<'tree>(
             name = package;
         }
 
-        if version.is_none() && path.is_none() && git.is_none() {
+        if version.is_none() && path.is_none() && git.is_none() && workspace.is_none() {
             return None; // Not a valid package
         }
 
@@ -183,6 +259,11 @@ pub fn parse_dependency<'tree>(
             features,
             path,
             git,
+            branch,
+            tag,
+            rev,
+            registry,
+            workspace,
         })
     } else if pair_or_table.kind() == "table" {
         // alias is last part in [dependencies."abcdef"."ghijkl".name]
@@ -204,6 +285,11 @@ pub fn parse_dependency<'tree>(
         let package = pairs.remove("package");
         let path = pairs.remove("path");
         let git = pairs.remove("git");
+        let branch = pairs.remove("branch");
+        let tag = pairs.remove("tag");
+        let rev = pairs.remove("rev");
+        let registry = pairs.remove("registry");
+        let workspace = pairs.remove("workspace");
 
         // [dependencies.aliased_serde]
         // package = "serde"
@@ -211,7 +297,7 @@ pub fn parse_dependency<'tree>(
             name = package;
         }
 
-        if version.is_none() && path.is_none() && git.is_none() {
+        if version.is_none() && path.is_none() && git.is_none() && workspace.is_none() {
             return None; // Not a valid package
         }
 
@@ -221,12 +307,38 @@ pub fn parse_dependency<'tree>(
             features,
             path,
             git,
+            branch,
+            tag,
+            rev,
+            registry,
+            workspace,
         })
     } else {
         None
     }
 }
 
+/**
+    The kind of reference used for a git dependency.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GitReferenceKind {
+    Branch,
+    Tag,
+    Rev,
+}
+
+impl GitReferenceKind {
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Branch => "branch",
+            Self::Tag => "tag",
+            Self::Rev => "rev",
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 pub struct CargoDependency<'tree> {
@@ -235,6 +347,11 @@ pub struct CargoDependency<'tree> {
     pub features: Option<TsNode<'tree>>,
     pub path: Option<TsNode<'tree>>,
     pub git: Option<TsNode<'tree>>,
+    pub branch: Option<TsNode<'tree>>,
+    pub tag: Option<TsNode<'tree>>,
+    pub rev: Option<TsNode<'tree>>,
+    pub registry: Option<TsNode<'tree>>,
+    pub workspace: Option<TsNode<'tree>>,
 }
 
 impl CargoDependency<'_> {
@@ -255,6 +372,31 @@ impl CargoDependency<'_> {
         self.git.map(|g| unquote(doc.node_text(g)))
     }
 
+    /**
+        Returns the kind of git reference and the node for it, if this git dependency
+        specifies a `branch`, `tag` or `rev`, in that order - Cargo does not allow more than one.
+    */
+    #[must_use]
+    pub fn git_reference(&self) -> Option<(GitReferenceKind, TsNode<'_>)> {
+        self.branch
+            .map(|node| (GitReferenceKind::Branch, node))
+            .or(self.tag.map(|node| (GitReferenceKind::Tag, node)))
+            .or(self.rev.map(|node| (GitReferenceKind::Rev, node)))
+    }
+
+    #[must_use]
+    pub fn registry_text(&self, doc: &Document) -> Option<String> {
+        self.registry.map(|r| unquote(doc.node_text(r)))
+    }
+
+    /**
+        Checks if this dependency is inherited from the workspace, using `workspace = true`.
+    */
+    #[must_use]
+    pub fn is_workspace(&self, doc: &Document) -> bool {
+        self.workspace.is_some_and(|w| doc.node_text(w) == "true")
+    }
+
     #[must_use]
     pub fn feature_nodes(&self) -> Vec<TsNode<'_>> {
         let mut nodes = Vec::new();
@@ -269,3 +411,245 @@ impl CargoDependency<'_> {
         nodes
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use async_language_server::{lsp_types::Url, server::DocumentMatcher};
+
+    use super::*;
+
+    fn document(text: &str) -> Document {
+        let matcher = DocumentMatcher::new("Cargo")
+            .with_url_globs(["**/Cargo.toml"])
+            .with_lang_grammar(crate::TOML_LANGUAGE.into());
+        let url = Url::parse("file:///project/Cargo.toml").unwrap();
+        Document::from_matcher(&matcher, url, text)
+    }
+
+    fn dependencies(doc: &Document) -> Vec<CargoDependency<'_>> {
+        find_all_dependencies(doc)
+            .into_iter()
+            .filter_map(|node| parse_dependency(doc, node))
+            .collect()
+    }
+
+    fn names(doc: &Document, nodes: &[TsNode<'_>]) -> Vec<String> {
+        nodes
+            .iter()
+            .filter_map(|node| parse_dependency(doc, *node))
+            .map(|dep| dep.text(doc).0)
+            .collect()
+    }
+
+    // parse_dependency
+
+    #[test]
+    fn parses_version_strings_and_inline_tables() {
+        let doc = document(
+            r#"
+[dependencies]
+serde = "1.0"
+tokio = { version = "1.45", features = ["rt", "macros"] }
+rand_alias = { package = "rand", version = "0.8" }
+local = { path = "../local" }
+invalid = { features = ["std"] }
+"#,
+        );
+        let deps = dependencies(&doc);
+        let texts = deps.iter().map(|dep| dep.text(&doc)).collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            [
+                ("serde".to_string(), Some("1.0".to_string())),
+                ("tokio".to_string(), Some("1.45".to_string())),
+                ("rand".to_string(), Some("0.8".to_string())),
+                ("local".to_string(), None),
+            ]
+        );
+        assert_eq!(deps[1].feature_nodes().len(), 2);
+        assert_eq!(deps[3].path_text(&doc).as_deref(), Some("../local"));
+    }
+
+    #[test]
+    fn parses_dependency_tables() {
+        let doc = document(
+            r#"
+[dependencies.serde]
+version = "1.0"
+features = ["derive"]
+
+[dev-dependencies.rand_alias]
+package = "rand"
+version = "0.8"
+
+[target.'cfg(unix)'.dependencies.libc]
+version = "0.2"
+"#,
+        );
+        let deps = dependencies(&doc);
+        let texts = deps.iter().map(|dep| dep.text(&doc)).collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            [
+                ("serde".to_string(), Some("1.0".to_string())),
+                ("rand".to_string(), Some("0.8".to_string())),
+                ("libc".to_string(), Some("0.2".to_string())),
+            ]
+        );
+        assert_eq!(deps[0].feature_nodes().len(), 1);
+    }
+
+    #[test]
+    fn parses_git_references() {
+        let doc = document(
+            r#"
+[dependencies]
+on_branch = { git = "https://github.com/a/b", branch = "next" }
+on_tag = { git = "https://github.com/a/b", tag = "v1.0.0" }
+on_rev = { git = "https://github.com/a/b", rev = "0123abc" }
+on_default = { git = "https://github.com/a/b" }
+"#,
+        );
+        let references = dependencies(&doc)
+            .iter()
+            .map(|dep| {
+                assert_eq!(
+                    dep.git_text(&doc).as_deref(),
+                    Some("https://github.com/a/b")
+                );
+                dep.git_reference()
+                    .map(|(kind, node)| (kind, unquote(doc.node_text(node))))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            references,
+            [
+                Some((GitReferenceKind::Branch, "next".to_string())),
+                Some((GitReferenceKind::Tag, "v1.0.0".to_string())),
+                Some((GitReferenceKind::Rev, "0123abc".to_string())),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_registries() {
+        let doc = document(
+            r#"
+[dependencies]
+private = { version = "1.0", registry = "my-registry" }
+public = "1.0"
+"#,
+        );
+        let registries = dependencies(&doc)
+            .iter()
+            .map(|dep| dep.registry_text(&doc))
+            .collect::<Vec<_>>();
+        assert_eq!(registries, [Some("my-registry".to_string()), None]);
+    }
+
+    #[test]
+    fn parses_workspace_dependencies() {
+        let doc = document(
+            r#"
+[dependencies]
+serde = { workspace = true, features = ["derive"] }
+rand.workspace = true
+tokio.features = ["rt"]
+local = { workspace = false, path = "../local" }
+
+[dependencies.log]
+workspace = true
+"#,
+        );
+        let deps = dependencies(&doc);
+        let workspace = deps
+            .iter()
+            .map(|dep| (dep.text(&doc).0, dep.is_workspace(&doc)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            workspace,
+            [
+                ("serde".to_string(), true),
+                ("rand".to_string(), true),
+                ("local".to_string(), false),
+                ("log".to_string(), true),
+            ]
+        );
+        assert_eq!(deps[0].feature_nodes().len(), 1);
+    }
+
+    // find_workspace_dependencies
+
+    #[test]
+    fn finds_workspace_dependencies() {
+        let doc = document(
+            r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.dependencies]
+serde = "1.0"
+tokio = { version = "1.45" }
+
+[workspace.dependencies.rand]
+version = "0.8"
+
+[dependencies]
+log = "0.4"
+"#,
+        );
+        assert_eq!(
+            names(&doc, &find_workspace_dependencies(&doc)),
+            ["serde", "tokio", "rand"]
+        );
+        assert_eq!(
+            names(&doc, &find_all_dependencies(&doc)),
+            ["serde", "tokio", "rand", "log"]
+        );
+    }
+
+    // find_all_dependency_sections
+
+    #[test]
+    fn finds_all_dependency_sections() {
+        let doc = document(
+            r#"
+[package]
+name = "example"
+
+[dependencies]
+serde = "1.0"
+
+[dev-dependencies]
+rand = "0.8"
+
+[build-dependencies.cc]
+version = "1.0"
+
+[target.'cfg(windows)'.dependencies]
+winapi = "0.3"
+
+[workspace.dependencies]
+log = "0.4"
+
+[features]
+default = []
+"#,
+        );
+        let sections = find_all_dependency_sections(&doc)
+            .into_iter()
+            .map(|node| table_key_parts(&doc, node).join("."))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sections,
+            [
+                "dependencies",
+                "dev-dependencies",
+                "build-dependencies.cc",
+                "target.cfg(windows).dependencies",
+                "workspace.dependencies",
+            ]
+        );
+    }
+}
@@ -31,6 +31,22 @@ pub fn find_all_dependencies(doc: &Document) -> Vec<TsNode<'_>> {
     deps
 }
 
+/**
+    Finds all `require` directives, both single
+    line directives and `require ( ... )` blocks.
+*/
+#[must_use]
+pub fn find_all_dependency_sections(doc: &Document) -> Vec<TsNode<'_>> {
+    let Some(root) = doc.node_at_root() else {
+        return Vec::new();
+    };
+
+    let mut cursor = root.walk();
+    root.children(&mut cursor)
+        .filter(|top_level| is_require_directive(top_level.kind()))
+        .collect()
+}
+
 #[must_use]
 pub fn find_dependency_at(doc: &Document, pos: Position) -> Option<TsNode<'_>> {
     let node = doc.node_at_position(pos)?;
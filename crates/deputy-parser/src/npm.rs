@@ -64,6 +64,31 @@ pub fn find_all_dependencies(doc: &Document) -> Vec<TsNode<'_>> {
     deps
 }
 
+/**
+    Finds all pairs that contain dependencies, such as `"devDependencies": { ... }`.
+*/
+#[allow(clippy::missing_panics_doc)]
+#[must_use]
+pub fn find_all_dependency_sections(doc: &Document) -> Vec<TsNode<'_>> {
+    let Some(root) = doc.node_at_root() else {
+        return Vec::new();
+    };
+    let Some(root) = root.named_child(0) else {
+        return Vec::new();
+    };
+
+    let mut cursor = root.walk();
+    root.children(&mut cursor)
+        .filter(|top_level| {
+            if top_level.kind() != "pair" {
+                return false;
+            }
+            let key = top_level.child_by_field_name("key").expect("valid pair");
+            DependencyKind::from_str(&unquote(doc.node_text(key))).is_ok()
+        })
+        .collect()
+}
+
 #[allow(clippy::missing_panics_doc)]
 #[must_use]
 pub fn find_dependency_at(doc: &Document, pos: Position) -> Option<TsNode<'_>> {
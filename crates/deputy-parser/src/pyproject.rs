@@ -87,6 +87,42 @@ pub fn find_all_dependencies(doc: &Document) -> Vec<TsNode<'_>> {
     deps
 }
 
+/**
+    Finds all pairs that contain arrays of dependencies, such as
+    `dependencies = [ ... ]` in the `[project]` table, or each
+    group in the `[dependency-groups]` table.
+*/
+#[must_use]
+pub fn find_all_dependency_sections(doc: &Document) -> Vec<TsNode<'_>> {
+    let Some(root) = doc.node_at_root() else {
+        return Vec::new();
+    };
+
+    let mut cursor = root.walk();
+    let mut sections = Vec::new();
+
+    for top_level in root.children(&mut cursor) {
+        let mut table_cursor = top_level.walk();
+        for child in top_level.children(&mut table_cursor) {
+            if child.kind() != "pair" {
+                continue;
+            }
+
+            let Some(key) = child.named_child(0) else {
+                continue;
+            };
+            let is_array = child.named_child(1).is_some_and(|v| v.kind() == "array");
+
+            let key_text = unquote(doc.node_text(key));
+            if is_array && check_dependencies(doc, top_level, &key_text).is_some() {
+                sections.push(child);
+            }
+        }
+    }
+
+    sections
+}
+
 #[must_use]
 pub fn find_dependency_at(doc: &Document, pos: Position) -> Option<TsNode<'_>> {
     let node = doc.node_at_position(pos)?;
@@ -49,6 +49,23 @@ pub(super) fn find_all_dependencies(doc: &Document, table_names: TableNames) ->
     deps
 }
 
+pub(super) fn find_all_dependency_sections(
+    doc: &Document,
+    table_names: TableNames,
+) -> Vec<TsNode<'_>> {
+    let Some(root) = doc.node_at_root() else {
+        return Vec::new();
+    };
+
+    let mut cursor = root.walk();
+    root.children(&mut cursor)
+        .filter(|top_level| {
+            find_child(*top_level, |c| c.kind() == "bare_key")
+                .is_some_and(|key| check_table_name(table_names, doc.node_text(key).as_str()))
+        })
+        .collect()
+}
+
 pub(super) fn find_dependency_at(
     doc: &Document,
     pos: Position,
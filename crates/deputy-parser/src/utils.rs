@@ -26,15 +26,23 @@ pub fn table_key_parts(doc: &Document, node: TsNode) -> Vec<String> {
             parts.push(unquote(doc.node_text(key)));
         } else if key.kind() == "dotted_key" {
             // [workspace.dependencies] etc
-            let mut cursor = key.walk();
-            for child in key.children(&mut cursor) {
-                if child.kind() == "bare_key" {
-                    parts.push(doc.node_text(child).clone());
-                } else if child.kind() == "quoted_key" {
-                    parts.push(unquote(doc.node_text(child)));
-                }
-            }
+            push_dotted_key_parts(doc, key, &mut parts);
         }
     }
     parts
 }
+
+fn push_dotted_key_parts(doc: &Document, key: TsNode, parts: &mut Vec<String>) {
+    // Keys with more than two parts are nested, such as [target."xx-yy-zz".dependencies]
+    // being parsed as a dotted key for `target."xx-yy-zz"` followed by `dependencies`
+    let mut cursor = key.walk();
+    for child in key.children(&mut cursor) {
+        if child.kind() == "dotted_key" {
+            push_dotted_key_parts(doc, child, parts);
+        } else if child.kind() == "bare_key" {
+            parts.push(doc.node_text(child).clone());
+        } else if child.kind() == "quoted_key" {
+            parts.push(unquote(doc.node_text(child)));
+        }
+    }
+}
@@ -10,13 +10,13 @@ use async_language_server::{
 };
 use tracing::debug;
 
-use deputy_clients::Clients;
+use deputy_clients::{Clients, crates::models::CrateRegistry};
 use deputy_parser::{cargo, utils::unquote};
 use deputy_versioning::Versioned;
 
 use crate::cargo::{
     constants::CratesIoPackage,
-    util::{get_features, get_local_metadata},
+    util::{get_dependency_spec, get_features, get_local_metadata, get_registry},
 };
 
 use super::constants::top_crates_io_packages_prefixed;
@@ -53,8 +53,15 @@ pub async fn get_cargo_completions(
     {
         let version = version.as_deref().unwrap_or_default();
         debug!("Completing version: {dep:?}");
+        let Some(spec) = get_dependency_spec(clients, doc, &dep).await else {
+            return Ok(None);
+        };
+        let Some(registry) = get_registry(clients, doc, &spec).await else {
+            return Ok(None);
+        };
         return complete_version(
             clients,
+            &registry,
             name.as_str(),
             version,
             ts_range_to_lsp_range(version_node.range()),
@@ -68,12 +75,20 @@ pub async fn get_cargo_completions(
         if ts_range_contains_lsp_position(feat_node.range(), pos) {
             debug!("Completing features: {dep:?}");
 
-            let known_features = if let Some(path) = dep.path_text(doc) {
-                get_local_metadata(clients, doc.url(), &path)
+            // Features of workspace dependencies are resolved
+            // using the version and source from the workspace
+            let spec = get_dependency_spec(clients, doc, &dep).await;
+            let known_features = if let Some(spec) = &spec
+                && let Some(path) = &spec.path
+            {
+                get_local_metadata(clients, doc.url(), path)
                     .await
                     .map(|m| m.features)
-            } else if let Some(version) = &version {
-                get_features(clients, &name, version).await
+            } else if let Some(spec) = &spec
+                && let Some(version) = &spec.version
+                && let Some(registry) = get_registry(clients, doc, spec).await
+            {
+                get_features(clients, &registry, &spec.name, version).await
             } else {
                 None
             };
@@ -142,11 +157,16 @@ async fn complete_name(
 
 async fn complete_version(
     clients: &Clients,
+    registry: &CrateRegistry,
     name: &str,
     version: &str,
     range: Range,
 ) -> ServerResult<Option<CompletionResponse>> {
-    let Ok(metadatas) = clients.crates.get_sparse_index_crate_metadatas(name).await else {
+    let Ok(metadatas) = clients
+        .crates
+        .get_registry_crate_metadatas(registry, name)
+        .await
+    else {
         return Ok(None);
     };
 
@@ -0,0 +1,97 @@
+use std::path::Path;
+
+use async_language_server::{
+    lsp_types::{GotoDefinitionResponse, Position},
+    server::{Document, ServerResult},
+    tree_sitter::Node,
+    tree_sitter_utils::ts_range_contains_lsp_position,
+};
+use tracing::debug;
+
+use deputy_parser::cargo;
+use deputy_versioning::VersionReq;
+
+use crate::shared::file_definition;
+
+use super::Clients;
+use super::util::{
+    get_dependency_spec, get_local_manifest_path, get_locked_versions,
+    get_workspace_dependency_line,
+};
+
+pub async fn get_cargo_definition(
+    clients: &Clients,
+    doc: &Document,
+    pos: Position,
+    node: Node<'_>,
+) -> ServerResult<Option<GotoDefinitionResponse>> {
+    let Some(dep) = cargo::parse_dependency(doc, node) else {
+        return Ok(None);
+    };
+    if !ts_range_contains_lsp_position(dep.name.range(), pos) {
+        return Ok(None);
+    }
+
+    // Workspace dependencies go to their entry in the workspace root
+    if dep.is_workspace(doc) {
+        let Some(doc_dir) = doc
+            .url()
+            .to_file_path()
+            .ok()
+            .and_then(|p| p.parent().map(Path::to_path_buf))
+        else {
+            return Ok(None);
+        };
+        let Some(workspace) = clients.crates.get_workspace(&doc_dir).await else {
+            return Ok(None);
+        };
+        let (name, _) = dep.text(doc);
+        let Some(line) = get_workspace_dependency_line(&workspace, &name) else {
+            debug!("No entry for '{name}' found in the workspace root manifest");
+            return Ok(None);
+        };
+        return Ok(file_definition(&workspace.manifest_path, line));
+    }
+
+    let Some(spec) = get_dependency_spec(clients, doc, &dep).await else {
+        return Ok(None);
+    };
+
+    // Path dependencies go to the manifest of the local crate
+    if let Some(path) = &spec.path {
+        return Ok(get_local_manifest_path(doc.url(), path)
+            .and_then(|manifest_path| file_definition(&manifest_path, 0)));
+    }
+
+    // Git dependencies are checked out elsewhere, and not supported
+    if spec.git.is_some() {
+        return Ok(None);
+    }
+
+    // Registry dependencies go to the unpacked sources that Cargo downloaded,
+    // preferring the locked version, and otherwise the newest matching version
+    let Some(version_req) = spec
+        .version
+        .as_deref()
+        .and_then(|v| VersionReq::parse(v).ok())
+    else {
+        return Ok(None);
+    };
+    let locked = get_locked_versions(clients, doc, &spec.name, &version_req).await;
+
+    let mut unpacked = clients.crates.get_unpacked_crates(&spec.name).await;
+    unpacked.retain(|(version, _)| version_req.matches(version));
+    unpacked.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    let found = unpacked
+        .iter()
+        .find(|(version, _)| locked.contains(version))
+        .or_else(|| unpacked.first());
+    let Some((version, manifest_path)) = found else {
+        debug!("No unpacked sources found for '{}'", spec.name);
+        return Ok(None);
+    };
+
+    debug!("Found sources for '{}' version {version}", spec.name);
+    Ok(file_definition(manifest_path, 0))
+}
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use async_language_server::{
     lsp_types::{Diagnostic, DiagnosticSeverity},
     server::{Document, ServerResult},
@@ -7,31 +9,54 @@ use async_language_server::{
 };
 use tracing::debug;
 
-use deputy_clients::crates::models::{IndexMetadata, LocalMetadata};
+use deputy_clients::{
+    crates::models::{IndexMetadata, LocalMetadata},
+    git::models::GitRemote,
+    osv::models::AdvisoryEcosystem,
+};
 use deputy_parser::{
-    cargo::{self, CargoDependency},
+    cargo::{self, CargoDependency, GitReferenceKind},
     utils::unquote,
 };
-use deputy_versioning::{VersionReq, VersionReqExt, Versioned};
+use deputy_versioning::{Version, VersionReq, VersionReqExt, Versioned};
 
-use crate::shared::{CodeActionMetadata, ResolveContext, did_you_mean};
+use crate::settings::EcosystemSettings;
+use crate::shared::{
+    AdvisoryCheck, CodeActionMetadata, DiagnosticKind, ResolveContext, did_you_mean,
+    get_advisory_diagnostics,
+};
 
 use super::Clients;
-use super::util::{get_features, get_local_metadata};
+use super::util::{
+    get_dependency_spec, get_features, get_local_metadata, get_locked_versions, get_registry,
+};
 
 pub async fn get_cargo_diagnostics(
     clients: &Clients,
+    settings: &EcosystemSettings,
     doc: &Document,
     node: Node<'_>,
 ) -> ServerResult<Vec<Diagnostic>> {
     let Some(dep) = cargo::parse_dependency(doc, node) else {
         return Ok(Vec::new());
     };
+    if settings.is_ignored(&dep.text(doc).0) {
+        return Ok(Vec::new());
+    }
+
+    // Dependencies inherited from the workspace use the version
+    // and source of the matching entry in the workspace root
+    let Some(spec) = get_dependency_spec(clients, doc, &dep).await else {
+        if dep.is_workspace(doc) {
+            return Ok(get_cargo_diagnostics_workspace(clients, doc, &dep).await);
+        }
+        return Ok(Vec::new());
+    };
 
     // For path dependencies, check version and features
     // against the local crate instead of the crates.io registry
-    if let Some(path) = dep.path_text(doc) {
-        let Some(local_meta) = get_local_metadata(clients, doc.url(), &path).await else {
+    if let Some(path) = &spec.path {
+        let Some(local_meta) = get_local_metadata(clients, doc.url(), path).await else {
             return Ok(Vec::new());
         };
         let mut diagnostics = Vec::new();
@@ -44,19 +69,32 @@ pub async fn get_cargo_diagnostics(
         return Ok(diagnostics);
     }
 
-    // Git dependencies are not checked against crates.io
-    // FUTURE: Implement proper resolution for git dependencies?
-    if dep.git_text(doc).is_some() {
-        return Ok(Vec::new());
+    // Git dependencies are not checked against crates.io, but against the
+    // repository - which is only possible if the reference is in this document
+    if spec.git.is_some() {
+        if dep.git.is_none() {
+            return Ok(Vec::new());
+        }
+        return Ok(get_cargo_diagnostics_git(clients, doc, &dep).await);
     }
 
-    let (name, _) = dep.text(doc);
-    let metas = match clients.crates.get_sparse_index_crate_metadatas(&name).await {
+    let Some(registry) = get_registry(clients, doc, &spec).await else {
+        return Ok(Vec::new());
+    };
+
+    let name = &spec.name;
+    let metas = match clients
+        .crates
+        .get_registry_crate_metadatas(&registry, name)
+        .await
+    {
         Ok(v) => v,
         Err(e) => {
-            if e.is_not_found_error() {
+            // Unknown workspace dependencies are reported in the workspace root
+            if e.is_not_found_error() && !dep.is_workspace(doc) {
                 return Ok(vec![Diagnostic {
                     source: Some(String::from("Cargo")),
+                    code: Some(DiagnosticKind::UnknownPackage.into()),
                     range: ts_range_to_lsp_range(dep.name.range()),
                     message: format!("No package exists with the name `{name}`"),
                     severity: Some(DiagnosticSeverity::ERROR),
@@ -67,19 +105,205 @@ pub async fn get_cargo_diagnostics(
         }
     };
 
-    let (name, version) = dep.text(doc);
-    let Some(version) = version else {
+    let Some(version) = &spec.version else {
         return Ok(Vec::new());
     };
 
     let mut diagnostics = Vec::new();
-    diagnostics.extend(get_cargo_diagnostics_version(doc, &dep, &version, &metas));
-    if let Some(known_features) = get_features(clients, &name, &version).await {
+    if dep.version.is_some() {
+        let locked = match VersionReq::parse(version) {
+            Ok(version_req) => get_locked_versions(clients, doc, name, &version_req).await,
+            Err(_) => Vec::new(),
+        };
+        diagnostics.extend(get_cargo_diagnostics_version(
+            doc,
+            &dep,
+            version,
+            &metas,
+            locked.first(),
+        ));
+    }
+    if let Some(known_features) = get_features(clients, &registry, name, version).await {
         diagnostics.extend(get_cargo_diagnostics_features(doc, &dep, &known_features));
     }
+    if registry.is_crates_io() && dep.version.is_some() {
+        diagnostics.extend(get_cargo_diagnostics_advisories(clients, doc, &dep, version).await);
+    }
     Ok(diagnostics)
 }
 
+/**
+    Creates a diagnostic for a dependency that is inherited from
+    the workspace, but that the workspace root does not define.
+*/
+async fn get_cargo_diagnostics_workspace(
+    clients: &Clients,
+    doc: &Document,
+    dep: &CargoDependency<'_>,
+) -> Vec<Diagnostic> {
+    let Some(doc_dir) = doc
+        .url()
+        .to_file_path()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+    else {
+        return Vec::new();
+    };
+
+    let name = dep.text(doc).0;
+    let message = match clients.crates.get_workspace(&doc_dir).await {
+        Some(_) => format!("No dependency named `{name}` exists in `[workspace.dependencies]`"),
+        None => format!("`{name}` is inherited from a workspace, but no workspace was found"),
+    };
+
+    vec![Diagnostic {
+        source: Some(String::from("Cargo")),
+        code: Some(DiagnosticKind::UnknownPackage.into()),
+        range: ts_range_to_lsp_range(dep.name.range()),
+        message,
+        severity: Some(DiagnosticSeverity::ERROR),
+        ..Default::default()
+    }]
+}
+
+/**
+    Checks that the branch, tag or commit of a git dependency exists in the
+    repository, and suggests newer tags for dependencies pinned to a tag.
+*/
+async fn get_cargo_diagnostics_git(
+    clients: &Clients,
+    doc: &Document,
+    dep: &CargoDependency<'_>,
+) -> Vec<Diagnostic> {
+    // Without a branch, tag or rev, the default branch is used, which always exists
+    let Some((kind, reference_node)) = dep.git_reference() else {
+        return Vec::new();
+    };
+    let Some(remote) = dep.git_text(doc).as_deref().and_then(GitRemote::parse) else {
+        return Vec::new();
+    };
+    let Ok(refs) = clients.git.get_refs(&remote).await else {
+        return Vec::new();
+    };
+
+    let reference = unquote(doc.node_text(reference_node));
+    let exists = match kind {
+        GitReferenceKind::Branch => refs.branch(&reference).is_some(),
+        GitReferenceKind::Tag => refs.tag(&reference).is_some(),
+        GitReferenceKind::Rev => {
+            // Commits that are not the tip of any branch or tag can only be
+            // checked using the GitHub API, so we assume that they exist otherwise
+            refs.find_commit(&reference).is_some()
+                || clients
+                    .git
+                    .get_commit(&remote, &reference)
+                    .await
+                    .map_or_else(|e| !e.is_not_found_error(), |_| true)
+        }
+    };
+    if !exists {
+        let url = &remote.http_url;
+        return vec![Diagnostic {
+            source: Some(String::from("Cargo")),
+            code: Some(DiagnosticKind::UnknownVersion.into()),
+            range: ts_range_to_lsp_range(reference_node.range()),
+            message: match kind {
+                GitReferenceKind::Rev => {
+                    format!("No commit matching `{reference}` exists in `{url}`")
+                }
+                _ => format!("No {} named `{reference}` exists in `{url}`", kind.name()),
+            },
+            severity: Some(DiagnosticSeverity::ERROR),
+            ..Default::default()
+        }];
+    }
+
+    if kind != GitReferenceKind::Tag {
+        return Vec::new();
+    }
+
+    // Tags that look like versions can be compared to find newer ones,
+    // the same way as versions are, ignoring any newer prereleases
+    let Some(current) = refs.tag(&reference) else {
+        return Vec::new();
+    };
+    let Some(latest) = current.extract_latest_version(refs.tags().cloned()) else {
+        return Vec::new();
+    };
+    if latest.item_version <= latest.this_version {
+        return Vec::new();
+    }
+    let Some(latest_tag) = latest.item.tag_name() else {
+        return Vec::new();
+    };
+
+    let (name, _) = dep.text(doc);
+    let metadata = CodeActionMetadata::LatestVersion {
+        package_name: name.clone(),
+        edit_range: ts_range_to_lsp_range(reference_node.range().shrink(1, 1)),
+        source_uri: doc.url().clone(),
+        source_text: reference.clone(),
+        version_current: reference.clone(),
+        version_latest: latest_tag.to_string(),
+        version_compatible: None,
+    };
+
+    vec![Diagnostic {
+        source: Some(String::from("Cargo")),
+        code: Some(DiagnosticKind::Outdated.into()),
+        range: ts_range_to_lsp_range(reference_node.range()),
+        message: format!(
+            "A newer tag of `{name}` is available.\
+            \nThe latest tag is `{latest_tag}`"
+        ),
+        severity: Some(DiagnosticSeverity::INFORMATION),
+        data: Some(
+            ResolveContext {
+                uri: doc.url().clone(),
+                value: metadata,
+            }
+            .into(),
+        ),
+        ..Default::default()
+    }]
+}
+
+async fn get_cargo_diagnostics_advisories(
+    clients: &Clients,
+    doc: &Document,
+    dep: &CargoDependency<'_>,
+    version: &str,
+) -> Vec<Diagnostic> {
+    let (name, _) = dep.text(doc);
+    let version_node = dep.version.expect("version node must exist");
+
+    let Ok(version_req) = VersionReq::parse(version) else {
+        return Vec::new();
+    };
+    let version_min = version_req.minimum_version().to_string();
+    let locked_versions = get_locked_versions(clients, doc, &name, &version_req)
+        .await
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    get_advisory_diagnostics(
+        clients,
+        doc,
+        AdvisoryCheck {
+            ecosystem: AdvisoryEcosystem::CratesIo,
+            source: "Cargo",
+            package_name: &name,
+            version: &version_min,
+            locked_versions,
+            version_text: version,
+            range: ts_range_to_lsp_range(version_node.range()),
+            edit_range: ts_range_to_lsp_range(version_node.range().shrink(1, 1)),
+        },
+    )
+    .await
+}
+
 fn get_cargo_diagnostics_local_version(
     doc: &Document,
     dep: &CargoDependency<'_>,
@@ -102,6 +326,7 @@ fn get_cargo_diagnostics_local_version(
         let version_node = dep.version.expect("version node must exist");
         return vec![Diagnostic {
             source: Some(String::from("Cargo")),
+            code: Some(DiagnosticKind::UnknownVersion.into()),
             range: ts_range_to_lsp_range(version_node.range()),
             message: format!("No local version exists that matches requirement `{version}`"),
             severity: Some(DiagnosticSeverity::ERROR),
@@ -117,6 +342,7 @@ fn get_cargo_diagnostics_version(
     dep: &CargoDependency<'_>,
     version: &str,
     metas: &[IndexMetadata],
+    locked: Option<&Version>,
 ) -> Vec<Diagnostic> {
     let (name, _) = dep.text(doc);
     let version_node = dep.version.expect("version node must exist");
@@ -135,6 +361,7 @@ fn get_cargo_diagnostics_version(
     }) {
         return vec![Diagnostic {
             source: Some(String::from("Cargo")),
+            code: Some(DiagnosticKind::UnknownVersion.into()),
             range: ts_range_to_lsp_range(version_node.range()),
             message: format!("No version exists that matches requirement `{version}`"),
             severity: Some(DiagnosticSeverity::ERROR),
@@ -142,11 +369,14 @@ fn get_cargo_diagnostics_version(
         }];
     }
 
-    // Try to find the latest non-prerelease version, filtering out
-    // any version that has been yanked - unless we exactly specify it
+    // Try to find the latest non-prerelease versions, filtering out
+    // any version that has been yanked - unless we exactly specify it.
+    // The locked version is what is actually used, when there is one,
+    // so we compare against that instead of the minimum version
     let latest_name = name.clone();
-    let Some(latest_version) = version_min
-        .extract_latest_version_filtered(metas.iter().cloned(), |v| {
+    let version_used = locked.unwrap_or(&version_min);
+    let Some(latest_versions) = version_used
+        .extract_latest_versions_filtered(metas.iter().cloned(), |v| {
             !v.item.yanked || v.is_exactly_compatible
         })
     else {
@@ -154,37 +384,77 @@ fn get_cargo_diagnostics_version(
         return Vec::new();
     };
 
-    if !latest_version.is_semver_compatible {
-        let latest_version_string = latest_version.item_version.to_string();
-
-        let metadata = CodeActionMetadata::LatestVersion {
-            edit_range: ts_range_to_lsp_range(version_node.range().shrink(1, 1)),
-            source_uri: doc.url().clone(),
-            source_text: version.to_string(),
-            version_current: version_min.to_string(),
-            version_latest: latest_version_string.clone(),
-        };
+    let latest_version = &latest_versions.latest;
+    let latest_version_string = latest_version.item_version.to_string();
+    let compatible_version_string = latest_versions
+        .compatible_update()
+        .map(|v| v.item_version.to_string());
 
-        return vec![Diagnostic {
-            source: Some(String::from("Cargo")),
-            range: ts_range_to_lsp_range(version_node.range()),
-            message: format!(
+    // Newer versions may exist both outside of the current requirement (breaking
+    // changes) and within it (compatible) - we want to offer both as updates
+    let (message, severity, kind) = if !latest_version.is_semver_compatible {
+        let message = if let Some(compatible) = &compatible_version_string {
+            format!(
+                "A newer version of `{latest_name}` is available.\
+                \nThe latest version is `{latest_version_string}`\
+                \nThe latest compatible version is `{compatible}`"
+            )
+        } else {
+            format!(
                 "A newer version of `{latest_name}` is available.\
                 \nThe latest version is `{latest_version_string}`"
-            ),
-            severity: Some(DiagnosticSeverity::INFORMATION),
-            data: Some(
-                ResolveContext {
-                    uri: doc.url().clone(),
-                    value: metadata,
-                }
-                .into(),
-            ),
-            ..Default::default()
-        }];
+            )
+        };
+        (
+            message,
+            DiagnosticSeverity::INFORMATION,
+            DiagnosticKind::Outdated,
+        )
+    } else if latest_version.item_version > latest_version.this_version {
+        let message = format!(
+            "A newer compatible version of `{latest_name}` is available.\
+            \nThe latest compatible version is `{latest_version_string}`"
+        );
+        (
+            message,
+            DiagnosticSeverity::HINT,
+            DiagnosticKind::CompatibleUpdate,
+        )
+    } else {
+        return Vec::new();
+    };
+
+    let metadata = CodeActionMetadata::LatestVersion {
+        package_name: latest_name.clone(),
+        edit_range: ts_range_to_lsp_range(version_node.range().shrink(1, 1)),
+        source_uri: doc.url().clone(),
+        source_text: version.to_string(),
+        version_current: version_min.to_string(),
+        version_latest: latest_version_string,
+        version_compatible: compatible_version_string,
+    };
+
+    // Compatible updates are only worth a hint when they change the requirement,
+    // and not for requirements such as `1` that already allow the latest version
+    if kind == DiagnosticKind::CompatibleUpdate && metadata.text_edit().is_none() {
+        return Vec::new();
     }
 
-    Vec::new()
+    vec![Diagnostic {
+        source: Some(String::from("Cargo")),
+        code: Some(kind.into()),
+        range: ts_range_to_lsp_range(version_node.range()),
+        message,
+        severity: Some(severity),
+        data: Some(
+            ResolveContext {
+                uri: doc.url().clone(),
+                value: metadata,
+            }
+            .into(),
+        ),
+        ..Default::default()
+    }]
 }
 
 fn get_cargo_diagnostics_features(
@@ -198,6 +468,7 @@ fn get_cargo_diagnostics_features(
         if !known_features.contains(&feat) {
             diagnostics.push(Diagnostic {
                 source: Some(String::from("Cargo")),
+                code: Some(DiagnosticKind::UnknownFeature.into()),
                 range: ts_range_to_lsp_range(feat_node.range()),
                 message: match did_you_mean(&feat, known_features) {
                     Some(suggestion) => {
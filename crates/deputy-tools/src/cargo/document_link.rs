@@ -0,0 +1,58 @@
+use async_language_server::{
+    lsp_types::DocumentLink, server::Document, tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_parser::cargo;
+
+use crate::shared::{ProjectLink, ProjectLinks, document_link, lazy_document_link};
+
+use super::Clients;
+use super::util::{get_dependency_spec, get_registry};
+
+pub async fn get_cargo_document_link(
+    clients: &Clients,
+    doc: &Document,
+    node: Node<'_>,
+) -> Option<DocumentLink> {
+    let dep = cargo::parse_dependency(doc, node)?;
+    let range = ts_range_to_lsp_range(dep.name.range());
+
+    let spec = get_dependency_spec(clients, doc, &dep).await?;
+    if spec.path.is_some() {
+        return None; // Local crates are reachable using go to definition
+    }
+    if let Some(git) = &spec.git {
+        if !git.starts_with("https://") && !git.starts_with("http://") {
+            return None;
+        }
+        let link = ProjectLink::new("Repository", git.trim_end_matches(".git"));
+        return document_link(range, &link, &spec.name);
+    }
+
+    // Only crates.io has documentation and links that we know how to find
+    let registry = get_registry(clients, doc, &spec).await?;
+    if !registry.is_crates_io() {
+        return None;
+    }
+
+    Some(lazy_document_link(doc.url(), range, spec.name))
+}
+
+pub async fn resolve_cargo_document_link(clients: &Clients, name: &str) -> Option<ProjectLink> {
+    // docs.rs has documentation for every crate, even if the crate does not link to it
+    let docs_rs = format!("https://docs.rs/{name}");
+    let links = match clients.crates.get_crate_data(name).await {
+        Ok(crate_data) => ProjectLinks::new(
+            crate_data.inner.links.documentation.as_deref(),
+            crate_data.inner.links.repository.as_deref(),
+            crate_data.inner.links.homepage.as_deref(),
+        ),
+        Err(_) => ProjectLinks::default(),
+    };
+    ProjectLinks {
+        documentation: links.documentation.clone().or(Some(docs_rs)),
+        ..links
+    }
+    .primary()
+}
@@ -6,9 +6,13 @@ use async_language_server::{
 };
 use tracing::trace;
 
+use deputy_clients::git::models::GitRemote;
 use deputy_parser::cargo;
+use deputy_versioning::VersionReq;
 
-use crate::shared::MarkdownBuilder;
+use crate::shared::{MarkdownBuilder, ProjectLinks};
+
+use super::util::{get_dependency_spec, get_latest_git_commit, get_locked_versions, get_registry};
 
 use super::Clients;
 
@@ -21,19 +25,56 @@ pub async fn get_cargo_hover(
         return Ok(None);
     };
 
-    let (dependency_name, dependency_version) = dep.text(doc);
+    let Some(spec) = get_dependency_spec(clients, doc, &dep).await else {
+        return Ok(None);
+    };
+    let dependency_name = &spec.name;
 
     // Add basic hover information with version and name
-    trace!("Hovering: {dependency_name} version {dependency_version:?}");
+    trace!("Hovering: {dependency_name} version {:?}", spec.version);
     let mut md = MarkdownBuilder::new();
-    md.h2(&dependency_name);
-    if let Some(version) = &dependency_version {
+    md.h2(dependency_name);
+    if let Some(version) = &spec.version {
         md.version(version);
+        if let Ok(version_req) = VersionReq::parse(version) {
+            md.locked(get_locked_versions(clients, doc, dependency_name, &version_req).await);
+        }
+        if dep.is_workspace(doc) {
+            md.extend_last(" (inherited from the workspace)");
+        }
+    }
+
+    // Git dependencies show their repository and its latest commit instead
+    if let Some(remote) = spec.git.as_deref().and_then(GitRemote::parse) {
+        md.br();
+        md.h3("Git");
+        md.p(format!("- Repository: <{}>", remote.http_url));
+        if let Some((kind, reference)) = &spec.git_reference {
+            md.p(format!("- Using {} `{reference}`", kind.name()));
+        }
+        if let Some((branch, commit)) = get_latest_git_commit(clients, &remote, &spec).await {
+            md.p(format!(
+                "- Latest commit on `{branch}`: `{}`",
+                commit.short_sha()
+            ));
+            if let Some(summary) = &commit.summary {
+                md.extend_last(format!(" {summary}"));
+            }
+            if let Some(date) = &commit.date {
+                // Dates are in ISO 8601 format, and the time is not very useful here
+                md.extend_last(format!(" ({})", date.get(..10).unwrap_or(date)));
+            }
+        }
     }
 
-    // Skip crates.io lookup for git and path dependencies
-    // FUTURE: Implement resolution for git and path dependencies?
-    if dep.git_text(doc).is_some() || dep.path_text(doc).is_some() {
+    // Skip crates.io lookup for git, path, and alternate registry dependencies
+    // FUTURE: Implement resolution for path dependencies?
+    let is_crates_io = spec.git.is_none()
+        && spec.path.is_none()
+        && get_registry(clients, doc, &spec)
+            .await
+            .is_some_and(|r| r.is_crates_io());
+    if !is_crates_io {
         return Ok(Some(Hover {
             range: Some(ts_range_to_lsp_range(node.range())),
             contents: HoverContents::Markup(MarkupContent {
@@ -47,7 +88,7 @@ pub async fn get_cargo_hover(
     trace!("Fetching crate data from crates.io");
     if let Ok(crate_data) = clients
         .crates
-        .get_crate_data(&dependency_name)
+        .get_crate_data(dependency_name)
         .await
         .map(|c| c.inner)
     {
@@ -55,15 +96,14 @@ pub async fn get_cargo_hover(
         md.p(crate_data.description);
 
         // Ignore homepage or docs if it's the same as the repo
-        let mut docs = crate_data.links.documentation.as_deref();
-        let mut page = crate_data.links.homepage.as_deref();
-        let repo = crate_data.links.repository.as_deref();
-        if page == repo {
-            page = None;
-        }
-        if docs == repo {
-            docs = None;
-        }
+        let links = ProjectLinks::new(
+            crate_data.links.documentation.as_deref(),
+            crate_data.links.repository.as_deref(),
+            crate_data.links.homepage.as_deref(),
+        );
+        let docs = links.documentation.as_deref();
+        let page = links.homepage.as_deref();
+        let repo = links.repository.as_deref();
 
         // Add links to documentation, repo, and homepage
         let docs_rs = format!("https://docs.rs/{}", crate_data.name);
@@ -0,0 +1,49 @@
+use async_language_server::{
+    lsp_types::InlayHint, server::Document, tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_parser::cargo;
+use deputy_versioning::{VersionReq, VersionReqExt, Versioned};
+
+use crate::shared::version_inlay_hint;
+
+use super::Clients;
+use super::util::{get_dependency_spec, get_locked_versions, get_registry};
+
+pub async fn get_cargo_inlay_hint(
+    clients: &Clients,
+    doc: &Document,
+    node: Node<'_>,
+) -> Option<InlayHint> {
+    let dep = cargo::parse_dependency(doc, node)?;
+    let spec = get_dependency_spec(clients, doc, &dep).await?;
+    if spec.path.is_some() || spec.git.is_some() {
+        return None;
+    }
+
+    let name = &spec.name;
+    let version_req = VersionReq::parse(spec.version.as_deref()?).ok()?;
+
+    let registry = get_registry(clients, doc, &spec).await?;
+    let metas = clients
+        .crates
+        .get_registry_crate_metadatas(&registry, name)
+        .await
+        .ok()?;
+
+    let locked = get_locked_versions(clients, doc, name, &version_req).await;
+    let version_used = locked
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| version_req.minimum_version());
+    let latest = version_used
+        .extract_latest_version_filtered(metas, |v| !v.item.yanked || v.is_exactly_compatible)?;
+
+    let version_latest = latest.item_version.to_string();
+    Some(version_inlay_hint(
+        ts_range_to_lsp_range(node.range()).end,
+        &version_used.to_string(),
+        (latest.item_version > latest.this_version).then_some(version_latest.as_str()),
+    ))
+}
@@ -1,23 +1,36 @@
 use async_language_server::{
-    lsp_types::{CompletionResponse, Diagnostic, DocumentDiagnosticParams, Hover, Position},
+    lsp_types::{
+        CompletionResponse, Diagnostic, DocumentDiagnosticParams, DocumentLink,
+        GotoDefinitionResponse, Hover, InlayHint, InlayHintParams, Position,
+    },
     server::{Document, ServerResult},
     tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
 };
-use futures::future::try_join_all;
+use futures::future::{join_all, try_join_all};
 use tracing::debug;
 
 use deputy_clients::Clients;
 use deputy_parser::cargo;
 
+use crate::settings::EcosystemSettings;
+use crate::shared::{DocumentLinkMetadata, ranges_overlap, resolve_document_link};
+
 mod completion;
 mod constants;
+mod definition;
 mod diagnostics;
+mod document_link;
 mod hover;
+mod inlay_hint;
 mod util;
 
 use self::completion::get_cargo_completions;
+use self::definition::get_cargo_definition;
 use self::diagnostics::get_cargo_diagnostics;
+use self::document_link::{get_cargo_document_link, resolve_cargo_document_link};
 use self::hover::get_cargo_hover;
+use self::inlay_hint::get_cargo_inlay_hint;
 
 #[derive(Debug, Clone)]
 pub struct Cargo {
@@ -59,10 +72,26 @@ impl Cargo {
         get_cargo_completions(&self.clients, doc, pos, dep).await
     }
 
+    pub(super) async fn definition(
+        &self,
+        doc: &Document,
+        pos: Position,
+        _node: Node<'_>,
+    ) -> ServerResult<Option<GotoDefinitionResponse>> {
+        let Some(dep) = cargo::find_dependency_at(doc, pos) else {
+            return Ok(None);
+        };
+
+        debug!("Finding definition: {dep:?}");
+
+        get_cargo_definition(&self.clients, doc, pos, dep).await
+    }
+
     pub(super) async fn diagnostics(
         &self,
         doc: &Document,
         _params: DocumentDiagnosticParams,
+        settings: &EcosystemSettings,
     ) -> ServerResult<Vec<Diagnostic>> {
         // Find all dependencies
         let dependencies = cargo::find_all_dependencies(doc);
@@ -75,10 +104,68 @@ impl Cargo {
         let results = try_join_all(
             dependencies
                 .into_iter()
-                .map(|node| get_cargo_diagnostics(&self.clients, doc, node)),
+                .map(|node| get_cargo_diagnostics(&self.clients, settings, doc, node)),
         )
         .await?;
 
         Ok(results.into_iter().flatten().collect())
     }
+
+    pub(super) async fn inlay_hints(
+        &self,
+        doc: &Document,
+        params: InlayHintParams,
+    ) -> ServerResult<Vec<InlayHint>> {
+        // Find all dependencies within the requested range
+        let dependencies = cargo::find_all_dependencies(doc)
+            .into_iter()
+            .filter(|node| ranges_overlap(ts_range_to_lsp_range(node.range()), params.range))
+            .collect::<Vec<_>>();
+        if dependencies.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Fetch all inlay hints concurrently
+        debug!("Fetching cargo inlay hints for dependencies");
+        let results = join_all(
+            dependencies
+                .into_iter()
+                .map(|node| get_cargo_inlay_hint(&self.clients, doc, node)),
+        )
+        .await;
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    pub(super) async fn document_links(&self, doc: &Document) -> ServerResult<Vec<DocumentLink>> {
+        // Find all dependencies
+        let dependencies = cargo::find_all_dependencies(doc);
+        if dependencies.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Create all links concurrently, without fetching any package data
+        let results = join_all(
+            dependencies
+                .into_iter()
+                .map(|node| get_cargo_document_link(&self.clients, doc, node)),
+        )
+        .await;
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    pub(super) async fn resolve_document_link(
+        &self,
+        _doc: &Document,
+        link: DocumentLink,
+    ) -> ServerResult<DocumentLink> {
+        let Some(meta) = DocumentLinkMetadata::from_document_link(&link) else {
+            return Ok(link);
+        };
+        debug!("Resolving document link: {meta:?}");
+        let target = resolve_cargo_document_link(&self.clients, &meta.package_name).await;
+
+        Ok(resolve_document_link(link, &meta.package_name, target))
+    }
 }
@@ -1,16 +1,136 @@
-use async_language_server::lsp_types::Url;
+use std::path::{Path, PathBuf};
 
-use deputy_clients::crates::models::LocalMetadata;
-use deputy_versioning::{VersionReq, Versioned};
+use async_language_server::{
+    lsp_types::Url,
+    server::{Document, DocumentMatcher},
+};
+
+use deputy_clients::{
+    crates::models::{CargoWorkspace, CrateRegistry, LocalMetadata},
+    git::models::{GitCommit, GitRemote},
+    lockfile::models::LockfileKind,
+};
+use deputy_parser::{
+    TOML_LANGUAGE,
+    cargo::{self, CargoDependency, GitReferenceKind},
+    utils::unquote,
+};
+use deputy_versioning::{Version, VersionReq, Versioned};
 
 use super::Clients;
 
-pub async fn get_features(clients: &Clients, dname: &str, dver: &str) -> Option<Vec<String>> {
+/**
+    The name, version and source of a dependency.
+
+    For dependencies inherited from the workspace using `workspace = true`,
+    these are read from the matching entry in `[workspace.dependencies]`.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct DependencySpec {
+    pub name: String,
+    pub version: Option<String>,
+    /// Path to a local crate, either absolute or relative to the document
+    pub path: Option<String>,
+    pub git: Option<String>,
+    /// The `branch`, `tag` or `rev` of a git dependency, if any
+    pub git_reference: Option<(GitReferenceKind, String)>,
+    pub registry: Option<String>,
+}
+
+impl DependencySpec {
+    fn from_dependency(doc: &Document, dep: &CargoDependency<'_>) -> Self {
+        let (name, version) = dep.text(doc);
+        Self {
+            name,
+            version,
+            path: dep.path_text(doc),
+            git: dep.git_text(doc),
+            git_reference: dep
+                .git_reference()
+                .map(|(kind, node)| (kind, unquote(doc.node_text(node)))),
+            registry: dep.registry_text(doc),
+        }
+    }
+}
+
+/**
+    Resolves the name, version and source of a dependency, looking
+    up the workspace entry for any dependency with `workspace = true`.
+
+    Returns `None` if the dependency is inherited from the workspace,
+    but no workspace - or no matching workspace entry - was found.
+*/
+pub async fn get_dependency_spec(
+    clients: &Clients,
+    doc: &Document,
+    dep: &CargoDependency<'_>,
+) -> Option<DependencySpec> {
+    if !dep.is_workspace(doc) {
+        return Some(DependencySpec::from_dependency(doc, dep));
+    }
+
+    let (name, _) = dep.text(doc);
+    let doc_dir = doc.url().to_file_path().ok()?.parent()?.to_path_buf();
+    let workspace = clients.crates.get_workspace(&doc_dir).await?;
+    let entry = workspace.dependency(&name)?;
+
+    Some(DependencySpec {
+        name: entry.package.clone().unwrap_or(name),
+        version: entry.version.clone(),
+        path: entry.path.as_ref().map(|path| {
+            let path = workspace.root_dir().join(path);
+            path.to_string_lossy().to_string()
+        }),
+        git: entry.git.clone(),
+        git_reference: [
+            (GitReferenceKind::Branch, &entry.branch),
+            (GitReferenceKind::Tag, &entry.tag),
+            (GitReferenceKind::Rev, &entry.rev),
+        ]
+        .into_iter()
+        .find_map(|(kind, reference)| reference.clone().map(|r| (kind, r))),
+        registry: entry.registry.clone(),
+    })
+}
+
+/**
+    Resolves the registry for a dependency, using any `.cargo/config.toml`
+    files around the document, as well as the `registry` key of the dependency.
+*/
+pub async fn get_registry(
+    clients: &Clients,
+    doc: &Document,
+    spec: &DependencySpec,
+) -> Option<CrateRegistry> {
+    let registry = spec.registry.as_deref();
+    let Some(doc_dir) = doc
+        .url()
+        .to_file_path()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+    else {
+        // Not a file on disk, so there can be no config files either
+        return registry.is_none().then(CrateRegistry::crates_io);
+    };
+
+    let resolved = clients.crates.get_registry(&doc_dir, registry).await;
+    if resolved.is_none() {
+        tracing::debug!("Failed to resolve cargo registry {registry:?}");
+    }
+    resolved
+}
+
+pub async fn get_features(
+    clients: &Clients,
+    registry: &CrateRegistry,
+    dname: &str,
+    dver: &str,
+) -> Option<Vec<String>> {
     let dreq = VersionReq::parse(dver).ok()?;
 
     let metas = clients
         .crates
-        .get_sparse_index_crate_metadatas(dname)
+        .get_registry_crate_metadatas(registry, dname)
         .await
         .inspect_err(|e| {
             tracing::error!("failed to get crate data for {dname}: {e}");
@@ -34,23 +154,115 @@ pub async fn get_features(clients: &Clients, dname: &str, dver: &str) -> Option<
     )
 }
 
-pub async fn get_local_metadata(
-    clients: &Clients,
-    doc_url: &Url,
-    relative_path: &str,
-) -> Option<LocalMetadata> {
+/**
+    Resolves the path to the manifest of a local crate, given the path
+    of a `path` dependency, which may point to either the manifest itself
+    or its directory, and is either absolute or relative to the document.
+*/
+pub fn get_local_manifest_path(doc_url: &Url, relative_path: &str) -> Option<PathBuf> {
     let doc_path = doc_url.to_file_path().ok()?;
     let doc_dir = doc_path.parent()?;
 
     let dep_path = doc_dir.join(relative_path);
-    let manifest_dir = if dep_path
+    if dep_path
         .extension()
         .is_some_and(|e| e.eq_ignore_ascii_case("toml"))
     {
-        dep_path.parent()?
+        Some(dep_path)
     } else {
-        dep_path.as_path()
-    };
+        Some(dep_path.join("Cargo.toml"))
+    }
+}
+
+/**
+    Finds the zero-based line in the workspace root manifest where
+    the entry in `[workspace.dependencies]` with the given name is.
+*/
+pub fn get_workspace_dependency_line(workspace: &CargoWorkspace, name: &str) -> Option<u32> {
+    let url = Url::from_file_path(&workspace.manifest_path).ok()?;
+    let matcher = DocumentMatcher::new("Cargo").with_lang_grammar(TOML_LANGUAGE.into());
+    let root = Document::from_matcher(&matcher, url, workspace.manifest_text.clone());
+
+    cargo::find_workspace_dependencies(&root)
+        .into_iter()
+        .filter_map(|node| cargo::parse_dependency(&root, node))
+        .find(|dep| dep.text(&root).0 == name)
+        .and_then(|dep| u32::try_from(dep.name.start_position().row).ok())
+}
+
+pub async fn get_local_metadata(
+    clients: &Clients,
+    doc_url: &Url,
+    relative_path: &str,
+) -> Option<LocalMetadata> {
+    let manifest_path = get_local_manifest_path(doc_url, relative_path)?;
+    let manifest_dir = manifest_path.parent()?;
 
     clients.crates.get_local_metadata(manifest_dir).await
 }
+
+/**
+    Finds the versions of a crate that are locked in the `Cargo.lock`
+    for the document, and that match the given requirement, newest first.
+*/
+pub async fn get_locked_versions(
+    clients: &Clients,
+    doc: &Document,
+    name: &str,
+    version_req: &VersionReq,
+) -> Vec<Version> {
+    let Some(doc_dir) = doc
+        .url()
+        .to_file_path()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+    else {
+        return Vec::new();
+    };
+    let Some(lockfile) = clients
+        .lockfile
+        .get_lockfile(LockfileKind::Cargo, &doc_dir)
+        .await
+    else {
+        return Vec::new();
+    };
+
+    let mut versions = lockfile
+        .locked_versions(name)
+        .iter()
+        .filter_map(|v| Version::parse(v).ok())
+        .filter(|v| version_req.matches(v))
+        .collect::<Vec<_>>();
+    versions.sort_unstable_by(|a, b| b.cmp(a));
+    versions
+}
+
+/**
+    Finds the latest commit for a git dependency, along with the name of the branch it is
+    on - either the branch of the dependency, or the default branch of the repository.
+
+    Commit messages and dates are only available for repositories on GitHub.
+*/
+pub async fn get_latest_git_commit(
+    clients: &Clients,
+    remote: &GitRemote,
+    spec: &DependencySpec,
+) -> Option<(String, GitCommit)> {
+    let refs = clients.git.get_refs(remote).await.ok()?;
+    let branch = match &spec.git_reference {
+        Some((GitReferenceKind::Branch, name)) => refs.branch(name)?,
+        _ => refs.default_branch()?,
+    };
+
+    let sha = branch.commit_sha();
+    let commit = match clients.git.get_commit(remote, sha).await {
+        Ok(Some(commit)) => commit,
+        _ => GitCommit {
+            sha: sha.to_string(),
+            summary: None,
+            date: None,
+        },
+    };
+
+    Some((branch.branch_name()?.to_string(), commit))
+}
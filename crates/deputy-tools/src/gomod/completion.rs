@@ -9,11 +9,13 @@ use async_language_server::{
 };
 use tracing::debug;
 
+use deputy_clients::golang::models::GoProxyConfig;
 use deputy_parser::gomod;
 use deputy_versioning::Versioned;
 
 use super::Clients;
 use super::constants::top_go_packages_prefixed;
+use super::util::get_proxy_config;
 
 const MAXIMUM_PACKAGES_SHOWN: usize = 64;
 const MAXIMUM_VERSIONS_SHOWN: usize = 64;
@@ -35,8 +37,10 @@ pub async fn get_gomod_completions(
         && ts_range_contains_lsp_position(version_node.range(), pos)
     {
         debug!("Completing version: {dep:?}");
+        let config = get_proxy_config(clients, doc).await;
         return complete_version(
             clients,
+            &config,
             &path,
             version.as_deref().unwrap_or_default(),
             ts_range_to_lsp_range(version_node.range()),
@@ -55,11 +59,16 @@ pub async fn get_gomod_completions(
 
 async fn complete_version(
     clients: &Clients,
+    config: &GoProxyConfig,
     module_path: &str,
     version: &str,
     range: Range,
 ) -> ServerResult<Option<CompletionResponse>> {
-    let Ok(versions) = clients.golang.get_module_versions(module_path).await else {
+    let Ok(versions) = clients
+        .golang
+        .get_module_versions_from(config, module_path)
+        .await
+    else {
         return Ok(None);
     };
 
@@ -0,0 +1,47 @@
+use async_language_server::{
+    lsp_types::{GotoDefinitionResponse, Position},
+    server::{Document, ServerResult},
+    tree_sitter::Node,
+    tree_sitter_utils::ts_range_contains_lsp_position,
+};
+
+use deputy_parser::gomod;
+
+use crate::shared::file_definition;
+
+use super::Clients;
+use super::util::get_locked_version;
+
+pub async fn get_gomod_definition(
+    clients: &Clients,
+    doc: &Document,
+    pos: Position,
+    node: Node<'_>,
+) -> ServerResult<Option<GotoDefinitionResponse>> {
+    let Some(dep) = gomod::parse_dependency(node) else {
+        return Ok(None);
+    };
+    if !ts_range_contains_lsp_position(dep.path.range(), pos) {
+        return Ok(None);
+    }
+
+    let (path, version) = dep.text(doc);
+    let Some(version) = version else {
+        return Ok(None);
+    };
+
+    // The version selected for the build may be newer than the
+    // required one, and is the one that is most likely downloaded
+    let mut versions = vec![version.clone()];
+    if let Some(locked) = get_locked_version(clients, doc, &path, &version).await {
+        versions.insert(0, locked);
+    }
+
+    for version in versions {
+        if let Some(go_mod) = clients.golang.find_cached_module(&path, &version).await {
+            return Ok(file_definition(&go_mod, 0));
+        }
+    }
+
+    Ok(None)
+}
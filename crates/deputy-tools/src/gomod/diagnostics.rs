@@ -5,15 +5,21 @@ use async_language_server::{
     tree_sitter_utils::ts_range_to_lsp_range,
 };
 
+use deputy_clients::osv::models::AdvisoryEcosystem;
 use deputy_parser::gomod;
 use deputy_versioning::Versioned;
 
-use crate::shared::{CodeActionMetadata, ResolveContext};
+use crate::settings::EcosystemSettings;
+use crate::shared::{
+    AdvisoryCheck, CodeActionMetadata, DiagnosticKind, ResolveContext, get_advisory_diagnostics,
+};
 
 use super::Clients;
+use super::util::{get_locked_version, get_proxy_config};
 
 pub async fn get_gomod_diagnostics(
     clients: &Clients,
+    settings: &EcosystemSettings,
     doc: &Document,
     node: Node<'_>,
 ) -> ServerResult<Vec<Diagnostic>> {
@@ -22,19 +28,30 @@ pub async fn get_gomod_diagnostics(
     };
 
     let (path, version) = dep.text(doc);
+    if settings.is_ignored(&path) {
+        return Ok(Vec::new());
+    }
     let Some(version) = version else {
         return Ok(Vec::new()); // Incomplete spec, skip
     };
 
     let parsed_version = version.trim_start_matches('v');
 
-    // Fetch versions from the Go proxy
-    let versions = match clients.golang.get_module_versions(&path).await {
+    // Fetch versions from the Go proxy - private modules are usually not
+    // available from any proxy, so we skip those quietly if they are missing,
+    // proxies respond with 410 Gone instead of 404 for modules they refuse to serve
+    let config = get_proxy_config(clients, doc).await;
+    let versions = match clients
+        .golang
+        .get_module_versions_from(&config, &path)
+        .await
+    {
         Ok(v) => v,
         Err(e) => {
-            if e.is_not_found_error() {
+            if e.is_not_found_or_gone_error() && !config.is_private(&path) {
                 return Ok(vec![Diagnostic {
                     source: Some(String::from("Go")),
+                    code: Some(DiagnosticKind::UnknownPackage.into()),
                     range: ts_range_to_lsp_range(dep.path.range()),
                     message: format!("No published module exists for `{path}`"),
                     severity: Some(DiagnosticSeverity::ERROR),
@@ -48,6 +65,7 @@ pub async fn get_gomod_diagnostics(
     if versions.is_empty() {
         return Ok(vec![Diagnostic {
             source: Some(String::from("Go")),
+            code: Some(DiagnosticKind::NoVersions.into()),
             range: ts_range_to_lsp_range(dep.path.range()),
             message: format!("No versions exist for the module `{path}`"),
             severity: Some(DiagnosticSeverity::ERROR),
@@ -62,6 +80,7 @@ pub async fn get_gomod_diagnostics(
     }) {
         return Ok(vec![Diagnostic {
             source: Some(String::from("Go")),
+            code: Some(DiagnosticKind::UnknownVersion.into()),
             range: ts_range_to_lsp_range(dep.version.unwrap().range()),
             message: format!("Version `{version}` does not exist for the module `{path}`"),
             severity: Some(DiagnosticSeverity::ERROR),
@@ -69,6 +88,28 @@ pub async fn get_gomod_diagnostics(
         }]);
     }
 
+    // Check for any security advisories that affect the version,
+    // these are never published for private modules
+    let version_node = dep.version.unwrap();
+    let mut diagnostics = Vec::new();
+    if !config.is_private(&path) {
+        diagnostics = get_advisory_diagnostics(
+            clients,
+            doc,
+            AdvisoryCheck {
+                ecosystem: AdvisoryEcosystem::Go,
+                source: "Go",
+                package_name: &path,
+                version: parsed_version,
+                locked_versions: Vec::new(),
+                version_text: &version,
+                range: ts_range_to_lsp_range(version_node.range()),
+                edit_range: ts_range_to_lsp_range(version_node.range()),
+            },
+        )
+        .await;
+    }
+
     // Everything is OK - but we may be able to suggest new versions...
     // ... try to find the latest non-prerelease version
     let stripped_versions: Vec<String> = versions
@@ -76,24 +117,32 @@ pub async fn get_gomod_diagnostics(
         .map(|v| v.trim_start_matches('v').to_string())
         .collect();
 
-    let Some(latest_version) = parsed_version.extract_latest_version(stripped_versions) else {
-        return Ok(Vec::new());
+    // The go.sum may contain a newer version than the one required, if
+    // it has been selected for the build, which is the version that is used
+    let locked = get_locked_version(clients, doc, &path, &version).await;
+    let version_used = locked
+        .as_deref()
+        .map_or(parsed_version, |v| v.trim_start_matches('v'));
+    let Some(latest_version) = version_used.extract_latest_version(stripped_versions) else {
+        return Ok(diagnostics);
     };
 
     if !latest_version.is_exactly_compatible {
         let latest_version_string = format!("v{}", latest_version.item_version);
 
-        let version_node = dep.version.unwrap();
         let metadata = CodeActionMetadata::LatestVersion {
+            package_name: path.clone(),
             edit_range: ts_range_to_lsp_range(version_node.range()),
             source_uri: doc.url().clone(),
             source_text: version.clone(),
             version_current: parsed_version.to_string(),
             version_latest: latest_version.item_version.to_string(),
+            version_compatible: None,
         };
 
-        return Ok(vec![Diagnostic {
+        diagnostics.push(Diagnostic {
             source: Some(String::from("Go")),
+            code: Some(DiagnosticKind::Outdated.into()),
             range: ts_range_to_lsp_range(node.range()),
             message: format!(
                 "A newer version of `{path}` is available.\
@@ -108,8 +157,8 @@ pub async fn get_gomod_diagnostics(
                 .into(),
             ),
             ..Default::default()
-        }]);
+        });
     }
 
-    Ok(Vec::new())
+    Ok(diagnostics)
 }
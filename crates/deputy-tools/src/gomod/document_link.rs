@@ -0,0 +1,28 @@
+use async_language_server::{
+    lsp_types::DocumentLink, server::Document, tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_parser::gomod;
+
+use crate::shared::{ProjectLink, document_link};
+
+use super::Clients;
+use super::util::get_proxy_config;
+
+pub async fn get_gomod_document_link(
+    clients: &Clients,
+    doc: &Document,
+    node: Node<'_>,
+) -> Option<DocumentLink> {
+    let dep = gomod::parse_dependency(node)?;
+
+    // Private modules have no documentation on pkg.go.dev
+    let (path, _) = dep.text(doc);
+    if get_proxy_config(clients, doc).await.is_private(&path) {
+        return None;
+    }
+
+    let link = ProjectLink::new("Documentation", format!("https://pkg.go.dev/{path}"));
+    document_link(ts_range_to_lsp_range(dep.path.range()), &link, &path)
+}
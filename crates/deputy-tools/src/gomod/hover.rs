@@ -11,6 +11,7 @@ use deputy_parser::gomod;
 use crate::shared::MarkdownBuilder;
 
 use super::Clients;
+use super::util::get_locked_version;
 
 pub async fn get_gomod_hover(
     clients: &Clients,
@@ -32,6 +33,7 @@ pub async fn get_gomod_hover(
     md.h2(&path);
     if let Some(version) = &version {
         md.version(version);
+        md.locked(get_locked_version(clients, doc, &path, version).await);
     }
 
     // Try to fetch description from GitHub (only works for github.com/... modules)
@@ -0,0 +1,48 @@
+use async_language_server::{
+    lsp_types::InlayHint, server::Document, tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_parser::gomod;
+use deputy_versioning::Versioned;
+
+use crate::shared::version_inlay_hint;
+
+use super::Clients;
+use super::util::{get_locked_version, get_proxy_config};
+
+pub async fn get_gomod_inlay_hint(
+    clients: &Clients,
+    doc: &Document,
+    node: Node<'_>,
+) -> Option<InlayHint> {
+    let dep = gomod::parse_dependency(node)?;
+
+    let (path, version) = dep.text(doc);
+    let version = version?;
+
+    let config = get_proxy_config(clients, doc).await;
+    let versions = clients
+        .golang
+        .get_module_versions_from(&config, &path)
+        .await
+        .ok()?;
+
+    let stripped_versions: Vec<String> = versions
+        .iter()
+        .map(|v| v.trim_start_matches('v').to_string())
+        .collect();
+
+    let locked = get_locked_version(clients, doc, &path, &version).await;
+    let version_used = locked.unwrap_or(version);
+    let latest = version_used
+        .trim_start_matches('v')
+        .extract_latest_version(stripped_versions)?;
+
+    let version_latest = format!("v{}", latest.item_version);
+    Some(version_inlay_hint(
+        ts_range_to_lsp_range(node.range()).end,
+        &version_used,
+        (latest.item_version > latest.this_version).then_some(version_latest.as_str()),
+    ))
+}
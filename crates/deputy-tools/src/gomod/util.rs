@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use async_language_server::server::Document;
+
+use deputy_clients::{golang::models::GoProxyConfig, lockfile::models::LockfileKind};
+use deputy_versioning::Version;
+
+use super::Clients;
+
+/**
+    Resolves the module proxy settings for the module that the document
+    belongs to, using `GOPROXY`, `GOPRIVATE`, and related settings.
+*/
+pub async fn get_proxy_config(clients: &Clients, doc: &Document) -> GoProxyConfig {
+    let Some(doc_dir) = doc
+        .url()
+        .to_file_path()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+    else {
+        return GoProxyConfig::default();
+    };
+    clients.golang.get_proxy_config(&doc_dir).await
+}
+
+/**
+    Finds the version of a module that is locked in the `go.sum` for the document.
+
+    The `go.sum` may contain several versions of a module, where the newest one is
+    the version that was selected for the build, as long as it is not older than
+    the required version - which means that the `go.sum` is out of date.
+*/
+pub async fn get_locked_version(
+    clients: &Clients,
+    doc: &Document,
+    path: &str,
+    version: &str,
+) -> Option<String> {
+    let doc_dir = doc
+        .url()
+        .to_file_path()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))?;
+    let lockfile = clients
+        .lockfile
+        .get_lockfile(LockfileKind::Go, &doc_dir)
+        .await?;
+
+    let required = Version::parse(version.trim_start_matches('v')).ok()?;
+    lockfile
+        .locked_versions(path)
+        .iter()
+        .filter_map(|v| Some((Version::parse(v.trim_start_matches('v')).ok()?, v)))
+        .filter(|(parsed, _)| *parsed >= required)
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, v)| v.clone())
+}
@@ -3,8 +3,16 @@ mod gomod;
 mod npm;
 mod pyproject;
 mod rokit;
+mod settings;
 mod shared;
 mod tools;
 mod wally;
 
+pub use self::settings::{
+    AdvisorySettings, CacheSettings, EcosystemSettings, Settings, SeveritySetting,
+};
+pub use self::shared::{
+    COMMAND_UPDATE_DEPENDENCIES, CodeActionMetadata, DiagnosticKind, ResolveContextPartial,
+    SOURCE_FIX_ALL_DEPUTY, SOURCE_UPDATE_DEPENDENCIES,
+};
 pub use self::tools::Tools;
@@ -10,11 +10,13 @@ use async_language_server::{
 };
 use tracing::debug;
 
+use deputy_clients::npm::models::NpmRegistry;
 use deputy_parser::npm;
 use deputy_versioning::Versioned;
 
 use super::Clients;
 use super::constants::top_npm_packages_prefixed;
+use super::util::{NpmSpec, get_registry};
 
 const MAXIMUM_PACKAGES_SHOWN: usize = 64;
 
@@ -29,17 +31,23 @@ pub async fn get_npm_completions(
     };
 
     let (name, spec) = dep.text(doc);
-    if spec.starts_with("file:") || spec.starts_with("github:") || spec.starts_with("git+") {
-        return Ok(None); // Ignore these spec formats, for now
-    }
 
-    // Try to complete specs (versions)
+    // Try to complete specs (versions) - only packages from a registry have versions,
+    // and aliases such as `npm:other@^1` complete versions of the aliased package
     if ts_range_contains_lsp_position(dep.spec.range(), pos) {
+        let parsed = NpmSpec::parse(&name, &spec);
+        let Some((package_name, range)) = parsed.registry() else {
+            return Ok(None);
+        };
         debug!("Completing version: {dep:?}");
+        let prefix = spec.strip_suffix(range).unwrap_or_default();
+        let registry = get_registry(clients, doc, package_name).await;
         return complete_spec(
             clients,
-            name.as_str(),
-            spec.as_str(),
+            &registry,
+            package_name,
+            prefix,
+            range,
             ts_range_to_lsp_range(dep.spec.range()),
         )
         .await;
@@ -78,15 +86,17 @@ fn complete_name(name: &str, range: Range) -> ServerResult<Option<CompletionResp
 
 async fn complete_spec(
     clients: &Clients,
+    registry: &NpmRegistry,
     name: &str,
+    prefix: &str,
     spec: &str,
     range: Range,
 ) -> ServerResult<Option<CompletionResponse>> {
-    let Ok(metadata) = clients.npm.get_registry_metadata(name).await else {
+    let Ok(metadata) = clients.npm.get_registry_metadata_from(registry, name).await else {
         return Ok(None);
     };
 
-    let valid_vec = spec
+    let mut valid_vec = spec
         .extract_completion_versions(metadata.versions.into_values())
         .into_iter()
         .take(MAXIMUM_PACKAGES_SHOWN)
@@ -96,12 +106,32 @@ async fn complete_spec(
             kind: Some(CompletionItemKind::VALUE),
             sort_text: Some(format!("{index:0>5}")),
             text_edit: Some(CompletionTextEdit::Edit(TextEdit {
-                new_text: potential_version.item_version_raw.clone(),
+                new_text: format!("{prefix}{}", potential_version.item_version_raw),
                 range: range.shrink(1, 1),
             })),
             ..Default::default()
         })
         .collect::<Vec<_>>();
 
+    // Dist-tags such as `latest` and `next` are also valid specs,
+    // and are shown after all versions, with the version they point to
+    let mut dist_tags = metadata
+        .dist_tags
+        .into_iter()
+        .filter(|(tag, _)| tag.starts_with(spec))
+        .collect::<Vec<_>>();
+    dist_tags.sort_unstable();
+    valid_vec.extend(dist_tags.into_iter().map(|(tag, version)| CompletionItem {
+        label: tag.clone(),
+        kind: Some(CompletionItemKind::CONSTANT),
+        detail: Some(version),
+        sort_text: Some(format!("~{tag}")),
+        text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+            new_text: format!("{prefix}{tag}"),
+            range: range.shrink(1, 1),
+        })),
+        ..Default::default()
+    }));
+
     Ok(Some(CompletionResponse::Array(valid_vec)))
 }
@@ -0,0 +1,41 @@
+use std::path::Path;
+
+use async_language_server::{
+    lsp_types::{GotoDefinitionResponse, Position},
+    server::{Document, ServerResult},
+    tree_sitter::Node,
+    tree_sitter_utils::ts_range_contains_lsp_position,
+};
+
+use deputy_parser::npm;
+
+use crate::shared::file_definition;
+
+use super::Clients;
+
+pub async fn get_npm_definition(
+    clients: &Clients,
+    doc: &Document,
+    pos: Position,
+    node: Node<'_>,
+) -> ServerResult<Option<GotoDefinitionResponse>> {
+    let Some(dep) = npm::parse_dependency(node) else {
+        return Ok(None);
+    };
+    if !ts_range_contains_lsp_position(dep.name.range(), pos) {
+        return Ok(None);
+    }
+
+    let Some(doc_dir) = doc
+        .url()
+        .to_file_path()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+    else {
+        return Ok(None);
+    };
+
+    let (name, _) = dep.text(doc);
+    let package_json = clients.npm.find_installed_package(&doc_dir, &name).await;
+    Ok(package_json.and_then(|path| file_definition(&path, 0)))
+}
@@ -6,15 +6,21 @@ use async_language_server::{
     tree_sitter_utils::ts_range_to_lsp_range,
 };
 
-use deputy_parser::npm;
+use deputy_clients::{git::models::GitRemote, osv::models::AdvisoryEcosystem};
+use deputy_parser::npm::{self, NpmDependency};
 use deputy_versioning::{VersionReqExt, Versioned, util::is_bare_version};
 
-use crate::shared::{CodeActionMetadata, ResolveContext};
+use crate::settings::EcosystemSettings;
+use crate::shared::{
+    AdvisoryCheck, CodeActionMetadata, DiagnosticKind, ResolveContext, get_advisory_diagnostics,
+};
 
 use super::Clients;
+use super::util::{NpmSpec, get_document_dir, get_locked_versions, get_registry};
 
 pub async fn get_npm_diagnostics(
     clients: &Clients,
+    settings: &EcosystemSettings,
     doc: &Document,
     node: Node<'_>,
 ) -> ServerResult<Vec<Diagnostic>> {
@@ -23,40 +29,87 @@ pub async fn get_npm_diagnostics(
     };
 
     let (name, spec) = dep.text(doc);
-    if spec.starts_with("file:") || spec.starts_with("github:") || spec.starts_with("git+") {
-        return Ok(Vec::new()); // Ignore these spec formats, for now
+    if settings.is_ignored(&name) {
+        return Ok(Vec::new());
     }
 
+    let diagnostics = match NpmSpec::parse(&name, &spec) {
+        NpmSpec::Registry { name, range } => {
+            get_npm_diagnostics_registry(clients, doc, &dep, &name, &range).await
+        }
+        NpmSpec::Workspace { name, range } => {
+            get_npm_diagnostics_workspace(clients, doc, &dep, &name, &range).await
+        }
+        NpmSpec::Path { path } => get_npm_diagnostics_path(clients, doc, &dep, &path).await,
+        NpmSpec::Git { url, reference } => {
+            get_npm_diagnostics_git(clients, doc, &dep, &url, reference.as_deref()).await
+        }
+        NpmSpec::Other => Vec::new(),
+    };
+    Ok(diagnostics)
+}
+
+/**
+    Checks a package from the registry - `name` is the name of the
+    package that is fetched, which differs from the dependency for aliases.
+*/
+async fn get_npm_diagnostics_registry(
+    clients: &Clients,
+    doc: &Document,
+    dep: &NpmDependency<'_>,
+    name: &str,
+    range: &str,
+) -> Vec<Diagnostic> {
+    let (dep_name, spec) = dep.text(doc);
+
     // In NPM, a bare version like "0.3.6" means exactly that version,
     // unlike Cargo where bare versions imply a range like "^0.3.6"
-    let normalized_spec = if is_bare_version(&spec) {
-        format!("={spec}")
+    let normalized_range = if is_bare_version(range) {
+        format!("={range}")
     } else {
-        spec.clone()
+        range.to_string()
     };
-
-    let Ok(version_req) = normalized_spec.parse_version_req() else {
-        return Ok(Vec::new());
-    };
-    let version = version_req.minimum_version();
+    let version_req = normalized_range.parse_version_req();
 
     // Fetch versions and make sure there is at least one
-    let meta = match clients.npm.get_registry_metadata(&name).await {
+    let registry = get_registry(clients, doc, name).await;
+    let meta = match clients
+        .npm
+        .get_registry_metadata_from(&registry, name)
+        .await
+    {
         Ok(v) => v,
         Err(e) => {
             if e.is_not_found_error() {
-                return Ok(vec![Diagnostic {
+                return vec![Diagnostic {
                     source: Some(String::from("NPM")),
+                    code: Some(DiagnosticKind::UnknownPackage.into()),
                     range: ts_range_to_lsp_range(dep.name.range()),
                     message: format!("No package exists with the name `{name}`"),
                     severity: Some(DiagnosticSeverity::ERROR),
                     ..Default::default()
-                }]);
+                }];
             }
-            return Ok(Vec::new());
+            return Vec::new();
         }
     };
 
+    // Dist-tags such as `latest` or `next` always point to a published version
+    let Ok(version_req) = version_req else {
+        if meta.dist_tags.contains_key(range) || !is_dist_tag_like(range) {
+            return Vec::new();
+        }
+        return vec![Diagnostic {
+            source: Some(String::from("NPM")),
+            code: Some(DiagnosticKind::UnknownVersion.into()),
+            range: ts_range_to_lsp_range(dep.spec.range()),
+            message: format!("No dist-tag named `{range}` exists for the package `{name}`"),
+            severity: Some(DiagnosticSeverity::ERROR),
+            ..Default::default()
+        }];
+    };
+    let version = version_req.minimum_version();
+
     let mut has_versions = false;
     let mut deprecation_reason = None;
     for version in meta.versions.values().filter(|v| {
@@ -77,62 +130,328 @@ pub async fn get_npm_diagnostics(
 
     // Check if any version meeting the one specified exists
     if !has_versions {
-        return Ok(vec![Diagnostic {
+        return vec![Diagnostic {
             source: Some(String::from("NPM")),
+            code: Some(DiagnosticKind::UnknownVersion.into()),
             range: ts_range_to_lsp_range(dep.spec.range()),
             message: format!("Version `{version}` does not exist for the package `{name}`"),
             severity: Some(DiagnosticSeverity::ERROR),
             ..Default::default()
-        }]);
+        }];
+    }
+
+    // Advisories are only published for packages on the public registry,
+    // a package from a private registry may just share its name with one
+    let locked = get_locked_versions(clients, doc, &dep_name, &version_req).await;
+    let mut diagnostics = Vec::new();
+    if registry.is_npmjs() {
+        diagnostics = get_advisory_diagnostics(
+            clients,
+            doc,
+            AdvisoryCheck {
+                ecosystem: AdvisoryEcosystem::Npm,
+                source: "NPM",
+                package_name: name,
+                version: &version.to_string(),
+                locked_versions: locked.iter().map(ToString::to_string).collect(),
+                version_text: &spec,
+                range: ts_range_to_lsp_range(dep.spec.range()),
+                edit_range: ts_range_to_lsp_range(dep.spec.range().shrink(1, 1)),
+            },
+        )
+        .await;
     }
 
     if let Some(deprecation_reason) = deprecation_reason {
-        return Ok(vec![Diagnostic {
+        diagnostics.push(Diagnostic {
             source: Some(String::from("NPM")),
+            code: Some(DiagnosticKind::DeprecatedVersion.into()),
             range: ts_range_to_lsp_range(dep.spec.range()),
             message: format!("Version `{version}` is deprecated: {deprecation_reason}"),
             severity: Some(DiagnosticSeverity::WARNING),
             tags: Some(vec![DiagnosticTag::DEPRECATED]),
             ..Default::default()
-        }]);
+        });
+        return diagnostics;
     }
 
     // Everything is OK - but we may be able to suggest new versions...
-    // ... try to find the latest non-prerelease version
-    let Some(latest_version) = version.extract_latest_version(meta.versions.values().cloned())
+    // ... try to find the latest non-prerelease versions, comparing against
+    // the locked version when there is one, since that is what is installed
+    let version_used = locked.first().unwrap_or(&version);
+    let Some(latest_versions) =
+        version_used.extract_latest_versions(meta.versions.values().cloned())
     else {
-        return Ok(Vec::new());
+        return diagnostics;
     };
 
-    if !latest_version.is_semver_compatible {
-        let latest_version_string = latest_version.item_version.to_string();
+    let latest_version = &latest_versions.latest;
+    let latest_version_string = latest_version.item_version.to_string();
+    let compatible_version_string = latest_versions
+        .compatible_update()
+        .map(|v| v.item_version.to_string());
 
-        let metadata = CodeActionMetadata::LatestVersion {
-            edit_range: ts_range_to_lsp_range(dep.spec.range().shrink(1, 1)),
-            source_uri: doc.url().clone(),
-            source_text: spec.clone(),
-            version_current: version.to_string(),
-            version_latest: latest_version_string.clone(),
+    let (message, severity, kind) = if !latest_version.is_semver_compatible {
+        let message = if let Some(compatible) = &compatible_version_string {
+            format!(
+                "A newer version of `{name}` is available.\
+                \nThe latest version is `{latest_version_string}`\
+                \nThe latest compatible version is `{compatible}`",
+            )
+        } else {
+            format!(
+                "A newer version of `{name}` is available.\
+                \nThe latest version is `{latest_version_string}`",
+            )
         };
+        (
+            message,
+            DiagnosticSeverity::INFORMATION,
+            DiagnosticKind::Outdated,
+        )
+    } else if latest_version.item_version > latest_version.this_version {
+        let message = format!(
+            "A newer compatible version of `{name}` is available.\
+            \nThe latest compatible version is `{latest_version_string}`",
+        );
+        (
+            message,
+            DiagnosticSeverity::HINT,
+            DiagnosticKind::CompatibleUpdate,
+        )
+    } else {
+        return diagnostics;
+    };
+
+    let metadata = CodeActionMetadata::LatestVersion {
+        package_name: name.to_string(),
+        edit_range: ts_range_to_lsp_range(dep.spec.range().shrink(1, 1)),
+        source_uri: doc.url().clone(),
+        source_text: spec.clone(),
+        version_current: version.to_string(),
+        version_latest: latest_version_string,
+        version_compatible: compatible_version_string,
+    };
+
+    // Compatible updates are only worth a hint when they change the requirement,
+    // and not for requirements such as `1` that already allow the latest version
+    if kind == DiagnosticKind::CompatibleUpdate && metadata.text_edit().is_none() {
+        return diagnostics;
+    }
+
+    diagnostics.push(Diagnostic {
+        source: Some(String::from("NPM")),
+        code: Some(kind.into()),
+        range: ts_range_to_lsp_range(dep.spec.range()),
+        message,
+        severity: Some(severity),
+        data: Some(
+            ResolveContext {
+                uri: doc.url().clone(),
+                value: metadata,
+            }
+            .into(),
+        ),
+        ..Default::default()
+    });
+    diagnostics
+}
 
-        return Ok(vec![Diagnostic {
+async fn get_npm_diagnostics_workspace(
+    clients: &Clients,
+    doc: &Document,
+    dep: &NpmDependency<'_>,
+    name: &str,
+    range: &str,
+) -> Vec<Diagnostic> {
+    let Some(doc_dir) = get_document_dir(doc) else {
+        return Vec::new();
+    };
+    let Some(workspace) = clients.npm.get_workspace(&doc_dir).await else {
+        return vec![Diagnostic {
             source: Some(String::from("NPM")),
+            code: Some(DiagnosticKind::InvalidSpec.into()),
             range: ts_range_to_lsp_range(dep.spec.range()),
-            message: format!(
-                "A newer version of `{name}` is available.\
-                \nThe latest version is `{latest_version_string}`",
-            ),
-            severity: Some(DiagnosticSeverity::INFORMATION),
-            data: Some(
-                ResolveContext {
-                    uri: doc.url().clone(),
-                    value: metadata,
-                }
-                .into(),
-            ),
+            message: String::from("The `workspace:` protocol can only be used within a workspace"),
+            severity: Some(DiagnosticSeverity::ERROR),
             ..Default::default()
-        }]);
+        }];
+    };
+    let Some(package) = workspace.package(name) else {
+        return vec![Diagnostic {
+            source: Some(String::from("NPM")),
+            code: Some(DiagnosticKind::UnknownPackage.into()),
+            range: ts_range_to_lsp_range(dep.spec.range()),
+            message: format!("No package named `{name}` exists in the workspace"),
+            severity: Some(DiagnosticSeverity::ERROR),
+            ..Default::default()
+        }];
+    };
+
+    // Bare `*`, `^` and `~` ranges always use the current version of the package
+    if matches!(range, "" | "*" | "^" | "~") {
+        return Vec::new();
+    }
+    let (Some(version), Ok(version_req)) = (package.version.as_deref(), range.parse_version_req())
+    else {
+        return Vec::new();
+    };
+    if version
+        .parse_version()
+        .is_ok_and(|v| version_req.matches(&v))
+    {
+        return Vec::new();
+    }
+
+    vec![Diagnostic {
+        source: Some(String::from("NPM")),
+        code: Some(DiagnosticKind::UnknownVersion.into()),
+        range: ts_range_to_lsp_range(dep.spec.range()),
+        message: format!(
+            "Version `{range}` does not match the workspace package `{name}`, \
+            which has version `{version}`"
+        ),
+        severity: Some(DiagnosticSeverity::ERROR),
+        ..Default::default()
+    }]
+}
+
+async fn get_npm_diagnostics_path(
+    clients: &Clients,
+    doc: &Document,
+    dep: &NpmDependency<'_>,
+    path: &str,
+) -> Vec<Diagnostic> {
+    let Some(doc_dir) = get_document_dir(doc) else {
+        return Vec::new();
+    };
+    if clients
+        .npm
+        .find_local_package(&doc_dir.join(path))
+        .await
+        .is_some()
+    {
+        return Vec::new();
+    }
+
+    vec![Diagnostic {
+        source: Some(String::from("NPM")),
+        code: Some(DiagnosticKind::UnknownPackage.into()),
+        range: ts_range_to_lsp_range(dep.spec.range()),
+        message: format!("No package exists at the path `{path}`"),
+        severity: Some(DiagnosticSeverity::ERROR),
+        ..Default::default()
+    }]
+}
+
+async fn get_npm_diagnostics_git(
+    clients: &Clients,
+    doc: &Document,
+    dep: &NpmDependency<'_>,
+    url: &str,
+    reference: Option<&str>,
+) -> Vec<Diagnostic> {
+    // Without a reference, the default branch is used, which always exists
+    let Some(reference) = reference.filter(|r| !r.is_empty()) else {
+        return Vec::new();
+    };
+    let Some(remote) = GitRemote::parse(url) else {
+        return Vec::new();
+    };
+    let Ok(refs) = clients.git.get_refs(&remote).await else {
+        return Vec::new();
+    };
+
+    let remote_url = &remote.http_url;
+    let (exists, message) = if let Some(range) = reference.strip_prefix("semver:") {
+        let Ok(version_req) = range.parse_version_req() else {
+            return Vec::new();
+        };
+        (
+            refs.tags()
+                .any(|tag| tag.parse_version().is_ok_and(|v| version_req.matches(&v))),
+            format!("No tag matching `{range}` exists in `{remote_url}`"),
+        )
+    } else {
+        // Commits that are not the tip of any branch or tag can only be
+        // checked using the GitHub API, so we assume that they exist otherwise
+        let exists = refs.branch(reference).is_some()
+            || refs.tag(reference).is_some()
+            || refs.find_commit(reference).is_some()
+            || clients
+                .git
+                .get_commit(&remote, reference)
+                .await
+                .map_or_else(|e| !e.is_not_found_error(), |_| true);
+        (
+            exists,
+            format!("No branch, tag or commit matching `{reference}` exists in `{remote_url}`"),
+        )
+    };
+    if !exists {
+        return vec![Diagnostic {
+            source: Some(String::from("NPM")),
+            code: Some(DiagnosticKind::UnknownVersion.into()),
+            range: ts_range_to_lsp_range(dep.spec.range()),
+            message,
+            severity: Some(DiagnosticSeverity::ERROR),
+            ..Default::default()
+        }];
     }
 
-    Ok(Vec::new())
+    // Tags that look like versions can be compared to find newer ones,
+    // the same way as versions are, ignoring any newer prereleases
+    let Some(current) = refs.tag(reference) else {
+        return Vec::new();
+    };
+    let Some(latest) = current.extract_latest_version(refs.tags().cloned()) else {
+        return Vec::new();
+    };
+    if latest.item_version <= latest.this_version {
+        return Vec::new();
+    }
+    let Some(latest_tag) = latest.item.tag_name() else {
+        return Vec::new();
+    };
+
+    let (name, spec) = dep.text(doc);
+    let metadata = CodeActionMetadata::LatestVersion {
+        package_name: name.clone(),
+        edit_range: ts_range_to_lsp_range(dep.spec.range().shrink(1, 1)),
+        source_uri: doc.url().clone(),
+        source_text: spec,
+        version_current: reference.to_string(),
+        version_latest: latest_tag.to_string(),
+        version_compatible: None,
+    };
+
+    vec![Diagnostic {
+        source: Some(String::from("NPM")),
+        code: Some(DiagnosticKind::Outdated.into()),
+        range: ts_range_to_lsp_range(dep.spec.range()),
+        message: format!(
+            "A newer tag of `{name}` is available.\
+            \nThe latest tag is `{latest_tag}`"
+        ),
+        severity: Some(DiagnosticSeverity::INFORMATION),
+        data: Some(
+            ResolveContext {
+                uri: doc.url().clone(),
+                value: metadata,
+            }
+            .into(),
+        ),
+        ..Default::default()
+    }]
+}
+
+/**
+    Checks if a spec that is not a valid version range looks like a dist-tag,
+    such as `latest`, `next` or `beta` - these must start with a letter.
+*/
+fn is_dist_tag_like(range: &str) -> bool {
+    range.starts_with(|c: char| c.is_ascii_alphabetic())
+        && range
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
 }
@@ -0,0 +1,45 @@
+use async_language_server::{
+    lsp_types::DocumentLink, server::Document, text_utils::RangeExt, tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_parser::npm;
+
+use crate::shared::{ProjectLink, lazy_document_link};
+
+use super::Clients;
+use super::util::{NpmSpec, get_package_links, get_registry};
+
+pub fn get_npm_document_link(doc: &Document, node: Node<'_>) -> Option<DocumentLink> {
+    let dep = npm::parse_dependency(node)?;
+
+    // Only packages from a registry have pages to link to, and
+    // aliased packages link to the package that they are an alias of
+    let (name, spec) = dep.text(doc);
+    let spec = NpmSpec::parse(&name, &spec);
+    let (name, _) = spec.registry()?;
+
+    let range = ts_range_to_lsp_range(dep.name.range().shrink(1, 1));
+    Some(lazy_document_link(doc.url(), range, name))
+}
+
+pub async fn resolve_npm_document_link(
+    clients: &Clients,
+    doc: &Document,
+    name: &str,
+) -> Option<ProjectLink> {
+    let registry = get_registry(clients, doc, name).await;
+    let links = clients
+        .npm
+        .get_registry_metadata_from(&registry, name)
+        .await
+        .map(|meta| get_package_links(&meta))
+        .unwrap_or_default();
+
+    // Packages on the public registry always have a page on npmjs.com
+    links.primary().or_else(|| {
+        registry
+            .is_npmjs()
+            .then(|| ProjectLink::new("Package", format!("https://www.npmjs.com/package/{name}")))
+    })
+}
@@ -6,13 +6,15 @@ use async_language_server::{
 };
 use tracing::trace;
 
-use deputy_clients::npm::models::RegistryMetadataRepositoryVariant;
 use deputy_parser::npm;
-use deputy_versioning::{VersionReqExt, Versioned, util::is_bare_version};
+use deputy_versioning::VersionReqExt;
 
 use crate::shared::MarkdownBuilder;
 
 use super::Clients;
+use super::util::{
+    NpmSpec, get_locked_versions, get_package_links, get_registry, resolve_version_req,
+};
 
 pub async fn get_npm_hover(
     clients: &Clients,
@@ -23,13 +25,12 @@ pub async fn get_npm_hover(
         return Ok(None);
     };
 
-    let (name, spec) = dep.text(doc);
-    let normalized_spec = if is_bare_version(&spec) {
-        format!("={spec}")
-    } else {
-        spec.clone()
+    let (dep_name, spec) = dep.text(doc);
+    let spec = NpmSpec::parse(&dep_name, &spec);
+    let Some((name, range)) = spec.registry() else {
+        return Ok(None);
     };
-    let Ok(version_req) = normalized_spec.parse_version_req() else {
+    let Some(version_req) = resolve_version_req(clients, doc, name, range).await else {
         return Ok(None);
     };
 
@@ -38,33 +39,33 @@ pub async fn get_npm_hover(
     // Add basic hover information with version and name
     trace!("Hovering: {name} version {version}");
     let mut md = MarkdownBuilder::new();
-    md.h2(&name);
+    md.h2(name);
     md.version(version);
+    md.locked(get_locked_versions(clients, doc, &dep_name, &version_req).await);
 
     // Try to fetch additional information from the index - description, links
     trace!("Fetching package data from npm");
-    if let Ok(meta) = clients.npm.get_registry_metadata(&name).await {
+    let registry = get_registry(clients, doc, name).await;
+    if let Ok(meta) = clients
+        .npm
+        .get_registry_metadata_from(&registry, name)
+        .await
+    {
         if let Some(desc) = meta.current_version.description.as_ref() {
             md.br();
             md.p(desc);
         }
 
-        // Ignore homepage or docs if it's the same as the repo
-        let mut page = meta.current_version.homepage.as_deref();
-        let repo = meta.current_version.repository.as_ref();
-        if page.is_some_and(|p| {
-            repo.is_some_and(|r| r.url().is_some_and(|u| u.eq_ignore_ascii_case(p)))
-        }) {
-            page = None;
-        }
+        // Ignore homepage if it's the same as the repo
+        let links = get_package_links(&meta);
 
         // Add links to repo and homepage
         md.br();
         md.h3("Links");
-        if let Some(repo) = repo.and_then(RegistryMetadataRepositoryVariant::url) {
+        if let Some(repo) = links.repository {
             md.a("Repository", repo);
         }
-        if let Some(page) = page {
+        if let Some(page) = links.homepage {
             md.a("Homepage", page);
         }
     }
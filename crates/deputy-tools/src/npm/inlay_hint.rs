@@ -0,0 +1,46 @@
+use async_language_server::{
+    lsp_types::InlayHint, server::Document, tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_parser::npm;
+use deputy_versioning::{VersionReqExt, Versioned};
+
+use crate::shared::version_inlay_hint;
+
+use super::Clients;
+use super::util::{NpmSpec, get_locked_versions, get_registry, resolve_version_req};
+
+pub async fn get_npm_inlay_hint(
+    clients: &Clients,
+    doc: &Document,
+    node: Node<'_>,
+) -> Option<InlayHint> {
+    let dep = npm::parse_dependency(node)?;
+
+    let (dep_name, spec) = dep.text(doc);
+    let spec = NpmSpec::parse(&dep_name, &spec);
+    let (name, range) = spec.registry()?;
+    let version_req = resolve_version_req(clients, doc, name, range).await?;
+
+    let registry = get_registry(clients, doc, name).await;
+    let meta = clients
+        .npm
+        .get_registry_metadata_from(&registry, name)
+        .await
+        .ok()?;
+
+    let locked = get_locked_versions(clients, doc, &dep_name, &version_req).await;
+    let version_used = locked
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| version_req.minimum_version());
+    let latest = version_used.extract_latest_version(meta.versions.values().cloned())?;
+
+    let version_latest = latest.item_version.to_string();
+    Some(version_inlay_hint(
+        ts_range_to_lsp_range(node.range()).end,
+        &version_used.to_string(),
+        (latest.item_version > latest.this_version).then_some(version_latest.as_str()),
+    ))
+}
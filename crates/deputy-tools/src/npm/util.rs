@@ -0,0 +1,396 @@
+use std::path::{Path, PathBuf};
+
+use async_language_server::server::Document;
+
+use deputy_clients::{
+    lockfile::models::LockfileKind,
+    npm::models::{NpmRegistry, RegistryMetadata, RegistryMetadataRepositoryVariant},
+};
+use deputy_versioning::{Version, VersionReq, Versioned, util::is_bare_version};
+
+use crate::shared::ProjectLinks;
+
+use super::Clients;
+
+/**
+    A parsed npm dependency spec, which may be one of many forms other than a plain version range.
+
+    See <https://docs.npmjs.com/cli/configuring-npm/package-json#dependencies>
+    and <https://pnpm.io/workspaces#workspace-protocol-workspace> for all forms.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NpmSpec {
+    /**
+        A package from the registry, with a version range or dist-tag.
+
+        Aliases such as `npm:other@^1` refer to the aliased package, in which
+        case the name is that of the aliased package and not of the dependency.
+    */
+    Registry { name: String, range: String },
+    /// A package in the same workspace, such as `workspace:*` or `workspace:^1.0.0`
+    Workspace { name: String, range: String },
+    /// A local directory or tarball, such as `file:../other` or `link:../other`
+    Path { path: String },
+    /// A git repository, such as `github:owner/repo#v1.0.0` or `git+https://host/repo.git`
+    Git {
+        url: String,
+        reference: Option<String>,
+    },
+    /// Any other spec, such as a tarball url, which we do not check
+    Other,
+}
+
+impl NpmSpec {
+    pub fn parse(name: &str, spec: &str) -> Self {
+        let spec = spec.trim();
+        if let Some(aliased) = spec.strip_prefix("npm:") {
+            // Scoped package names start with an @, which is not a separator
+            return match aliased.char_indices().skip(1).find(|(_, c)| *c == '@') {
+                Some((index, _)) => Self::Registry {
+                    name: aliased[..index].to_string(),
+                    range: aliased[index + 1..].to_string(),
+                },
+                None => Self::Registry {
+                    name: aliased.to_string(),
+                    range: String::from("latest"),
+                },
+            };
+        }
+        if let Some(range) = spec.strip_prefix("workspace:") {
+            return match range.char_indices().skip(1).find(|(_, c)| *c == '@') {
+                Some((index, _)) => Self::Workspace {
+                    name: range[..index].to_string(),
+                    range: range[index + 1..].to_string(),
+                },
+                None => Self::Workspace {
+                    name: name.to_string(),
+                    range: range.to_string(),
+                },
+            };
+        }
+        if let Some(path) = spec
+            .strip_prefix("file:")
+            .or_else(|| spec.strip_prefix("link:"))
+        {
+            return Self::Path {
+                path: path.to_string(),
+            };
+        }
+        if spec.starts_with("./") || spec.starts_with("../") || spec.starts_with('/') {
+            return Self::Path {
+                path: spec.to_string(),
+            };
+        }
+
+        let (repo, reference) = match spec.split_once('#') {
+            Some((repo, reference)) => (repo, Some(reference.to_string())),
+            None => (spec, None),
+        };
+        let hosted = [
+            ("github:", "https://github.com/"),
+            ("gitlab:", "https://gitlab.com/"),
+            ("bitbucket:", "https://bitbucket.org/"),
+        ]
+        .into_iter()
+        .find_map(|(prefix, base)| {
+            repo.strip_prefix(prefix)
+                .map(|path| format!("{base}{path}"))
+        });
+        if let Some(url) = hosted {
+            return Self::Git { url, reference };
+        }
+        if let Some(url) = repo.strip_prefix("git+") {
+            return Self::Git {
+                url: url.replacen("ssh://git@github.com:", "git@github.com:", 1),
+                reference,
+            };
+        }
+        if repo.starts_with("git://") || repo.starts_with("git@") {
+            return Self::Git {
+                url: repo.to_string(),
+                reference,
+            };
+        }
+        // A plain `owner/repo` is shorthand for a GitHub repository
+        if !repo.contains(':')
+            && let Some((owner, repository)) = repo.split_once('/')
+            && !owner.is_empty()
+            && !repository.is_empty()
+            && !repository.contains('/')
+        {
+            return Self::Git {
+                url: format!("https://github.com/{repo}"),
+                reference,
+            };
+        }
+        if spec.contains("://") {
+            return Self::Other;
+        }
+
+        Self::Registry {
+            name: name.to_string(),
+            range: spec.to_string(),
+        }
+    }
+
+    /**
+        Gets the name and version range of the registry package that this spec refers to, if any.
+    */
+    pub fn registry(&self) -> Option<(&str, &str)> {
+        match self {
+            Self::Registry { name, range } => Some((name, range)),
+            _ => None,
+        }
+    }
+}
+
+/**
+    Gets the directory that contains the given document, if it is a local file.
+*/
+pub fn get_document_dir(doc: &Document) -> Option<PathBuf> {
+    doc.url()
+        .to_file_path()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+}
+
+/**
+    Resolves the registry for a package, using any `.npmrc` files
+    around the document, as well as any scoped registry for the package.
+*/
+pub async fn get_registry(clients: &Clients, doc: &Document, name: &str) -> NpmRegistry {
+    let Some(doc_dir) = get_document_dir(doc) else {
+        return NpmRegistry::npmjs();
+    };
+    clients.npm.get_registry(&doc_dir, name).await
+}
+
+/**
+    Resolves the version range of a spec for a registry package.
+
+    Bare versions such as `1.2.3` mean exactly that version, and
+    dist-tags such as `latest` resolve to the version they point to.
+*/
+pub async fn resolve_version_req(
+    clients: &Clients,
+    doc: &Document,
+    name: &str,
+    range: &str,
+) -> Option<VersionReq> {
+    let normalized_range = if is_bare_version(range) {
+        format!("={range}")
+    } else {
+        range.to_string()
+    };
+    if let Ok(version_req) = normalized_range.parse_version_req() {
+        return Some(version_req);
+    }
+
+    let registry = get_registry(clients, doc, name).await;
+    let meta = clients
+        .npm
+        .get_registry_metadata_from(&registry, name)
+        .await
+        .ok()?;
+    let tagged = meta.dist_tag_version(range)?;
+    format!("={}", tagged.version).parse_version_req().ok()
+}
+
+/**
+    Finds the versions of a package that are locked in the lockfile for the
+    document - using npm, pnpm or yarn - and that match the given requirement, newest first.
+
+    Packages are locked under the name of the dependency, which differs from the
+    name of the package for aliases, so this must be given the dependency name.
+*/
+pub async fn get_locked_versions(
+    clients: &Clients,
+    doc: &Document,
+    name: &str,
+    version_req: &VersionReq,
+) -> Vec<Version> {
+    let Some(doc_dir) = get_document_dir(doc) else {
+        return Vec::new();
+    };
+    let Some(lockfile) = clients
+        .lockfile
+        .get_lockfile(LockfileKind::Npm, &doc_dir)
+        .await
+    else {
+        return Vec::new();
+    };
+
+    let mut versions = lockfile
+        .locked_versions(name)
+        .iter()
+        .filter_map(|v| Version::parse(v).ok())
+        .filter(|v| version_req.matches(v))
+        .collect::<Vec<_>>();
+    versions.sort_unstable_by(|a, b| b.cmp(a));
+    versions
+}
+
+/**
+    Gets the repository and homepage links of a package, from its registry metadata.
+*/
+pub fn get_package_links(meta: &RegistryMetadata) -> ProjectLinks {
+    // Repository urls are often git urls, such as "git+https://github.com/owner/repo.git"
+    let repo = meta
+        .current_version
+        .repository
+        .as_ref()
+        .and_then(RegistryMetadataRepositoryVariant::url)
+        .map(|url| {
+            url.trim_start_matches("git+")
+                .trim_end_matches(".git")
+                .to_string()
+        });
+    ProjectLinks::new(
+        None,
+        repo.as_deref(),
+        meta.current_version.homepage.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(name: &str, range: &str) -> NpmSpec {
+        NpmSpec::Registry {
+            name: name.to_string(),
+            range: range.to_string(),
+        }
+    }
+
+    fn git(url: &str, reference: Option<&str>) -> NpmSpec {
+        NpmSpec::Git {
+            url: url.to_string(),
+            reference: reference.map(ToString::to_string),
+        }
+    }
+
+    // parse
+
+    #[test]
+    fn parses_registry_specs() {
+        for (spec, expected) in [
+            ("^1.2.3", registry("dep", "^1.2.3")),
+            (" 1.2.3 ", registry("dep", "1.2.3")),
+            (">=1 <2 || 3.x", registry("dep", ">=1 <2 || 3.x")),
+            ("latest", registry("dep", "latest")),
+            ("", registry("dep", "")),
+            ("npm:other@^2.0.0", registry("other", "^2.0.0")),
+            ("npm:@scope/other@~1.0", registry("@scope/other", "~1.0")),
+            ("npm:other", registry("other", "latest")),
+            ("npm:@scope/other", registry("@scope/other", "latest")),
+        ] {
+            assert_eq!(NpmSpec::parse("dep", spec), expected, "{spec}");
+        }
+    }
+
+    #[test]
+    fn parses_workspace_specs() {
+        for (spec, name, range) in [
+            ("workspace:*", "dep", "*"),
+            ("workspace:^1.0.0", "dep", "^1.0.0"),
+            ("workspace:other@*", "other", "*"),
+            ("workspace:@scope/other@^1", "@scope/other", "^1"),
+        ] {
+            assert_eq!(
+                NpmSpec::parse("dep", spec),
+                NpmSpec::Workspace {
+                    name: name.to_string(),
+                    range: range.to_string(),
+                },
+                "{spec}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_path_specs() {
+        for (spec, path) in [
+            ("file:../other", "../other"),
+            ("link:../other", "../other"),
+            ("file:other.tgz", "other.tgz"),
+            ("./other", "./other"),
+            ("../other", "../other"),
+            ("/abs/other", "/abs/other"),
+        ] {
+            assert_eq!(
+                NpmSpec::parse("dep", spec),
+                NpmSpec::Path {
+                    path: path.to_string()
+                },
+                "{spec}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_git_specs() {
+        for (spec, expected) in [
+            (
+                "github:owner/repo",
+                git("https://github.com/owner/repo", None),
+            ),
+            (
+                "github:owner/repo#v1.0.0",
+                git("https://github.com/owner/repo", Some("v1.0.0")),
+            ),
+            (
+                "gitlab:group/project#main",
+                git("https://gitlab.com/group/project", Some("main")),
+            ),
+            (
+                "bitbucket:owner/repo",
+                git("https://bitbucket.org/owner/repo", None),
+            ),
+            (
+                "owner/repo#semver:^1.0.0",
+                git("https://github.com/owner/repo", Some("semver:^1.0.0")),
+            ),
+            (
+                "git+https://example.com/repo.git#abc1234",
+                git("https://example.com/repo.git", Some("abc1234")),
+            ),
+            (
+                "git+ssh://git@github.com:owner/repo.git",
+                git("git@github.com:owner/repo.git", None),
+            ),
+            (
+                "git://example.com/repo.git#dev",
+                git("git://example.com/repo.git", Some("dev")),
+            ),
+            (
+                "git@github.com:owner/repo.git",
+                git("git@github.com:owner/repo.git", None),
+            ),
+        ] {
+            assert_eq!(NpmSpec::parse("dep", spec), expected, "{spec}");
+        }
+    }
+
+    #[test]
+    fn parses_other_specs() {
+        for spec in [
+            "https://example.com/dep-1.0.0.tgz",
+            "http://example.com/dep.tgz",
+        ] {
+            assert_eq!(NpmSpec::parse("dep", spec), NpmSpec::Other, "{spec}");
+        }
+    }
+
+    // registry
+
+    #[test]
+    fn registry_returns_name_and_range() {
+        assert_eq!(
+            NpmSpec::parse("dep", "npm:other@^1").registry(),
+            Some(("other", "^1"))
+        );
+        assert_eq!(NpmSpec::parse("dep", "workspace:*").registry(), None);
+        assert_eq!(NpmSpec::parse("dep", "owner/repo").registry(), None);
+    }
+}
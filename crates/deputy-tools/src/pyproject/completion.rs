@@ -9,11 +9,13 @@ use async_language_server::{
 };
 use tracing::debug;
 
+use deputy_clients::pypi::models::PyPiIndex;
 use deputy_parser::pyproject;
 use deputy_versioning::PepVersioned;
 
 use super::Clients;
 use super::constants::top_pypi_packages_prefixed;
+use super::util::{get_indexes, get_pypi_metadata};
 
 const MAXIMUM_PACKAGES_SHOWN: usize = 64;
 
@@ -35,8 +37,10 @@ pub async fn get_pyproject_completions(
         && ts_range_contains_lsp_position(range, pos)
     {
         debug!("Completing version: {dep:?}");
+        let indexes = get_indexes(clients, doc).await;
         return complete_version(
             clients,
+            &indexes,
             name.unwrap_or_default(),
             version.unwrap_or_default(),
             ts_range_to_lsp_range(range),
@@ -53,8 +57,10 @@ pub async fn get_pyproject_completions(
                 .as_str()
                 .unwrap_or_default();
             debug!("Completing extras: {dep:?}");
+            let indexes = get_indexes(clients, doc).await;
             return complete_extras(
                 clients,
+                &indexes,
                 name.as_deref().unwrap_or_default(),
                 extra_text,
                 ts_range_to_lsp_range(extra_range),
@@ -98,11 +104,12 @@ fn complete_name(name: impl AsRef<str>, range: Range) -> ServerResult<Option<Com
 
 async fn complete_extras(
     clients: &Clients,
+    indexes: &[PyPiIndex],
     name: &str,
     current: &str,
     range: Range,
 ) -> ServerResult<Option<CompletionResponse>> {
-    let Ok(meta) = clients.pypi.get_registry_metadata(name).await else {
+    let Some(meta) = get_pypi_metadata(clients, indexes, name).await else {
         return Ok(None);
     };
     let Some(extras) = meta.info.provides_extra else {
@@ -130,11 +137,16 @@ async fn complete_extras(
 
 async fn complete_version(
     clients: &Clients,
+    indexes: &[PyPiIndex],
     name: impl AsRef<str>,
     version: impl AsRef<str>,
     range: Range,
 ) -> ServerResult<Option<CompletionResponse>> {
-    let Ok(metadata) = clients.pypi.get_simple_metadata(name.as_ref()).await else {
+    let Ok(metadata) = clients
+        .pypi
+        .get_simple_metadata_from(indexes, name.as_ref())
+        .await
+    else {
         return Ok(None);
     };
 
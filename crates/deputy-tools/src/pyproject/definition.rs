@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use async_language_server::{
+    lsp_types::{GotoDefinitionResponse, Position},
+    server::{Document, ServerResult},
+    tree_sitter::Node,
+    tree_sitter_utils::ts_range_contains_lsp_position,
+};
+
+use deputy_parser::pyproject;
+
+use crate::shared::file_definition;
+
+use super::Clients;
+
+pub async fn get_pyproject_definition(
+    clients: &Clients,
+    doc: &Document,
+    pos: Position,
+    node: Node<'_>,
+) -> ServerResult<Option<GotoDefinitionResponse>> {
+    let Some(dep) = pyproject::parse_dependency(node) else {
+        return Ok(None);
+    };
+    let ranges = dep.spec_ranges(doc);
+    if !ranges
+        .name
+        .is_some_and(|range| ts_range_contains_lsp_position(range, pos))
+    {
+        return Ok(None);
+    }
+
+    let (Some(name), _) = dep.text(doc) else {
+        return Ok(None);
+    };
+    let Some(doc_dir) = doc
+        .url()
+        .to_file_path()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+    else {
+        return Ok(None);
+    };
+
+    let metadata = clients
+        .pypi
+        .find_installed_distribution(&doc_dir, &name)
+        .await;
+    Ok(metadata.and_then(|path| file_definition(&path, 0)))
+}
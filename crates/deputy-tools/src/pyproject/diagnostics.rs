@@ -5,15 +5,22 @@ use async_language_server::{
     tree_sitter_utils::ts_range_to_lsp_range,
 };
 
+use deputy_clients::{osv::models::AdvisoryEcosystem, pypi::models::PyPiIndex};
 use deputy_parser::pyproject::{self, PyProjectDependency};
 use deputy_versioning::{PepVersionReqExt, PepVersioned};
 
-use crate::shared::{CodeActionMetadata, ResolveContext, did_you_mean};
+use crate::settings::EcosystemSettings;
+use crate::shared::{
+    AdvisoryCheck, CodeActionMetadata, DiagnosticKind, ResolveContext, did_you_mean,
+    get_advisory_diagnostics,
+};
 
 use super::Clients;
+use super::util::{get_indexes, get_locked_versions, get_pypi_metadata};
 
 pub async fn get_pyproject_diagnostics(
     clients: &Clients,
+    settings: &EcosystemSettings,
     doc: &Document,
     node: Node<'_>,
 ) -> ServerResult<Vec<Diagnostic>> {
@@ -26,6 +33,7 @@ pub async fn get_pyproject_diagnostics(
     if ranges.name.is_none() {
         return Ok(vec![Diagnostic {
             source: Some(String::from("PyPI")),
+            code: Some(DiagnosticKind::InvalidSpec.into()),
             range: ts_range_to_lsp_range(dep.spec_node.range()),
             message: String::from("Missing package name"),
             severity: Some(DiagnosticSeverity::WARNING),
@@ -36,6 +44,9 @@ pub async fn get_pyproject_diagnostics(
     let (Some(name), version) = dep.text(doc) else {
         return Ok(Vec::new());
     };
+    if settings.is_ignored(&name) {
+        return Ok(Vec::new());
+    }
 
     // No version spec is valid PEP 508 (e.g. just "requests")
     let Some(version) = version else {
@@ -48,12 +59,14 @@ pub async fn get_pyproject_diagnostics(
     let version_min = version_req.minimum_version();
 
     // Fetch versions and make sure there is at least one
-    let meta = match clients.pypi.get_simple_metadata(&name).await {
+    let indexes = get_indexes(clients, doc).await;
+    let meta = match clients.pypi.get_simple_metadata_from(&indexes, &name).await {
         Ok(v) => v,
         Err(e) => {
             if e.is_not_found_error() {
                 return Ok(vec![Diagnostic {
                     source: Some(String::from("PyPI")),
+                    code: Some(DiagnosticKind::UnknownPackage.into()),
                     range: ts_range_to_lsp_range(ranges.name.unwrap()),
                     message: format!("No package exists with the name `{name}`"),
                     severity: Some(DiagnosticSeverity::ERROR),
@@ -84,6 +97,7 @@ pub async fn get_pyproject_diagnostics(
     if !has_versions {
         return Ok(vec![Diagnostic {
             source: Some(String::from("PyPI")),
+            code: Some(DiagnosticKind::UnknownVersion.into()),
             range: ts_range_to_lsp_range(ranges.version.unwrap()),
             message: format!("Version `{version_min}` does not exist for the package `{name}`"),
             severity: Some(DiagnosticSeverity::ERROR),
@@ -94,6 +108,7 @@ pub async fn get_pyproject_diagnostics(
     if has_versions && all_yanked {
         return Ok(vec![Diagnostic {
             source: Some(String::from("PyPI")),
+            code: Some(DiagnosticKind::DeprecatedVersion.into()),
             range: ts_range_to_lsp_range(ranges.version.unwrap()),
             message: format!("Version `{version_min}` is yanked for the package `{name}`"),
             severity: Some(DiagnosticSeverity::WARNING),
@@ -105,22 +120,28 @@ pub async fn get_pyproject_diagnostics(
     // Version is valid - collect remaining diagnostics
     let mut diagnostics = Vec::new();
 
-    // Check for newer versions available
-    if let Some(latest_version) = version_min.extract_latest_version(versions)
+    // Check for newer versions available, comparing against the
+    // locked version when there is one, since that is what is installed
+    let locked = get_locked_versions(clients, doc, &name, &version_req).await;
+    let version_used = locked.first().unwrap_or(&version_min);
+    if let Some(latest_version) = version_used.extract_latest_version(versions)
         && !latest_version.is_compatible
     {
         let latest_version_string = latest_version.item_version.to_string();
 
         let metadata = CodeActionMetadata::LatestVersion {
+            package_name: name.clone(),
             edit_range: ts_range_to_lsp_range(ranges.version.unwrap()),
             source_uri: doc.url().clone(),
             source_text: version.clone(),
             version_current: version_min.to_string(),
             version_latest: latest_version_string.clone(),
+            version_compatible: None,
         };
 
         diagnostics.push(Diagnostic {
             source: Some(String::from("PyPI")),
+            code: Some(DiagnosticKind::Outdated.into()),
             range: ts_range_to_lsp_range(dep.spec_node.range()),
             message: format!(
                 "A newer version of `{name}` is available.\
@@ -138,8 +159,32 @@ pub async fn get_pyproject_diagnostics(
         });
     }
 
-    // Check extras against known extras from the registry
-    if let Ok(meta) = clients.pypi.get_registry_metadata(&name).await
+    // Check for any security advisories that affect the version - these
+    // are only published for PyPI, so we skip them for private indexes
+    let is_pypi = indexes.iter().any(PyPiIndex::is_pypi);
+    if is_pypi {
+        diagnostics.extend(
+            get_advisory_diagnostics(
+                clients,
+                doc,
+                AdvisoryCheck {
+                    ecosystem: AdvisoryEcosystem::PyPi,
+                    source: "PyPI",
+                    package_name: &name,
+                    version: &version_min.to_string(),
+                    locked_versions: locked.iter().map(ToString::to_string).collect(),
+                    version_text: &version,
+                    range: ts_range_to_lsp_range(ranges.version.unwrap()),
+                    edit_range: ts_range_to_lsp_range(ranges.version.unwrap()),
+                },
+            )
+            .await,
+        );
+    }
+
+    // Check extras against known extras from the registry - these are only
+    // available from PyPI, which is skipped if PyPI is not one of the indexes
+    if let Some(meta) = get_pypi_metadata(clients, &indexes, &name).await
         && let Some(known_extras) = &meta.info.provides_extra
     {
         diagnostics.extend(get_pyproject_diagnostics_extras(doc, &dep, known_extras));
@@ -166,6 +211,7 @@ fn get_pyproject_diagnostics_extras(
         if !known_extras.iter().any(|e| e.eq_ignore_ascii_case(extra)) {
             diagnostics.push(Diagnostic {
                 source: Some(String::from("PyPI")),
+                code: Some(DiagnosticKind::UnknownFeature.into()),
                 range: ts_range_to_lsp_range(extra_range),
                 message: match did_you_mean(extra, known_extras) {
                     Some(suggestion) => {
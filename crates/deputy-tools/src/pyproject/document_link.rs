@@ -0,0 +1,52 @@
+use async_language_server::{
+    lsp_types::DocumentLink, server::Document, tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_clients::pypi::models::PyPiIndex;
+use deputy_parser::pyproject;
+
+use crate::shared::{ProjectLink, lazy_document_link};
+
+use super::Clients;
+use super::util::{get_indexes, get_project_links};
+
+pub fn get_pyproject_document_link(doc: &Document, node: Node<'_>) -> Option<DocumentLink> {
+    let dep = pyproject::parse_dependency(node)?;
+
+    let range = dep.spec_ranges(doc).name?;
+    let (name, _) = dep.text(doc);
+
+    Some(lazy_document_link(
+        doc.url(),
+        ts_range_to_lsp_range(range),
+        name?,
+    ))
+}
+
+pub async fn resolve_pyproject_document_link(
+    clients: &Clients,
+    doc: &Document,
+    name: &str,
+) -> Option<ProjectLink> {
+    // Only packages on PyPI have pages to link to - a package from a
+    // private index may just share its name with an unrelated one on PyPI
+    let indexes = get_indexes(clients, doc).await;
+    if !indexes.iter().any(PyPiIndex::is_pypi) {
+        return None;
+    }
+
+    let links = clients
+        .pypi
+        .get_registry_metadata(name)
+        .await
+        .map(|meta| get_project_links(&meta))
+        .unwrap_or_default();
+
+    links.primary().or_else(|| {
+        Some(ProjectLink::new(
+            "Package",
+            format!("https://pypi.org/project/{name}/"),
+        ))
+    })
+}
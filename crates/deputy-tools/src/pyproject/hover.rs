@@ -7,10 +7,12 @@ use async_language_server::{
 use tracing::trace;
 
 use deputy_parser::pyproject;
+use deputy_versioning::PepVersioned;
 
 use crate::shared::MarkdownBuilder;
 
 use super::Clients;
+use super::util::{get_indexes, get_locked_versions, get_project_links, get_pypi_metadata};
 
 pub async fn get_pyproject_hover(
     clients: &Clients,
@@ -34,60 +36,32 @@ pub async fn get_pyproject_hover(
     md.h2(&name);
     if let Some(version) = &version {
         md.version(version);
+        if let Ok(version_req) = version.parse_version_req() {
+            md.locked(get_locked_versions(clients, doc, &name, &version_req).await);
+        }
     }
 
     // Try to fetch additional information - description, links
     trace!("Fetching package data from PyPI");
-    if let Ok(meta) = clients.pypi.get_registry_metadata(&name).await {
+    let indexes = get_indexes(clients, doc).await;
+    if let Some(meta) = get_pypi_metadata(clients, &indexes, &name).await {
         if let Some(summary) = meta.info.summary.as_deref() {
             md.br();
             md.p(summary);
         }
 
         // Collect links from project_urls and top-level fields
-        let mut repo = None;
-        let mut docs = None;
-        let mut page = meta.info.home_page.as_deref();
-
-        if let Some(project_urls) = &meta.info.project_urls {
-            for (key, url) in project_urls {
-                let key_lower = key.to_ascii_lowercase();
-                if repo.is_none()
-                    && (key_lower.contains("repository")
-                        || key_lower.contains("source")
-                        || key_lower == "github")
-                {
-                    repo = Some(url.as_str());
-                } else if docs.is_none()
-                    && (key_lower.contains("documentation") || key_lower.contains("docs"))
-                {
-                    docs = Some(url.as_str());
-                } else if page.is_none()
-                    && (key_lower.contains("homepage") || key_lower.contains("home"))
-                {
-                    page = Some(url.as_str());
-                }
-            }
-        }
-
-        // Deduplicate: ignore homepage or docs if same as repo
-        if page == repo {
-            page = None;
-        }
-        if docs == repo {
-            docs = None;
-        }
-
-        if repo.is_some() || docs.is_some() || page.is_some() {
+        let links = get_project_links(&meta);
+        if !links.is_empty() {
             md.br();
             md.h3("Links");
-            if let Some(docs) = docs {
+            if let Some(docs) = links.documentation {
                 md.a("Documentation", docs);
             }
-            if let Some(repo) = repo {
+            if let Some(repo) = links.repository {
                 md.a("Repository", repo);
             }
-            if let Some(page) = page {
+            if let Some(page) = links.homepage {
                 md.a("Homepage", page);
             }
         }
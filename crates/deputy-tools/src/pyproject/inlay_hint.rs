@@ -0,0 +1,46 @@
+use async_language_server::{
+    lsp_types::InlayHint, server::Document, tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_parser::pyproject;
+use deputy_versioning::{PepVersionReqExt, PepVersioned};
+
+use crate::shared::version_inlay_hint;
+
+use super::Clients;
+use super::util::{get_indexes, get_locked_versions};
+
+pub async fn get_pyproject_inlay_hint(
+    clients: &Clients,
+    doc: &Document,
+    node: Node<'_>,
+) -> Option<InlayHint> {
+    let dep = pyproject::parse_dependency(node)?;
+
+    let (Some(name), Some(version)) = dep.text(doc) else {
+        return None;
+    };
+    let version_req = version.parse_version_req().ok()?;
+
+    let indexes = get_indexes(clients, doc).await;
+    let meta = clients
+        .pypi
+        .get_simple_metadata_from(&indexes, &name)
+        .await
+        .ok()?;
+
+    let locked = get_locked_versions(clients, doc, &name, &version_req).await;
+    let version_used = locked
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| version_req.minimum_version());
+    let latest = version_used.extract_latest_version(meta.versions())?;
+
+    let version_latest = latest.item_version.to_string();
+    Some(version_inlay_hint(
+        ts_range_to_lsp_range(node.range()).end,
+        &version_used.to_string(),
+        (latest.item_version > latest.this_version).then_some(version_latest.as_str()),
+    ))
+}
@@ -1,22 +1,36 @@
 use async_language_server::{
-    lsp_types::{CompletionResponse, Diagnostic, DocumentDiagnosticParams, Hover, Position},
+    lsp_types::{
+        CompletionResponse, Diagnostic, DocumentDiagnosticParams, DocumentLink,
+        GotoDefinitionResponse, Hover, InlayHint, InlayHintParams, Position,
+    },
     server::{Document, ServerResult},
     tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
 };
-use futures::future::try_join_all;
+use futures::future::{join_all, try_join_all};
 use tracing::debug;
 
 use deputy_clients::Clients;
 use deputy_parser::pyproject;
 
+use crate::settings::EcosystemSettings;
+use crate::shared::{DocumentLinkMetadata, ranges_overlap, resolve_document_link};
+
 mod completion;
 mod constants;
+mod definition;
 mod diagnostics;
+mod document_link;
 mod hover;
+mod inlay_hint;
+mod util;
 
 use self::completion::get_pyproject_completions;
+use self::definition::get_pyproject_definition;
 use self::diagnostics::get_pyproject_diagnostics;
+use self::document_link::{get_pyproject_document_link, resolve_pyproject_document_link};
 use self::hover::get_pyproject_hover;
+use self::inlay_hint::get_pyproject_inlay_hint;
 
 #[derive(Debug, Clone)]
 pub struct PyProject {
@@ -58,10 +72,26 @@ impl PyProject {
         get_pyproject_completions(&self.clients, doc, pos, dep).await
     }
 
+    pub(super) async fn definition(
+        &self,
+        doc: &Document,
+        pos: Position,
+        _node: Node<'_>,
+    ) -> ServerResult<Option<GotoDefinitionResponse>> {
+        let Some(dep) = pyproject::find_dependency_at(doc, pos) else {
+            return Ok(None);
+        };
+
+        debug!("Finding definition: {dep:?}");
+
+        get_pyproject_definition(&self.clients, doc, pos, dep).await
+    }
+
     pub(super) async fn diagnostics(
         &self,
         doc: &Document,
         _params: DocumentDiagnosticParams,
+        settings: &EcosystemSettings,
     ) -> ServerResult<Vec<Diagnostic>> {
         // Find all dependencies
         let dependencies = pyproject::find_all_dependencies(doc);
@@ -74,10 +104,59 @@ impl PyProject {
         let results = try_join_all(
             dependencies
                 .into_iter()
-                .map(|node| get_pyproject_diagnostics(&self.clients, doc, node)),
+                .map(|node| get_pyproject_diagnostics(&self.clients, settings, doc, node)),
         )
         .await?;
 
         Ok(results.into_iter().flatten().collect())
     }
+
+    pub(super) async fn inlay_hints(
+        &self,
+        doc: &Document,
+        params: InlayHintParams,
+    ) -> ServerResult<Vec<InlayHint>> {
+        // Find all dependencies within the requested range
+        let dependencies = pyproject::find_all_dependencies(doc)
+            .into_iter()
+            .filter(|node| ranges_overlap(ts_range_to_lsp_range(node.range()), params.range))
+            .collect::<Vec<_>>();
+        if dependencies.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Fetch all inlay hints concurrently
+        debug!("Fetching pyproject inlay hints for dependencies");
+        let results = join_all(
+            dependencies
+                .into_iter()
+                .map(|node| get_pyproject_inlay_hint(&self.clients, doc, node)),
+        )
+        .await;
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    #[allow(clippy::unused_self)]
+    pub(super) fn document_links(&self, doc: &Document) -> Vec<DocumentLink> {
+        // Links are resolved later, so no package data is fetched here
+        pyproject::find_all_dependencies(doc)
+            .into_iter()
+            .filter_map(|node| get_pyproject_document_link(doc, node))
+            .collect()
+    }
+
+    pub(super) async fn resolve_document_link(
+        &self,
+        doc: &Document,
+        link: DocumentLink,
+    ) -> ServerResult<DocumentLink> {
+        let Some(meta) = DocumentLinkMetadata::from_document_link(&link) else {
+            return Ok(link);
+        };
+        debug!("Resolving document link: {meta:?}");
+        let target = resolve_pyproject_document_link(&self.clients, doc, &meta.package_name).await;
+
+        Ok(resolve_document_link(link, &meta.package_name, target))
+    }
 }
@@ -0,0 +1,167 @@
+use std::path::Path;
+
+use async_language_server::server::Document;
+
+use deputy_clients::{
+    lockfile::models::LockfileKind,
+    pypi::models::{PyPiIndex, RegistryMetadata},
+};
+use deputy_versioning::{PepVersion, PepVersionReq};
+
+use crate::shared::ProjectLinks;
+
+use super::Clients;
+
+/**
+    Resolves the package indexes for the project that the document belongs to,
+    using any uv or Poetry configuration, or any pip configuration, in priority order.
+*/
+pub async fn get_indexes(clients: &Clients, doc: &Document) -> Vec<PyPiIndex> {
+    let Some(doc_dir) = doc
+        .url()
+        .to_file_path()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+    else {
+        return vec![PyPiIndex::pypi()];
+    };
+    clients.pypi.get_indexes(&doc_dir).await
+}
+
+/**
+    Finds the versions of a package that are locked in the `uv.lock` or `poetry.lock`
+    for the document, and that match the given requirement, newest first.
+*/
+pub async fn get_locked_versions(
+    clients: &Clients,
+    doc: &Document,
+    name: &str,
+    version_req: &PepVersionReq,
+) -> Vec<PepVersion> {
+    let Some(doc_dir) = doc
+        .url()
+        .to_file_path()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+    else {
+        return Vec::new();
+    };
+    let Some(lockfile) = clients
+        .lockfile
+        .get_lockfile(LockfileKind::Python, &doc_dir)
+        .await
+    else {
+        return Vec::new();
+    };
+
+    let mut versions = lockfile
+        .locked_versions(name)
+        .iter()
+        .filter_map(|v| v.parse::<PepVersion>().ok())
+        .filter(|v| version_req.matches(v))
+        .collect::<Vec<_>>();
+    versions.sort_unstable_by(|a, b| b.cmp(a));
+    versions
+}
+
+/**
+    Fetches the metadata of a package from the public registry, but only if it is
+    one of the indexes - a package from a private index may just share its name with an
+    unrelated public one, and private package names should never be sent there either.
+*/
+pub async fn get_pypi_metadata(
+    clients: &Clients,
+    indexes: &[PyPiIndex],
+    name: &str,
+) -> Option<RegistryMetadata> {
+    if !indexes.iter().any(PyPiIndex::is_pypi) {
+        return None;
+    }
+    clients.pypi.get_registry_metadata(name).await.ok()
+}
+
+/**
+    Gets the documentation, repository, and homepage links of a package,
+    from the `project_urls` and `home_page` fields of its registry metadata.
+*/
+pub fn get_project_links(meta: &RegistryMetadata) -> ProjectLinks {
+    let mut repo = None;
+    let mut docs = None;
+    let mut page = meta.info.home_page.as_deref();
+
+    if let Some(project_urls) = &meta.info.project_urls {
+        for (key, url) in project_urls {
+            let key_lower = key.to_ascii_lowercase();
+            if repo.is_none()
+                && (key_lower.contains("repository")
+                    || key_lower.contains("source")
+                    || key_lower == "github")
+            {
+                repo = Some(url.as_str());
+            } else if docs.is_none()
+                && (key_lower.contains("documentation") || key_lower.contains("docs"))
+            {
+                docs = Some(url.as_str());
+            } else if page.is_none()
+                && (key_lower.contains("homepage") || key_lower.contains("home"))
+            {
+                page = Some(url.as_str());
+            }
+        }
+    }
+
+    ProjectLinks::new(docs, repo, page)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use deputy_clients::{HttpTransport, TransportFuture, TransportRequest, TransportResponse};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct CountingTransport(AtomicUsize);
+
+    impl HttpTransport for CountingTransport {
+        fn execute(&self, _: TransportRequest) -> TransportFuture<'_> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(TransportResponse::new(404, "not found")) })
+        }
+    }
+
+    fn clients() -> (Arc<CountingTransport>, Clients) {
+        let transport = Arc::new(CountingTransport::default());
+        let clients = Clients::new(transport.clone());
+        clients.set_persistent_cache(false, None);
+        (transport, clients)
+    }
+
+    // get_pypi_metadata
+
+    #[tokio::test]
+    async fn does_not_fetch_metadata_for_private_indexes() {
+        let (transport, clients) = clients();
+        let indexes = [PyPiIndex::new("https://pypi.internal.example/simple")];
+
+        let meta = get_pypi_metadata(&clients, &indexes, "internal-package").await;
+        assert!(meta.is_none());
+        assert_eq!(transport.0.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn fetches_metadata_when_pypi_is_an_index() {
+        let (transport, clients) = clients();
+        let indexes = [
+            PyPiIndex::new("https://pypi.internal.example/simple"),
+            PyPiIndex::pypi(),
+        ];
+
+        get_pypi_metadata(&clients, &indexes, "requests").await;
+        assert_eq!(transport.0.load(Ordering::SeqCst), 1);
+    }
+}
@@ -8,7 +8,8 @@ use async_language_server::{
 use deputy_parser::rokit;
 use deputy_versioning::Versioned;
 
-use crate::shared::{CodeActionMetadata, ResolveContext};
+use crate::settings::EcosystemSettings;
+use crate::shared::{CodeActionMetadata, DiagnosticKind, ResolveContext};
 
 use super::Clients;
 
@@ -28,6 +29,7 @@ fn diag_source_for_doc(doc: &Document) -> String {
 
 pub async fn get_rokit_diagnostics(
     clients: &Clients,
+    settings: &EcosystemSettings,
     doc: &Document,
     node: Node<'_>,
 ) -> ServerResult<Vec<Diagnostic>> {
@@ -51,6 +53,7 @@ pub async fn get_rokit_diagnostics(
     if let Some(diag) = missing_diag {
         return Ok(vec![Diagnostic {
             source: Some(diag_source_for_doc(doc)),
+            code: Some(DiagnosticKind::InvalidSpec.into()),
             range: ts_range_to_lsp_range(dep.spec.range()),
             message: diag.to_string(),
             severity: Some(DiagnosticSeverity::WARNING), // Most likely during typing, don't emit a hard error
@@ -61,6 +64,9 @@ pub async fn get_rokit_diagnostics(
     let (Some(owner), Some(repository), Some(version)) = ranges.text(doc) else {
         return Ok(Vec::new());
     };
+    if settings.is_ignored(&format!("{owner}/{repository}")) {
+        return Ok(Vec::new());
+    }
 
     // Fetch releases and make sure there is at least one
     let parsed_version = version.trim_start_matches('v');
@@ -74,6 +80,7 @@ pub async fn get_rokit_diagnostics(
             if e.is_not_found_error() {
                 return Ok(vec![Diagnostic {
                     source: Some(diag_source_for_doc(doc)),
+                    code: Some(DiagnosticKind::UnknownPackage.into()),
                     range: ts_range_to_lsp_range(dep.spec.range()),
                     message: format!("No tool exists for `{owner}/{repository}`"),
                     severity: Some(DiagnosticSeverity::ERROR),
@@ -86,6 +93,7 @@ pub async fn get_rokit_diagnostics(
     if releases.is_empty() {
         return Ok(vec![Diagnostic {
             source: Some(diag_source_for_doc(doc)),
+            code: Some(DiagnosticKind::NoVersions.into()),
             range: ts_range_to_lsp_range(dep.spec.range()),
             message: format!("No releases exist for the tool `{owner}/{repository}`"),
             severity: Some(DiagnosticSeverity::ERROR),
@@ -102,6 +110,7 @@ pub async fn get_rokit_diagnostics(
     }) {
         return Ok(vec![Diagnostic {
             source: Some(diag_source_for_doc(doc)),
+            code: Some(DiagnosticKind::UnknownVersion.into()),
             range: ts_range_to_lsp_range(dep.spec.range()),
             message: format!(
                 "Version `{parsed_version}` does not exist for the tool `{owner}/{repository}`"
@@ -121,15 +130,18 @@ pub async fn get_rokit_diagnostics(
         let latest_version_string = latest_version.item_version.to_string();
 
         let metadata = CodeActionMetadata::LatestVersion {
+            package_name: format!("{owner}/{repository}"),
             edit_range: ts_range_to_lsp_range(ranges.version.unwrap()),
             source_uri: doc.url().clone(),
             source_text: version.to_string(),
             version_current: parsed_version.to_string(),
             version_latest: latest_version_string.clone(),
+            version_compatible: None,
         };
 
         return Ok(vec![Diagnostic {
             source: Some(diag_source_for_doc(doc)),
+            code: Some(DiagnosticKind::Outdated.into()),
             range: ts_range_to_lsp_range(dep.spec.range()),
             message: format!(
                 "A newer version of `{owner}/{repository}` is available.\
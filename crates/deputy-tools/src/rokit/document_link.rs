@@ -0,0 +1,32 @@
+use async_language_server::{
+    lsp_types::{DocumentLink, Range},
+    server::Document,
+    tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_parser::rokit;
+
+use crate::shared::{ProjectLink, document_link};
+
+pub fn get_rokit_document_link(doc: &Document, node: Node<'_>) -> Option<DocumentLink> {
+    let dep = rokit::parse_dependency(node)?;
+
+    let ranges = dep.spec_ranges(doc);
+    let (Some(owner_range), Some(repository_range)) = (ranges.owner, ranges.repository) else {
+        return None;
+    };
+    let (Some(owner), Some(repository), _) = ranges.text(doc) else {
+        return None;
+    };
+
+    let range = Range::new(
+        ts_range_to_lsp_range(owner_range).start,
+        ts_range_to_lsp_range(repository_range).end,
+    );
+    let link = ProjectLink::new(
+        "Repository",
+        format!("https://github.com/{owner}/{repository}"),
+    );
+    document_link(range, &link, &format!("{owner}/{repository}"))
+}
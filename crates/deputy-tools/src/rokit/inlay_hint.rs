@@ -0,0 +1,39 @@
+use async_language_server::{
+    lsp_types::InlayHint, server::Document, tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_parser::rokit;
+use deputy_versioning::Versioned;
+
+use crate::shared::version_inlay_hint;
+
+use super::Clients;
+
+pub async fn get_rokit_inlay_hint(
+    clients: &Clients,
+    doc: &Document,
+    node: Node<'_>,
+) -> Option<InlayHint> {
+    let dep = rokit::parse_dependency(node)?;
+
+    let ranges = dep.spec_ranges(doc);
+    let (Some(owner), Some(repository), Some(version)) = ranges.text(doc) else {
+        return None;
+    };
+
+    let parsed_version = version.trim_start_matches('v');
+    let releases = clients
+        .github
+        .get_repository_releases(owner, repository)
+        .await
+        .ok()?;
+    let latest = parsed_version.extract_latest_version(releases)?;
+
+    let version_latest = latest.item_version.to_string();
+    Some(version_inlay_hint(
+        ts_range_to_lsp_range(node.range()).end,
+        parsed_version,
+        (latest.item_version > latest.this_version).then_some(version_latest.as_str()),
+    ))
+}
@@ -1,22 +1,33 @@
 use async_language_server::{
-    lsp_types::{CompletionResponse, Diagnostic, DocumentDiagnosticParams, Hover, Position},
+    lsp_types::{
+        CompletionResponse, Diagnostic, DocumentDiagnosticParams, DocumentLink, Hover, InlayHint,
+        InlayHintParams, Position,
+    },
     server::{Document, ServerResult},
     tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
 };
-use futures::future::try_join_all;
+use futures::future::{join_all, try_join_all};
 use tracing::debug;
 
 use deputy_clients::Clients;
 use deputy_parser::rokit;
 
+use crate::settings::EcosystemSettings;
+use crate::shared::ranges_overlap;
+
 mod completion;
 mod constants;
 mod diagnostics;
+mod document_link;
 mod hover;
+mod inlay_hint;
 
 use self::completion::get_rokit_completions;
 use self::diagnostics::get_rokit_diagnostics;
+use self::document_link::get_rokit_document_link;
 use self::hover::get_rokit_hover;
+use self::inlay_hint::get_rokit_inlay_hint;
 
 #[derive(Debug, Clone)]
 pub struct Rokit {
@@ -62,6 +73,7 @@ impl Rokit {
         &self,
         doc: &Document,
         _params: DocumentDiagnosticParams,
+        settings: &EcosystemSettings,
     ) -> ServerResult<Vec<Diagnostic>> {
         // Find all dependencies
         let dependencies = rokit::find_all_dependencies(doc);
@@ -74,10 +86,45 @@ impl Rokit {
         let results = try_join_all(
             dependencies
                 .into_iter()
-                .map(|node| get_rokit_diagnostics(&self.clients, doc, node)),
+                .map(|node| get_rokit_diagnostics(&self.clients, settings, doc, node)),
         )
         .await?;
 
         Ok(results.into_iter().flatten().collect())
     }
+
+    pub(super) async fn inlay_hints(
+        &self,
+        doc: &Document,
+        params: InlayHintParams,
+    ) -> ServerResult<Vec<InlayHint>> {
+        // Find all dependencies within the requested range
+        let dependencies = rokit::find_all_dependencies(doc)
+            .into_iter()
+            .filter(|node| ranges_overlap(ts_range_to_lsp_range(node.range()), params.range))
+            .collect::<Vec<_>>();
+        if dependencies.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Fetch all inlay hints concurrently
+        debug!("Fetching rokit inlay hints for dependencies");
+        let results = join_all(
+            dependencies
+                .into_iter()
+                .map(|node| get_rokit_inlay_hint(&self.clients, doc, node)),
+        )
+        .await;
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    #[allow(clippy::unused_self)]
+    pub(super) fn document_links(&self, doc: &Document) -> Vec<DocumentLink> {
+        // Links are resolved later, so no package data is fetched here
+        rokit::find_all_dependencies(doc)
+            .into_iter()
+            .filter_map(|node| get_rokit_document_link(doc, node))
+            .collect()
+    }
 }
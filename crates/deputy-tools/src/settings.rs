@@ -0,0 +1,312 @@
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+
+use async_language_server::lsp_types::{Diagnostic, DiagnosticSeverity};
+use serde::{Deserialize, Deserializer};
+use serde_json::{Error as JsonError, Value as JsonValue};
+use tracing::warn;
+
+use deputy_clients::{HttpConfig, osv::models::AdvisorySource};
+
+use crate::shared::DiagnosticKind;
+use crate::tools::Tool;
+
+/**
+    Settings for the language server, sent by the editor as initialization
+    options when starting the server, or whenever its configuration changes.
+
+    All settings are optional, and any settings that are not given use defaults.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Settings {
+    pub cargo: EcosystemSettings,
+    #[serde(alias = "go")]
+    pub gomod: EcosystemSettings,
+    pub npm: EcosystemSettings,
+    #[serde(alias = "python", alias = "pypi")]
+    pub pyproject: EcosystemSettings,
+    pub rokit: EcosystemSettings,
+    pub wally: EcosystemSettings,
+    /// Severities for each kind of diagnostic, overriding the defaults
+    #[serde(deserialize_with = "deserialize_severities")]
+    pub severities: HashMap<DiagnosticKind, SeveritySetting>,
+    pub cache: CacheSettings,
+    pub advisories: AdvisorySettings,
+    pub http: HttpSettings,
+    /// If no requests should be sent, using only data from the persistent cache
+    pub offline: bool,
+}
+
+/**
+    Settings for a single ecosystem, such as Cargo or npm.
+*/
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EcosystemSettings {
+    /// If the ecosystem should be enabled at all
+    pub enabled: bool,
+    /// Packages that should never have any diagnostics, may end with `*` to match a prefix
+    pub ignored_packages: Vec<String>,
+    /// Registry url to use instead of the default public registry for the ecosystem
+    pub registry: Option<String>,
+}
+
+/**
+    The severity to use for a kind of diagnostic, or `off` to hide it entirely.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeveritySetting {
+    Error,
+    Warning,
+    #[serde(alias = "info")]
+    Information,
+    Hint,
+    #[serde(alias = "none")]
+    Off,
+}
+
+/**
+    Deserializes severities, skipping any unknown kinds or severities with a warning -
+    a typo in a single key should not make all other settings fall back to defaults.
+*/
+fn deserialize_severities<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<DiagnosticKind, SeveritySetting>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let map = HashMap::<String, JsonValue>::deserialize(deserializer)?;
+    let mut severities = HashMap::new();
+    for (key, value) in map {
+        let kind = serde_json::from_value::<DiagnosticKind>(JsonValue::String(key.clone()));
+        let severity = serde_json::from_value::<SeveritySetting>(value);
+        match (kind, severity) {
+            (Ok(kind), Ok(severity)) => {
+                severities.insert(kind, severity);
+            }
+            (Err(e), _) => warn!("Ignoring severity for unknown diagnostic '{key}' - {e}"),
+            (_, Err(e)) => warn!("Ignoring invalid severity for '{key}' - {e}"),
+        }
+    }
+    Ok(severities)
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CacheSettings {
+    /// How many seconds to cache registry data for, instead of the defaults
+    pub ttl_seconds: Option<u64>,
+    /// If registry data should also be cached on disk, to be available after restarting and while offline
+    pub persistent: bool,
+    /// Directory to store the persistent cache in, instead of the platform cache directory
+    pub directory: Option<String>,
+    /// If responses to requests sent with credentials, such as for private registries, should also be cached on disk
+    pub persist_authenticated: bool,
+}
+
+/**
+    Settings for security advisories, which are checked for Cargo, npm, Python and Go dependencies.
+*/
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AdvisorySettings {
+    /// If dependencies should be checked for advisories at all
+    pub enabled: bool,
+    /// Local directory with advisories as OSV `.json` files, used instead of any endpoint
+    pub directory: Option<String>,
+    /// OSV query endpoint to use instead of the public one at `api.osv.dev`
+    pub endpoint: Option<String>,
+}
+
+/**
+    Settings for how requests are sent, such as through a corporate proxy.
+
+    Proxies from `HTTPS_PROXY` / `NO_PROXY` and certificates from `SSL_CERT_FILE`
+    are always used, these settings are only needed to override or add to them.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HttpSettings {
+    /// Proxy url to send all requests through, instead of any proxy from the environment
+    pub proxy: Option<String>,
+    /// Comma-separated hosts that should not be proxied, instead of `NO_PROXY`
+    pub no_proxy: Option<String>,
+    /// PEM file with extra root certificates to trust
+    pub ca_file: Option<String>,
+    /// PEM file with a client certificate to send, which may also contain its private key
+    pub client_certificate: Option<String>,
+    /// PEM file with the private key for the client certificate
+    pub client_key: Option<String>,
+    pub connect_timeout_seconds: Option<u64>,
+    pub read_timeout_seconds: Option<u64>,
+}
+
+impl Settings {
+    /**
+        Parses settings from initialization options or a configuration change.
+
+        Settings may either be given directly, or nested under a `deputy`
+        key, which is how most editors send configuration sections.
+    */
+    #[allow(clippy::missing_errors_doc)]
+    pub fn from_json(value: JsonValue) -> Result<Self, JsonError> {
+        match value {
+            JsonValue::Null => Ok(Self::default()),
+            JsonValue::Object(mut map) if map.contains_key("deputy") => {
+                Self::from_json(map.remove("deputy").unwrap_or_default())
+            }
+            value => serde_json::from_value(value),
+        }
+    }
+
+    pub(crate) fn ecosystem(&self, tool: Tool) -> &EcosystemSettings {
+        match tool {
+            Tool::Cargo => &self.cargo,
+            Tool::GoMod => &self.gomod,
+            Tool::Npm => &self.npm,
+            Tool::Pyproject => &self.pyproject,
+            Tool::Rokit => &self.rokit,
+            Tool::Wally => &self.wally,
+        }
+    }
+
+    pub(crate) fn cache_ttl(&self) -> Option<Duration> {
+        self.cache.ttl_seconds.map(Duration::from_secs)
+    }
+
+    pub(crate) fn cache_directory(&self) -> Option<PathBuf> {
+        self.cache.directory.as_ref().map(PathBuf::from)
+    }
+
+    pub(crate) fn http_config(&self) -> HttpConfig {
+        let http = &self.http;
+        HttpConfig {
+            proxy: http.proxy.clone(),
+            no_proxy: http.no_proxy.clone(),
+            ca_file: http.ca_file.as_ref().map(PathBuf::from),
+            client_certificate: http.client_certificate.as_ref().map(PathBuf::from),
+            client_key: http.client_key.as_ref().map(PathBuf::from),
+            connect_timeout: http.connect_timeout_seconds.map(Duration::from_secs),
+            read_timeout: http.read_timeout_seconds.map(Duration::from_secs),
+        }
+    }
+
+    pub(crate) fn advisory_source(&self) -> Option<AdvisorySource> {
+        let advisories = &self.advisories;
+        if !advisories.enabled {
+            None
+        } else if let Some(dir) = &advisories.directory {
+            Some(AdvisorySource::Directory(PathBuf::from(dir)))
+        } else if let Some(url) = &advisories.endpoint {
+            Some(AdvisorySource::Endpoint(url.clone()))
+        } else {
+            Some(AdvisorySource::default())
+        }
+    }
+
+    /**
+        Applies any configured severity to the given diagnostic,
+        returning `None` if diagnostics of its kind are turned off.
+    */
+    pub(crate) fn apply_severity(&self, mut diag: Diagnostic) -> Option<Diagnostic> {
+        let setting = DiagnosticKind::from_diagnostic(&diag)
+            .and_then(|kind| self.severities.get(&kind))
+            .copied();
+        match setting {
+            Some(SeveritySetting::Error) => diag.severity = Some(DiagnosticSeverity::ERROR),
+            Some(SeveritySetting::Warning) => diag.severity = Some(DiagnosticSeverity::WARNING),
+            Some(SeveritySetting::Information) => {
+                diag.severity = Some(DiagnosticSeverity::INFORMATION);
+            }
+            Some(SeveritySetting::Hint) => diag.severity = Some(DiagnosticSeverity::HINT),
+            Some(SeveritySetting::Off) => return None,
+            None => {}
+        }
+        Some(diag)
+    }
+}
+
+impl EcosystemSettings {
+    /**
+        Checks if the package with the given name should be ignored.
+    */
+    #[must_use]
+    pub fn is_ignored(&self, name: &str) -> bool {
+        self.ignored_packages
+            .iter()
+            .any(|ignored| match ignored.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == ignored,
+            })
+    }
+}
+
+impl Default for EcosystemSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ignored_packages: Vec::new(),
+            registry: None,
+        }
+    }
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            ttl_seconds: None,
+            persistent: true,
+            directory: None,
+            persist_authenticated: false,
+        }
+    }
+}
+
+impl Default for AdvisorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            directory: None,
+            endpoint: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    // from_json
+
+    #[test]
+    fn reads_severities_by_code_and_camel_case_name() {
+        let settings = Settings::from_json(json!({
+            "severities": { "unknown-package": "error", "compatibleUpdate": "off" }
+        }))
+        .unwrap();
+        assert_eq!(
+            settings.severities,
+            HashMap::from([
+                (DiagnosticKind::UnknownPackage, SeveritySetting::Error),
+                (DiagnosticKind::CompatibleUpdate, SeveritySetting::Off),
+            ])
+        );
+    }
+
+    #[test]
+    fn skips_unknown_severities_and_keeps_other_settings() {
+        let settings = Settings::from_json(json!({
+            "severities": { "outdatd": "hint", "outdated": "loud", "vulnerable": "error" },
+            "offline": true
+        }))
+        .unwrap();
+        assert_eq!(
+            settings.severities,
+            HashMap::from([(DiagnosticKind::Vulnerable, SeveritySetting::Error)])
+        );
+        assert!(settings.offline);
+    }
+}
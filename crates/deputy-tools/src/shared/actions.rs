@@ -6,52 +6,382 @@ use async_language_server::lsp_types::{
 };
 use serde::{Deserialize, Serialize};
 
+use super::ResolveContext;
+
+/**
+    Source action kind for updating all dependencies in a document.
+*/
+pub const SOURCE_UPDATE_DEPENDENCIES: CodeActionKind =
+    CodeActionKind::new("source.updateDependencies");
+
+/**
+    Source action kind for updating all dependencies in a
+    document, as part of a "fix all" action - such as on save.
+*/
+pub const SOURCE_FIX_ALL_DEPUTY: CodeActionKind = CodeActionKind::new("source.fixAll.deputy");
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "kind")]
 pub enum CodeActionMetadata {
     LatestVersion {
+        package_name: String,
         edit_range: Range,
         source_uri: Url,
         source_text: String,
         version_current: String,
         version_latest: String,
+        /// The latest semver compatible version, if it differs from the latest version
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version_compatible: Option<String>,
+    },
+    PatchedVersion {
+        package_name: String,
+        advisory_id: String,
+        edit_range: Range,
+        source_uri: Url,
+        source_text: String,
+        version_current: String,
+        version_patched: String,
     },
 }
 
 impl CodeActionMetadata {
-    pub fn into_code_action(self, diag: Diagnostic) -> CodeActionOrCommand {
+    /**
+        Extracts code action metadata from the data of a diagnostic, if any.
+    */
+    #[must_use]
+    pub fn from_diagnostic(diag: &Diagnostic) -> Option<Self> {
+        diag.data
+            .as_ref()
+            .map(ResolveContext::<Self>::try_from)
+            .and_then(Result::ok)
+            .map(ResolveContext::into_inner)
+    }
+
+    /**
+        Creates the text edit that updates to the latest version,
+        or to the first patched version for security advisories.
+
+        This is the same edit used by the code action, and may
+        also be used to apply the action outside of an editor.
+
+        Returns `None` if the version requirement would not change, such as
+        for a requirement like `1` that already allows the latest `1.x` version.
+    */
+    #[must_use]
+    pub fn text_edit(&self) -> Option<TextEdit> {
+        match self {
+            Self::LatestVersion { version_latest, .. } => self.text_edit_to(version_latest),
+            Self::PatchedVersion {
+                version_patched, ..
+            } => self.text_edit_to(version_patched),
+        }
+    }
+
+    /**
+        Creates the text edit that updates to the latest semver compatible
+        version, if there is one that differs from the latest version, and
+        if the version requirement would change.
+    */
+    #[must_use]
+    pub fn text_edit_compatible(&self) -> Option<TextEdit> {
+        match self {
+            Self::LatestVersion {
+                version_compatible, ..
+            } => version_compatible
+                .as_deref()
+                .and_then(|version| self.text_edit_to(version)),
+            Self::PatchedVersion { .. } => None,
+        }
+    }
+
+    /**
+        The uri of the document that this code action applies to.
+    */
+    #[must_use]
+    pub fn source_uri(&self) -> &Url {
+        match self {
+            Self::LatestVersion { source_uri, .. } | Self::PatchedVersion { source_uri, .. } => {
+                source_uri
+            }
+        }
+    }
+
+    fn text_edit_to(&self, version: &str) -> Option<TextEdit> {
         match self {
             Self::LatestVersion {
                 edit_range,
-                source_uri,
                 source_text,
                 version_current,
-                version_latest,
+                ..
+            }
+            | Self::PatchedVersion {
+                edit_range,
+                source_text,
+                version_current,
                 ..
             } => {
-                let replaced = source_text.replace(&version_current, &version_latest);
-                let text_edit = TextEdit {
-                    new_text: if replaced == source_text {
-                        // failed to replace substring, just insert latest version
-                        version_latest
-                    } else {
-                        // means we replaced substring like ^x0.y0.z0 with ^x1.y1.z1
-                        replaced
-                    },
-                    range: edit_range,
-                };
-                CodeActionOrCommand::CodeAction(CodeAction {
-                    title: String::from("Update to latest version"),
-                    kind: Some(CodeActionKind::QUICKFIX),
-                    edit: Some(WorkspaceEdit {
-                        changes: Some(HashMap::from([(source_uri, vec![text_edit])])),
-                        ..Default::default()
-                    }),
-                    diagnostics: Some(vec![diag]),
-                    is_preferred: Some(true),
-                    ..Default::default()
+                let new_text = replace_version(source_text, version_current, version)?;
+                (new_text != *source_text).then_some(TextEdit {
+                    new_text,
+                    range: *edit_range,
                 })
             }
         }
     }
+
+    /**
+        Creates code actions for the given diagnostic.
+
+        If there is a semver compatible version that differs from the latest
+        version, this creates two actions - one for each version - and
+        prefers the compatible one, since it should not contain breaking changes.
+    */
+    #[must_use]
+    pub fn into_code_actions(self, diag: Diagnostic) -> Vec<CodeActionOrCommand> {
+        let edit_latest = self.text_edit();
+        let edit_compatible = self.text_edit_compatible();
+        match self {
+            Self::LatestVersion {
+                source_uri,
+                version_latest,
+                version_compatible,
+                ..
+            } => {
+                let mut actions = Vec::new();
+                if let (Some(version), Some(edit)) = (version_compatible, edit_compatible) {
+                    actions.push(quickfix(
+                        format!("Update to latest compatible ({version})"),
+                        diag.clone(),
+                        source_uri.clone(),
+                        edit,
+                        true,
+                    ));
+                }
+                if let Some(edit) = edit_latest {
+                    let is_preferred = actions.is_empty();
+                    actions.push(quickfix(
+                        format!("Update to latest ({version_latest})"),
+                        diag,
+                        source_uri,
+                        edit,
+                        is_preferred,
+                    ));
+                }
+                actions
+            }
+            Self::PatchedVersion {
+                source_uri,
+                version_patched,
+                ..
+            } => edit_latest
+                .map(|edit| {
+                    quickfix(
+                        format!("Update to patched version ({version_patched})"),
+                        diag,
+                        source_uri,
+                        edit,
+                        true,
+                    )
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/**
+    Replaces the current version in a version requirement with a new version,
+    such as `^1.2.3` with `^1.4.0`, keeping the precision of the requirement -
+    a requirement of `1.2` is replaced with `1.4`, and not with `1.4.0`.
+
+    Returns `None` if the current version is not part of the requirement.
+*/
+fn replace_version(requirement: &str, current: &str, new: &str) -> Option<String> {
+    let current_parts = current.split('.').collect::<Vec<_>>();
+    let new_parts = new.split('.').collect::<Vec<_>>();
+    (1..=current_parts.len()).rev().find_map(|precision| {
+        let written = current_parts[..precision].join(".");
+        let index = find_version(requirement, &written)?;
+        // Prerelease and build suffixes are part of the last component,
+        // so those versions are always written out in full
+        let replacement = if precision < new_parts.len() && !new.contains(['-', '+']) {
+            new_parts[..precision].join(".")
+        } else {
+            new.to_string()
+        };
+        Some(format!(
+            "{}{replacement}{}",
+            &requirement[..index],
+            &requirement[index + written.len()..]
+        ))
+    })
+}
+
+/**
+    Finds a version in a version requirement, making sure that it is not
+    part of a longer version - `1.2` is not found in `1.20` or in `11.2`.
+*/
+fn find_version(requirement: &str, version: &str) -> Option<usize> {
+    let is_version_char = |c: char| c.is_ascii_digit() || c == '.';
+    requirement
+        .match_indices(version)
+        .map(|(index, _)| index)
+        .find(|index| {
+            let before = requirement[..*index].chars().next_back();
+            let after = requirement[index + version.len()..].chars().next();
+            !before.is_some_and(is_version_char) && !after.is_some_and(is_version_char)
+        })
+}
+
+fn quickfix(
+    title: String,
+    diag: Diagnostic,
+    uri: Url,
+    edit: TextEdit,
+    is_preferred: bool,
+) -> CodeActionOrCommand {
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri, vec![edit])])),
+            ..Default::default()
+        }),
+        diagnostics: Some(vec![diag]),
+        is_preferred: Some(is_preferred),
+        ..Default::default()
+    })
+}
+
+/**
+    Creates a single source action that applies all of the given
+    code actions at once, merging their edits into one workspace edit.
+
+    Returns `None` if there are no code actions to apply.
+*/
+#[must_use]
+pub fn source_action_for_all(
+    kind: CodeActionKind,
+    title: impl Into<String>,
+    metadatas: impl IntoIterator<Item = CodeActionMetadata>,
+) -> Option<CodeActionOrCommand> {
+    let edit = workspace_edit_for_all(metadatas)?;
+
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title: title.into(),
+        kind: Some(kind),
+        edit: Some(edit),
+        ..Default::default()
+    }))
+}
+
+/**
+    Merges the edits of all the given code actions into a single workspace
+    edit, skipping any duplicates, so that they can be applied all at once.
+
+    Returns `None` if there are no code actions to apply.
+*/
+#[must_use]
+pub fn workspace_edit_for_all(
+    metadatas: impl IntoIterator<Item = CodeActionMetadata>,
+) -> Option<WorkspaceEdit> {
+    let mut changes = HashMap::<Url, Vec<TextEdit>>::new();
+    for metadata in metadatas {
+        // Updating to the latest version also covers any patched version
+        if matches!(metadata, CodeActionMetadata::PatchedVersion { .. }) {
+            continue;
+        }
+        let Some(text_edit) = metadata.text_edit() else {
+            continue;
+        };
+        let edits = changes.entry(metadata.source_uri().clone()).or_default();
+        if !edits.iter().any(|edit| edit.range == text_edit.range) {
+            edits.push(text_edit);
+        }
+    }
+
+    if changes.is_empty() {
+        return None;
+    }
+
+    Some(WorkspaceEdit {
+        changes: Some(changes),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use async_language_server::lsp_types::Position;
+
+    use super::*;
+
+    fn metadata(source_text: &str, current: &str, latest: &str) -> CodeActionMetadata {
+        CodeActionMetadata::LatestVersion {
+            package_name: String::from("package"),
+            edit_range: Range::new(Position::new(0, 0), Position::new(0, 1)),
+            source_uri: Url::parse("file:///project/Cargo.toml").unwrap(),
+            source_text: source_text.to_string(),
+            version_current: current.to_string(),
+            version_latest: latest.to_string(),
+            version_compatible: None,
+        }
+    }
+
+    // replace_version
+
+    #[test]
+    fn replaces_versions_keeping_precision() {
+        for (requirement, current, new, expected) in [
+            ("1.0.100", "1.0.100", "1.0.219", Some("1.0.219")),
+            ("^1.2.3", "1.2.3", "2.0.0", Some("^2.0.0")),
+            ("~1.2", "1.2.0", "1.4.1", Some("~1.4")),
+            ("1", "1.0.0", "1.0.219", Some("1")),
+            ("1", "1.0.0", "2.3.4", Some("2")),
+            (">=2.28", "2.28.0", "2.32.3", Some(">=2.32")),
+            (">=1.2, <1.20", "1.2.0", "1.5.0", Some(">=1.5, <1.20")),
+            ("v1.2.3", "1.2.3", "1.4.0", Some("v1.4.0")),
+            ("0.1", "0.1.0", "1.0.0-rc.1", Some("1.0.0-rc.1")),
+            ("*", "0.0.0", "1.0.0", None),
+            ("latest", "3.1.0", "3.2.0", None),
+            ("11.2", "1.2.0", "1.3.0", None),
+        ] {
+            assert_eq!(
+                replace_version(requirement, current, new).as_deref(),
+                expected,
+                "{requirement}"
+            );
+        }
+    }
+
+    // text_edit
+
+    #[test]
+    fn skips_edits_that_do_not_change_the_requirement() {
+        assert_eq!(metadata("1", "1.0.0", "1.0.219").text_edit(), None);
+        assert_eq!(metadata("*", "0.0.0", "1.0.0").text_edit(), None);
+        assert_eq!(
+            metadata("1", "1.0.0", "2.0.0")
+                .text_edit()
+                .map(|edit| edit.new_text),
+            Some(String::from("2"))
+        );
+    }
+
+    #[test]
+    fn skips_code_actions_without_edits() {
+        let diag = Diagnostic::default();
+        assert!(
+            metadata("1", "1.0.0", "1.0.219")
+                .into_code_actions(diag.clone())
+                .is_empty()
+        );
+        assert_eq!(
+            metadata("1.0.0", "1.0.0", "1.0.219")
+                .into_code_actions(diag)
+                .len(),
+            1
+        );
+    }
 }
@@ -0,0 +1,210 @@
+use std::fmt::Write;
+
+use async_language_server::{
+    lsp_types::{CodeDescription, Diagnostic, DiagnosticSeverity, Range, Url},
+    server::Document,
+};
+
+use deputy_clients::{
+    Clients,
+    osv::models::{AdvisoryEcosystem, Vulnerability},
+};
+
+use super::{CodeActionMetadata, DiagnosticKind, ResolveContext};
+
+/**
+    A dependency that should be checked for security advisories.
+*/
+#[derive(Debug, Clone)]
+pub struct AdvisoryCheck<'a> {
+    pub ecosystem: AdvisoryEcosystem,
+    /// The source of any diagnostics, such as `Cargo`
+    pub source: &'static str,
+    pub package_name: &'a str,
+    /// The lowest version matched by the version requirement
+    pub version: &'a str,
+    /// The versions in the lockfile that match the version requirement, if any,
+    /// which are checked instead of the lowest version since they are what is used
+    pub locked_versions: Vec<String>,
+    /// The full text of the version requirement, which edits will replace
+    pub version_text: &'a str,
+    pub range: Range,
+    pub edit_range: Range,
+}
+
+/**
+    Creates one diagnostic for each advisory that affects the
+    version of the given dependency, along with a quickfix to
+    update to the first patched version, if there is one.
+*/
+pub async fn get_advisory_diagnostics(
+    clients: &Clients,
+    doc: &Document,
+    check: AdvisoryCheck<'_>,
+) -> Vec<Diagnostic> {
+    let Ok(vulns) = clients
+        .osv
+        .get_vulnerabilities(check.ecosystem, check.package_name)
+        .await
+    else {
+        return Vec::new();
+    };
+
+    vulns
+        .iter()
+        .filter_map(|vuln| {
+            let version = find_affected_version(&check, vuln)?;
+            Some(advisory_diagnostic(doc, &check, vuln, version))
+        })
+        .collect()
+}
+
+/**
+    Finds the version of the dependency that is affected by the given advisory - one of the
+    locked versions if there are any, and otherwise the lowest version of the requirement.
+*/
+fn find_affected_version<'a>(
+    check: &'a AdvisoryCheck<'_>,
+    vuln: &Vulnerability,
+) -> Option<&'a str> {
+    if check.locked_versions.is_empty() {
+        return vuln
+            .affects(check.ecosystem, check.package_name, check.version)
+            .then_some(check.version);
+    }
+    check
+        .locked_versions
+        .iter()
+        .map(String::as_str)
+        .find(|version| vuln.affects(check.ecosystem, check.package_name, version))
+}
+
+fn advisory_diagnostic(
+    doc: &Document,
+    check: &AdvisoryCheck<'_>,
+    vuln: &Vulnerability,
+    version: &str,
+) -> Diagnostic {
+    let name = check.package_name;
+    let patched = vuln.first_patched_version(check.ecosystem, name, version);
+
+    let mut message = match vuln.informational() {
+        Some(kind) => format!("`{name}` {version} has an advisory ({kind})."),
+        None => format!("`{name}` {version} is affected by a security advisory."),
+    };
+    let _ = write!(message, "\n{}", vuln.id);
+    if !vuln.aliases.is_empty() {
+        let _ = write!(message, " ({})", vuln.aliases.join(", "));
+    }
+    let summary = vuln
+        .summary
+        .as_deref()
+        .or_else(|| vuln.details.as_deref().and_then(|d| d.lines().next()))
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    if let Some(summary) = summary {
+        let _ = write!(message, ": {summary}");
+    }
+    match &patched {
+        Some(patched) => {
+            let _ = write!(message, "\nPatched in `{patched}`");
+        }
+        None => message.push_str("\nNo patched version is available"),
+    }
+
+    let severity = if vuln.informational().is_none() && vuln.is_severe() {
+        DiagnosticSeverity::ERROR
+    } else {
+        DiagnosticSeverity::WARNING
+    };
+
+    let data = patched.map(|version_patched| {
+        ResolveContext {
+            uri: doc.url().clone(),
+            value: CodeActionMetadata::PatchedVersion {
+                package_name: name.to_string(),
+                advisory_id: vuln.id.clone(),
+                edit_range: check.edit_range,
+                source_uri: doc.url().clone(),
+                source_text: check.version_text.to_string(),
+                version_current: check.version.to_string(),
+                version_patched,
+            },
+        }
+        .into()
+    });
+
+    Diagnostic {
+        source: Some(check.source.to_string()),
+        code: Some(DiagnosticKind::Vulnerable.into()),
+        code_description: Url::parse(&vuln.url())
+            .ok()
+            .map(|href| CodeDescription { href }),
+        range: check.range,
+        message,
+        severity: Some(severity),
+        data,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vulnerability() -> Vulnerability {
+        Vulnerability::try_from_json(
+            r#"{
+                "id": "RUSTSEC-0000-0000",
+                "affected": [{
+                    "package": { "ecosystem": "crates.io", "name": "hyper" },
+                    "ranges": [{
+                        "type": "SEMVER",
+                        "events": [{ "introduced": "1.0.0" }, { "fixed": "1.4.0" }]
+                    }]
+                }]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn check(locked_versions: &[&str]) -> AdvisoryCheck<'static> {
+        AdvisoryCheck {
+            ecosystem: AdvisoryEcosystem::CratesIo,
+            source: "Cargo",
+            package_name: "hyper",
+            version: "1.0.0",
+            locked_versions: locked_versions.iter().map(ToString::to_string).collect(),
+            version_text: "1.0",
+            range: Range::default(),
+            edit_range: Range::default(),
+        }
+    }
+
+    // find_affected_version
+
+    #[test]
+    fn checks_lowest_version_without_lockfile() {
+        let check = check(&[]);
+        assert_eq!(
+            find_affected_version(&check, &vulnerability()),
+            Some("1.0.0")
+        );
+    }
+
+    #[test]
+    fn checks_locked_versions_instead_of_lowest_version() {
+        for (locked, expected) in [
+            (vec!["1.4.2"], None),
+            (vec!["1.3.0"], Some("1.3.0")),
+            (vec!["1.4.2", "1.2.0"], Some("1.2.0")),
+        ] {
+            let check = check(&locked);
+            assert_eq!(
+                find_affected_version(&check, &vulnerability()),
+                expected,
+                "{locked:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use async_language_server::lsp_types::{
+    CodeLens, Command, Diagnostic, DiagnosticSeverity, Range, WorkspaceEdit,
+};
+
+use super::{CodeActionMetadata, DiagnosticKind, workspace_edit_for_all};
+
+/**
+    Command that applies a workspace edit, used for updating all
+    dependencies in a section when clicking its code lens.
+
+    The only argument is the `WorkspaceEdit` to apply. This command is
+    executed by the language server, which asks the editor to apply the
+    edit, so that it works in any editor that supports code lenses.
+*/
+pub const COMMAND_UPDATE_DEPENDENCIES: &str = "deputy.updateDependencies";
+
+/**
+    Creates a code lens that summarizes the diagnostics for a section
+    of dependencies, such as `[dependencies]` in a `Cargo.toml` file,
+    and updates all dependencies in the section when clicked.
+
+    Compatible updates are not counted, since those are already allowed
+    by the version requirements, and would otherwise be shown for most
+    sections. Returns `None` if there is nothing to update in the section.
+*/
+#[must_use]
+pub fn dependency_section_code_lens(
+    section: Range,
+    diagnostics: &[Diagnostic],
+) -> Option<CodeLens> {
+    let diagnostics = diagnostics
+        .iter()
+        .filter(|diag| range_contains(section, diag.range))
+        .collect::<Vec<_>>();
+
+    let edit = workspace_edit_for_all(
+        diagnostics
+            .iter()
+            .filter(|diag| {
+                DiagnosticKind::from_diagnostic(diag) != Some(DiagnosticKind::CompatibleUpdate)
+            })
+            .filter_map(|diag| CodeActionMetadata::from_diagnostic(diag)),
+    )?;
+    let updates = count_edits(&edit);
+    let errors = diagnostics
+        .iter()
+        .filter(|diag| diag.severity == Some(DiagnosticSeverity::ERROR))
+        .count();
+
+    let mut parts = vec![pluralize(updates, "update available", "updates available")];
+    if errors > 0 {
+        parts.push(pluralize(errors, "error", "errors"));
+    }
+
+    Some(CodeLens {
+        range: Range::new(section.start, section.start),
+        command: Some(Command {
+            title: parts.join(" · "),
+            command: COMMAND_UPDATE_DEPENDENCIES.to_string(),
+            arguments: Some(vec![serde_json::to_value(edit).ok()?]),
+        }),
+        data: None,
+    })
+}
+
+fn count_edits(edit: &WorkspaceEdit) -> usize {
+    edit.changes
+        .as_ref()
+        .map_or(0, |changes| changes.values().map(Vec::len).sum())
+}
+
+fn range_contains(outer: Range, inner: Range) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+fn pluralize(count: usize, singular: &str, plural: &str) -> String {
+    if count == 1 {
+        format!("{count} {singular}")
+    } else {
+        format!("{count} {plural}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_language_server::lsp_types::{Position, Url};
+
+    use super::super::ResolveContext;
+    use super::*;
+
+    fn update(line: u32, kind: DiagnosticKind) -> Diagnostic {
+        let uri = Url::parse("file:///project/Cargo.toml").unwrap();
+        let range = Range::new(Position::new(line, 10), Position::new(line, 15));
+        let metadata = CodeActionMetadata::LatestVersion {
+            package_name: format!("package-{line}"),
+            edit_range: range,
+            source_uri: uri.clone(),
+            source_text: String::from("1.0.0"),
+            version_current: String::from("1.0.0"),
+            version_latest: String::from("2.0.0"),
+            version_compatible: None,
+        };
+        Diagnostic {
+            range,
+            code: Some(kind.into()),
+            severity: Some(DiagnosticSeverity::INFORMATION),
+            data: Some(
+                ResolveContext {
+                    uri,
+                    value: metadata,
+                }
+                .into(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    fn error(line: u32) -> Diagnostic {
+        Diagnostic {
+            range: Range::new(Position::new(line, 0), Position::new(line, 5)),
+            code: Some(DiagnosticKind::UnknownPackage.into()),
+            severity: Some(DiagnosticSeverity::ERROR),
+            ..Default::default()
+        }
+    }
+
+    fn section() -> Range {
+        Range::new(Position::new(1, 0), Position::new(5, 0))
+    }
+
+    // dependency_section_code_lens
+
+    #[test]
+    fn summarizes_updates_and_errors() {
+        let diagnostics = [
+            update(2, DiagnosticKind::Outdated),
+            update(3, DiagnosticKind::Outdated),
+            error(4),
+            // Outside of the section
+            update(7, DiagnosticKind::Outdated),
+            error(8),
+        ];
+        let lens = dependency_section_code_lens(section(), &diagnostics).unwrap();
+        let command = lens.command.unwrap();
+        assert_eq!(lens.range.start, section().start);
+        assert_eq!(command.title, "2 updates available · 1 error");
+        assert_eq!(command.command, COMMAND_UPDATE_DEPENDENCIES);
+
+        let edit: WorkspaceEdit =
+            serde_json::from_value(command.arguments.unwrap().remove(0)).unwrap();
+        assert_eq!(count_edits(&edit), 2);
+    }
+
+    #[test]
+    fn ignores_compatible_updates() {
+        let diagnostics = [
+            update(2, DiagnosticKind::CompatibleUpdate),
+            update(3, DiagnosticKind::Outdated),
+        ];
+        let lens = dependency_section_code_lens(section(), &diagnostics).unwrap();
+        assert_eq!(lens.command.unwrap().title, "1 update available");
+
+        let diagnostics = [update(2, DiagnosticKind::CompatibleUpdate)];
+        assert!(dependency_section_code_lens(section(), &diagnostics).is_none());
+    }
+
+    #[test]
+    fn skips_sections_without_updates() {
+        assert!(dependency_section_code_lens(section(), &[]).is_none());
+        assert!(dependency_section_code_lens(section(), &[error(2)]).is_none());
+    }
+}
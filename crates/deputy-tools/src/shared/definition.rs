@@ -0,0 +1,17 @@
+use std::path::Path;
+
+use async_language_server::lsp_types::{GotoDefinitionResponse, Location, Position, Range, Url};
+
+/**
+    Creates a definition response pointing to the start
+    of the given line, in the file at the given path.
+*/
+#[must_use]
+pub fn file_definition(path: &Path, line: u32) -> Option<GotoDefinitionResponse> {
+    let uri = Url::from_file_path(path).ok()?;
+    let position = Position::new(line, 0);
+    Some(GotoDefinitionResponse::Scalar(Location {
+        uri,
+        range: Range::new(position, position),
+    }))
+}
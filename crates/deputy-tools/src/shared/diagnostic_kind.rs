@@ -0,0 +1,93 @@
+use async_language_server::lsp_types::{Diagnostic, NumberOrString};
+use serde::{Deserialize, Serialize};
+
+/**
+    The kind of a diagnostic, shared across all ecosystems.
+
+    Each kind is sent as the diagnostic code, which makes it possible to
+    configure severities for each kind, and to tell diagnostics apart
+    when they are printed or exported outside of an editor.
+
+    Kinds are (de)serialized using the same kebab-case names as their codes,
+    but the camelCase names used by earlier versions are also accepted.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticKind {
+    /// A dependency is missing required fields, usually while typing
+    #[serde(alias = "invalidSpec")]
+    InvalidSpec,
+    /// A dependency refers to a package that does not exist
+    #[serde(alias = "unknownPackage")]
+    UnknownPackage,
+    /// A package exists, but has no published versions
+    #[serde(alias = "noVersions")]
+    NoVersions,
+    /// No version of a package matches the specified version
+    #[serde(alias = "unknownVersion")]
+    UnknownVersion,
+    /// The specified version is deprecated or yanked
+    #[serde(alias = "deprecatedVersion")]
+    DeprecatedVersion,
+    /// A dependency enables a feature or extra that does not exist
+    #[serde(alias = "unknownFeature")]
+    UnknownFeature,
+    /// A newer, incompatible version of a package is available
+    Outdated,
+    /// A newer version of a package is available, within the current version range
+    #[serde(alias = "compatibleUpdate")]
+    CompatibleUpdate,
+    /// The specified version is affected by a security advisory
+    Vulnerable,
+    /// Requests to a registry are being rejected, usually by a proxy
+    #[serde(alias = "requestsRejected")]
+    RequestsRejected,
+}
+
+impl DiagnosticKind {
+    #[must_use]
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::InvalidSpec => "invalid-spec",
+            Self::UnknownPackage => "unknown-package",
+            Self::NoVersions => "no-versions",
+            Self::UnknownVersion => "unknown-version",
+            Self::DeprecatedVersion => "deprecated-version",
+            Self::UnknownFeature => "unknown-feature",
+            Self::Outdated => "outdated",
+            Self::CompatibleUpdate => "compatible-update",
+            Self::Vulnerable => "vulnerable",
+            Self::RequestsRejected => "requests-rejected",
+        }
+    }
+
+    /**
+        Extracts the kind of a diagnostic from its code, if any.
+    */
+    #[must_use]
+    pub fn from_diagnostic(diag: &Diagnostic) -> Option<Self> {
+        let Some(NumberOrString::String(code)) = &diag.code else {
+            return None;
+        };
+        [
+            Self::InvalidSpec,
+            Self::UnknownPackage,
+            Self::NoVersions,
+            Self::UnknownVersion,
+            Self::DeprecatedVersion,
+            Self::UnknownFeature,
+            Self::Outdated,
+            Self::CompatibleUpdate,
+            Self::Vulnerable,
+            Self::RequestsRejected,
+        ]
+        .into_iter()
+        .find(|kind| kind.code() == code)
+    }
+}
+
+impl From<DiagnosticKind> for NumberOrString {
+    fn from(kind: DiagnosticKind) -> Self {
+        NumberOrString::String(kind.code().to_string())
+    }
+}
@@ -0,0 +1,165 @@
+use async_language_server::lsp_types::{DocumentLink, Range, Url};
+use serde::{Deserialize, Serialize};
+
+use super::ResolveContext;
+
+/**
+    Metadata for a document link that has not yet been resolved.
+
+    Finding the target of most links requires fetching data about the
+    package, which is only done when the editor resolves the link, so
+    that opening a manifest does not fetch data for every dependency.
+*/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentLinkMetadata {
+    pub package_name: String,
+}
+
+impl DocumentLinkMetadata {
+    /**
+        Extracts document link metadata from the data of a document link, if any.
+    */
+    #[must_use]
+    pub fn from_document_link(link: &DocumentLink) -> Option<Self> {
+        link.data
+            .as_ref()
+            .map(ResolveContext::<Self>::try_from)
+            .and_then(Result::ok)
+            .map(ResolveContext::into_inner)
+    }
+}
+
+/**
+    Creates a document link for a package, with a target that is resolved later.
+*/
+#[must_use]
+pub fn lazy_document_link(
+    uri: &Url,
+    range: Range,
+    package_name: impl Into<String>,
+) -> DocumentLink {
+    DocumentLink {
+        range,
+        target: None,
+        tooltip: None,
+        data: Some(
+            ResolveContext {
+                uri: uri.clone(),
+                value: DocumentLinkMetadata {
+                    package_name: package_name.into(),
+                },
+            }
+            .into(),
+        ),
+    }
+}
+
+/**
+    Creates a document link for a package, with a target that is already known.
+*/
+#[must_use]
+pub fn document_link(range: Range, link: &ProjectLink, package_name: &str) -> Option<DocumentLink> {
+    Some(DocumentLink {
+        range,
+        target: Some(Url::parse(&link.url).ok()?),
+        tooltip: Some(link.tooltip(package_name)),
+        data: None,
+    })
+}
+
+/**
+    Resolves the target of a lazy document link, if a target was found.
+*/
+#[must_use]
+pub fn resolve_document_link(
+    mut link: DocumentLink,
+    package_name: &str,
+    target: Option<ProjectLink>,
+) -> DocumentLink {
+    if let Some(target) = target
+        && let Ok(url) = Url::parse(&target.url)
+    {
+        link.target = Some(url);
+        link.tooltip = Some(target.tooltip(package_name));
+    }
+    link
+}
+
+/**
+    A single link for a package, along with a label describing it.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectLink {
+    pub label: &'static str,
+    pub url: String,
+}
+
+impl ProjectLink {
+    #[must_use]
+    pub fn new(label: &'static str, url: impl Into<String>) -> Self {
+        Self {
+            label,
+            url: url.into(),
+        }
+    }
+
+    fn tooltip(&self, package_name: &str) -> String {
+        format!("{} for `{package_name}`", self.label)
+    }
+}
+
+/**
+    Links to the documentation, repository, and homepage of a package.
+
+    Documentation and homepage links that are the same as the repository
+    link are removed, since they would otherwise be shown twice.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectLinks {
+    pub documentation: Option<String>,
+    pub repository: Option<String>,
+    pub homepage: Option<String>,
+}
+
+impl ProjectLinks {
+    #[must_use]
+    pub fn new(
+        documentation: Option<&str>,
+        repository: Option<&str>,
+        homepage: Option<&str>,
+    ) -> Self {
+        let is_repository =
+            |url: &str| repository.is_some_and(|repo| repo.eq_ignore_ascii_case(url));
+        Self {
+            documentation: documentation
+                .filter(|url| !is_repository(url))
+                .map(ToString::to_string),
+            repository: repository.map(ToString::to_string),
+            homepage: homepage
+                .filter(|url| !is_repository(url))
+                .map(ToString::to_string),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.documentation.is_none() && self.repository.is_none() && self.homepage.is_none()
+    }
+
+    /**
+        The most useful link of a package - its documentation,
+        followed by its homepage, and finally its repository.
+    */
+    #[must_use]
+    pub fn primary(&self) -> Option<ProjectLink> {
+        if let Some(docs) = &self.documentation {
+            Some(ProjectLink::new("Documentation", docs))
+        } else if let Some(page) = &self.homepage {
+            Some(ProjectLink::new("Homepage", page))
+        } else {
+            self.repository
+                .as_ref()
+                .map(|repo| ProjectLink::new("Repository", repo))
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use async_language_server::lsp_types::{
+    InlayHint, InlayHintLabel, InlayHintTooltip, Position, Range,
+};
+
+/**
+    Creates an inlay hint to show after a dependency - either with a newer
+    version, if one is available, or with the version that is currently used.
+
+    Editors do not support styling inlay hints, so up to date dependencies
+    are marked with a check mark instead, and outdated ones with an arrow.
+*/
+#[must_use]
+pub fn version_inlay_hint(
+    position: Position,
+    version_used: &str,
+    version_latest: Option<&str>,
+) -> InlayHint {
+    let (label, tooltip) = match version_latest {
+        Some(latest) => (
+            format!("↑ {latest}"),
+            format!("A newer version is available.\nUsing `{version_used}`, latest is `{latest}`"),
+        ),
+        None => (
+            format!("✓ {version_used}"),
+            format!("Up to date.\nUsing `{version_used}`, which is the latest version"),
+        ),
+    };
+    InlayHint {
+        position,
+        label: InlayHintLabel::String(label),
+        kind: None,
+        text_edits: None,
+        tooltip: Some(InlayHintTooltip::String(tooltip)),
+        padding_left: Some(true),
+        padding_right: None,
+        data: None,
+    }
+}
+
+/**
+    Checks if the given ranges overlap, which is used to only
+    create inlay hints for the range requested by the editor.
+*/
+#[must_use]
+pub fn ranges_overlap(a: Range, b: Range) -> bool {
+    a.start <= b.end && b.start <= a.end
+}
@@ -38,6 +38,20 @@ impl MarkdownBuilder {
         self.p(format!("Version **{}**", version.to_string()));
     }
 
+    /**
+        Adds locked versions to the end of the last line, which
+        should be the line added by [`MarkdownBuilder::version`].
+    */
+    pub fn locked<V: ToString>(&mut self, versions: impl IntoIterator<Item = V>) {
+        let versions = versions
+            .into_iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>();
+        if !versions.is_empty() {
+            self.extend_last(format!(" (locked: {})", versions.join(", ")));
+        }
+    }
+
     pub fn extend_last(&mut self, suffix: impl AsRef<str>) {
         let last = self.lines.last_mut().unwrap();
         last.push_str(suffix.as_ref());
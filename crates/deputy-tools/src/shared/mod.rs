@@ -1,11 +1,25 @@
 mod actions;
+mod advisories;
+mod code_lens;
 mod completion_map;
+mod definition;
+mod diagnostic_kind;
+mod document_links;
+mod inlay_hints;
 mod markdown_builder;
+mod rejections;
 mod resolve_context;
 mod strsim;
 
 pub use self::actions::*;
+pub use self::advisories::*;
+pub use self::code_lens::*;
 pub use self::completion_map::*;
+pub use self::definition::*;
+pub use self::diagnostic_kind::*;
+pub use self::document_links::*;
+pub use self::inlay_hints::*;
 pub use self::markdown_builder::*;
+pub use self::rejections::*;
 pub use self::resolve_context::*;
 pub use self::strsim::*;
@@ -0,0 +1,40 @@
+use std::collections::BTreeSet;
+
+use async_language_server::lsp_types::{Diagnostic, DiagnosticSeverity, Range};
+
+use deputy_clients::Clients;
+
+use super::DiagnosticKind;
+
+/**
+    Creates one diagnostic, at the top of the document, for each host that requests
+    are currently being rejected for by a proxy - without these, a misconfigured
+    proxy would only show up as every package silently having no information.
+
+    Only hosts that the document sent requests to, given by `used_hosts`, are
+    included, so that other manifests are not cluttered by hosts they never use.
+*/
+#[must_use]
+pub fn get_rejected_diagnostics(
+    clients: &Clients,
+    used_hosts: &BTreeSet<String>,
+) -> Vec<Diagnostic> {
+    clients
+        .rejected_hosts()
+        .into_iter()
+        .filter(|rejected| used_hosts.contains(&rejected.host))
+        .map(|rejected| Diagnostic {
+            source: Some(String::from("Deputy")),
+            code: Some(DiagnosticKind::RequestsRejected.into()),
+            range: Range::default(),
+            message: format!(
+                "Requests to `{}` are being rejected, most likely by a proxy.\n{}\n\
+                Set the proxy using `HTTPS_PROXY` or the `http.proxy` setting, and if it \
+                intercepts TLS, trust its root certificate using `SSL_CERT_FILE` or `http.caFile`.",
+                rejected.host, rejected.reason
+            ),
+            severity: Some(DiagnosticSeverity::WARNING),
+            ..Default::default()
+        })
+        .collect()
+}
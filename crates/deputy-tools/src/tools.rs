@@ -1,24 +1,46 @@
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hasher},
+    sync::{Arc, Mutex, PoisonError, RwLock},
+};
+
 use async_language_server::{
     lsp_types::{
-        CodeActionOrCommand, CodeActionParams, CompletionResponse, Diagnostic,
-        DocumentDiagnosticParams, Hover, Position,
+        CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeLens, CodeLensParams,
+        CompletionResponse, Diagnostic, DocumentDiagnosticParams, DocumentLink,
+        ExecuteCommandParams, GotoDefinitionResponse, Hover, InlayHint, InlayHintParams, Position,
+        Url, WorkspaceEdit,
     },
     server::{Document, ServerResult},
     tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
 };
 
-use deputy_clients::Clients;
+use deputy_clients::{Clients, track_used_hosts};
+use deputy_parser::{cargo, gomod, npm, pyproject, rokit, wally};
+use tracing::error;
 
 use crate::cargo::Cargo;
 use crate::gomod::GoMod;
 use crate::npm::Npm;
 use crate::pyproject::PyProject;
 use crate::rokit::Rokit;
-use crate::shared::{CodeActionMetadata, ResolveContext};
+use crate::settings::Settings;
+use crate::shared::{
+    COMMAND_UPDATE_DEPENDENCIES, CodeActionMetadata, SOURCE_FIX_ALL_DEPUTY,
+    SOURCE_UPDATE_DEPENDENCIES, dependency_section_code_lens, get_rejected_diagnostics,
+    source_action_for_all,
+};
 use crate::wally::Wally;
 
+// The latest diagnostics for each document, along with a hash of the document text
+type LatestDiagnostics = HashMap<Url, (u64, Vec<Diagnostic>)>;
+
 #[derive(Debug, Clone)]
 pub struct Tools {
+    clients: Clients,
+    settings: Arc<RwLock<Settings>>,
+    latest_diagnostics: Arc<Mutex<LatestDiagnostics>>,
     cargo: Cargo,
     gomod: GoMod,
     npm: Npm,
@@ -31,6 +53,9 @@ impl Tools {
     #[must_use]
     pub fn new(clients: &Clients) -> Self {
         Self {
+            clients: clients.clone(),
+            settings: Arc::new(RwLock::new(Settings::default())),
+            latest_diagnostics: Arc::new(Mutex::new(HashMap::new())),
             cargo: Cargo::new(clients.clone()),
             gomod: GoMod::new(clients.clone()),
             npm: Npm::new(clients.clone()),
@@ -40,6 +65,68 @@ impl Tools {
         }
     }
 
+    /**
+        Returns a copy of the current settings.
+    */
+    #[must_use]
+    pub fn settings(&self) -> Settings {
+        self.settings
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /**
+        Replaces the current settings, applying them right away.
+
+        Settings that affect registries and caching are forwarded to the
+        clients, and all other settings apply to any subsequent requests.
+    */
+    pub fn set_settings(&self, settings: Settings) {
+        // Changing the TTL rebuilds the caches, so only do it when needed
+        if settings.cache_ttl() != self.settings().cache_ttl() {
+            self.clients.set_cache_ttl(settings.cache_ttl());
+        }
+        self.clients
+            .set_persistent_cache(settings.cache.persistent, settings.cache_directory());
+        self.clients
+            .set_persist_authenticated(settings.cache.persist_authenticated);
+        self.clients.set_offline(settings.offline);
+        if let Err(e) = self.clients.set_http_config(settings.http_config()) {
+            error!("Invalid http settings, keeping the previous ones - {e}");
+        }
+        self.clients
+            .crates
+            .set_registry_override(settings.cargo.registry.clone());
+        self.clients
+            .golang
+            .set_registry_override(settings.gomod.registry.clone());
+        self.clients
+            .npm
+            .set_registry_override(settings.npm.registry.clone());
+        self.clients
+            .pypi
+            .set_registry_override(settings.pyproject.registry.clone());
+        self.clients.osv.set_source(settings.advisory_source());
+
+        *self
+            .settings
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = settings;
+        self.latest_diagnostics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /**
+        Finds the tool for the given document, if it is enabled.
+    */
+    fn enabled_tool(&self, doc: &Document) -> Option<Tool> {
+        let tool = Tool::from_document(doc)?;
+        self.settings().ecosystem(tool).enabled.then_some(tool)
+    }
+
     #[allow(clippy::missing_errors_doc)]
     pub async fn hover(
         &self,
@@ -47,7 +134,7 @@ impl Tools {
         pos: Position,
         node: Node<'_>,
     ) -> ServerResult<Option<Hover>> {
-        let Some(tool) = Tool::from_document(doc) else {
+        let Some(tool) = self.enabled_tool(doc) else {
             return Ok(None);
         };
 
@@ -68,7 +155,7 @@ impl Tools {
         pos: Position,
         node: Node<'_>,
     ) -> ServerResult<Option<CompletionResponse>> {
-        let Some(tool) = Tool::from_document(doc) else {
+        let Some(tool) = self.enabled_tool(doc) else {
             return Ok(None);
         };
 
@@ -82,51 +169,326 @@ impl Tools {
         }
     }
 
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn definition(
+        &self,
+        doc: &Document,
+        pos: Position,
+        node: Node<'_>,
+    ) -> ServerResult<Option<GotoDefinitionResponse>> {
+        let Some(tool) = self.enabled_tool(doc) else {
+            return Ok(None);
+        };
+
+        match tool {
+            Tool::Cargo => self.cargo.definition(doc, pos, node).await,
+            Tool::GoMod => self.gomod.definition(doc, pos, node).await,
+            Tool::Npm => self.npm.definition(doc, pos, node).await,
+            Tool::Pyproject => self.pyproject.definition(doc, pos, node).await,
+            // Tools and packages for Rokit and Wally are not stored anywhere we can point to
+            Tool::Rokit | Tool::Wally => Ok(None),
+        }
+    }
+
     #[allow(clippy::missing_errors_doc)]
     pub async fn diagnostics(
         &self,
         doc: &Document,
         params: DocumentDiagnosticParams,
     ) -> ServerResult<Vec<Diagnostic>> {
-        let Some(tool) = Tool::from_document(doc) else {
+        let Some(tool) = self.enabled_tool(doc) else {
+            return Ok(Vec::new());
+        };
+
+        let settings = self.settings();
+        let eco = settings.ecosystem(tool);
+        let (diagnostics, used_hosts) = track_used_hosts(async {
+            match tool {
+                Tool::Cargo => self.cargo.diagnostics(doc, params, eco).await,
+                Tool::GoMod => self.gomod.diagnostics(doc, params, eco).await,
+                Tool::Npm => self.npm.diagnostics(doc, params, eco).await,
+                Tool::Pyproject => self.pyproject.diagnostics(doc, params, eco).await,
+                Tool::Rokit => self.rokit.diagnostics(doc, params, eco).await,
+                Tool::Wally => self.wally.diagnostics(doc, params, eco).await,
+            }
+        })
+        .await;
+
+        let diagnostics = diagnostics?
+            .into_iter()
+            .chain(get_rejected_diagnostics(&self.clients, &used_hosts))
+            .filter_map(|diag| settings.apply_severity(diag))
+            .collect::<Vec<_>>();
+
+        self.latest_diagnostics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(doc.url().clone(), (document_hash(doc), diagnostics.clone()));
+
+        Ok(diagnostics)
+    }
+
+    /**
+        Gets the latest diagnostics for the given document, if
+        the document has not changed since they were computed.
+    */
+    #[must_use]
+    pub fn latest_diagnostics(&self, doc: &Document) -> Option<Vec<Diagnostic>> {
+        self.latest_diagnostics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(doc.url())
+            .filter(|(hash, _)| *hash == document_hash(doc))
+            .map(|(_, diagnostics)| diagnostics.clone())
+    }
+
+    /**
+        Forgets the latest diagnostics for the given document,
+        such as when it has been closed or deleted.
+    */
+    pub fn forget_diagnostics(&self, url: &Url) {
+        self.latest_diagnostics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(url);
+    }
+
+    /**
+        Gets the diagnostics for the given document, reusing the latest ones
+        if the document has not changed since, and computing them otherwise.
+    */
+    async fn cached_diagnostics(
+        &self,
+        doc: &Document,
+        params: DocumentDiagnosticParams,
+    ) -> ServerResult<Vec<Diagnostic>> {
+        match self.latest_diagnostics(doc) {
+            Some(diagnostics) => Ok(diagnostics),
+            None => self.diagnostics(doc, params).await,
+        }
+    }
+
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn inlay_hints(
+        &self,
+        doc: &Document,
+        params: InlayHintParams,
+    ) -> ServerResult<Vec<InlayHint>> {
+        let Some(tool) = self.enabled_tool(doc) else {
             return Ok(Vec::new());
         };
 
         match tool {
-            Tool::Cargo => self.cargo.diagnostics(doc, params).await,
-            Tool::GoMod => self.gomod.diagnostics(doc, params).await,
-            Tool::Npm => self.npm.diagnostics(doc, params).await,
-            Tool::Pyproject => self.pyproject.diagnostics(doc, params).await,
-            Tool::Rokit => self.rokit.diagnostics(doc, params).await,
-            Tool::Wally => self.wally.diagnostics(doc, params).await,
+            Tool::Cargo => self.cargo.inlay_hints(doc, params).await,
+            Tool::GoMod => self.gomod.inlay_hints(doc, params).await,
+            Tool::Npm => self.npm.inlay_hints(doc, params).await,
+            Tool::Pyproject => self.pyproject.inlay_hints(doc, params).await,
+            Tool::Rokit => self.rokit.inlay_hints(doc, params).await,
+            Tool::Wally => self.wally.inlay_hints(doc, params).await,
         }
     }
 
-    #[allow(clippy::unused_self)]
-    #[allow(clippy::unused_async)]
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn document_links(&self, doc: &Document) -> ServerResult<Vec<DocumentLink>> {
+        let Some(tool) = self.enabled_tool(doc) else {
+            return Ok(Vec::new());
+        };
+
+        match tool {
+            Tool::Cargo => self.cargo.document_links(doc).await,
+            Tool::GoMod => self.gomod.document_links(doc).await,
+            Tool::Npm => Ok(self.npm.document_links(doc)),
+            Tool::Pyproject => Ok(self.pyproject.document_links(doc)),
+            Tool::Rokit => Ok(self.rokit.document_links(doc)),
+            Tool::Wally => Ok(self.wally.document_links(doc)),
+        }
+    }
+
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn document_link_resolve(
+        &self,
+        doc: &Document,
+        link: DocumentLink,
+    ) -> ServerResult<DocumentLink> {
+        let Some(tool) = self.enabled_tool(doc) else {
+            return Ok(link);
+        };
+
+        match tool {
+            Tool::Cargo => self.cargo.resolve_document_link(doc, link).await,
+            Tool::Npm => self.npm.resolve_document_link(doc, link).await,
+            Tool::Pyproject => self.pyproject.resolve_document_link(doc, link).await,
+            Tool::Wally => self.wally.resolve_document_link(doc, link).await,
+            // Links for Go modules and Rokit tools are never resolved lazily
+            Tool::GoMod | Tool::Rokit => Ok(link),
+        }
+    }
+
+    /**
+        Creates a code lens above each section of dependencies, summarizing
+        available updates and errors from the diagnostics for the document.
+    */
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn code_lenses(
+        &self,
+        doc: &Document,
+        params: CodeLensParams,
+    ) -> ServerResult<Vec<CodeLens>> {
+        let Some(tool) = self.enabled_tool(doc) else {
+            return Ok(Vec::new());
+        };
+
+        let sections = match tool {
+            Tool::Cargo => cargo::find_all_dependency_sections(doc),
+            Tool::GoMod => gomod::find_all_dependency_sections(doc),
+            Tool::Npm => npm::find_all_dependency_sections(doc),
+            Tool::Pyproject => pyproject::find_all_dependency_sections(doc),
+            Tool::Rokit => rokit::find_all_dependency_sections(doc),
+            Tool::Wally => wally::find_all_dependency_sections(doc),
+        };
+        if sections.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let params = DocumentDiagnosticParams {
+            text_document: params.text_document,
+            identifier: None,
+            previous_result_id: None,
+            work_done_progress_params: params.work_done_progress_params,
+            partial_result_params: params.partial_result_params,
+        };
+        let diagnostics = self.cached_diagnostics(doc, params).await?;
+
+        Ok(sections
+            .into_iter()
+            .filter_map(|node| {
+                dependency_section_code_lens(ts_range_to_lsp_range(node.range()), &diagnostics)
+            })
+            .collect())
+    }
+
     #[allow(clippy::missing_errors_doc)]
     pub async fn code_action(
         &self,
         doc: &Document,
         params: CodeActionParams,
     ) -> ServerResult<Vec<CodeActionOrCommand>> {
-        if Tool::from_document(doc).is_none() {
+        if self.enabled_tool(doc).is_none() {
             return Ok(Vec::new());
         }
 
+        let only = params.context.only.as_deref();
         let mut actions = Vec::new();
-        for diag in params.context.diagnostics {
-            if let Some(Ok(action)) = diag
-                .data
-                .as_ref()
-                .map(ResolveContext::<CodeActionMetadata>::try_from)
-            {
-                actions.push(action.into_inner().into_code_action(diag.clone()));
+
+        let metadatas = params
+            .context
+            .diagnostics
+            .iter()
+            .filter_map(CodeActionMetadata::from_diagnostic)
+            .collect::<Vec<_>>();
+
+        if is_kind_requested(only, &CodeActionKind::QUICKFIX) {
+            for diag in params.context.diagnostics {
+                if let Some(metadata) = CodeActionMetadata::from_diagnostic(&diag) {
+                    actions.extend(metadata.into_code_actions(diag));
+                }
+            }
+        }
+
+        // Requests without any kinds, such as for the light bulb, are sent whenever the
+        // cursor moves, so instead of checking all of the dependencies in the document
+        // again, we reuse the diagnostics that the editor sent along with the request
+        let Some(only) = only else {
+            if metadatas.len() > 1 {
+                actions.extend(source_action_for_all(
+                    SOURCE_UPDATE_DEPENDENCIES,
+                    "Update all dependencies in selection",
+                    metadatas,
+                ));
+            }
+            return Ok(actions);
+        };
+
+        // Source actions apply to the whole document, not just the diagnostics
+        // in the requested range, so we need to gather all of those here
+        let source_kinds = [SOURCE_UPDATE_DEPENDENCIES, SOURCE_FIX_ALL_DEPUTY]
+            .into_iter()
+            .filter(|kind| {
+                // Only return "fix all" actions when explicitly asked for
+                if *kind == SOURCE_FIX_ALL_DEPUTY {
+                    only.iter()
+                        .any(|o| o != &CodeActionKind::SOURCE && is_kind_match(o, kind))
+                } else {
+                    is_kind_requested(Some(only), kind)
+                }
+            })
+            .collect::<Vec<_>>();
+        if !source_kinds.is_empty() {
+            // Editors usually pull diagnostics for the same document text right
+            // before this, so only check the dependencies again if it changed
+            let params = DocumentDiagnosticParams {
+                text_document: params.text_document,
+                identifier: None,
+                previous_result_id: None,
+                work_done_progress_params: params.work_done_progress_params,
+                partial_result_params: params.partial_result_params,
+            };
+            let metadatas = self
+                .cached_diagnostics(doc, params)
+                .await?
+                .iter()
+                .filter_map(CodeActionMetadata::from_diagnostic)
+                .collect::<Vec<_>>();
+            for kind in source_kinds {
+                actions.extend(source_action_for_all(
+                    kind,
+                    "Update all dependencies in this file",
+                    metadatas.iter().cloned(),
+                ));
             }
         }
 
         Ok(actions)
     }
+
+    /**
+        Gets the workspace edit to apply for a command, such as the one
+        sent by clicking a code lens to update all dependencies in a section.
+
+        Returns `None` if the command is unknown, or has no edit to apply.
+    */
+    #[must_use]
+    pub fn command_edit(&self, params: ExecuteCommandParams) -> Option<WorkspaceEdit> {
+        if params.command != COMMAND_UPDATE_DEPENDENCIES {
+            return None;
+        }
+        let edit = params.arguments.into_iter().next()?;
+        serde_json::from_value(edit).ok()
+    }
+}
+
+/**
+    Hashes the full text of a document, to tell if it changed.
+*/
+fn document_hash(doc: &Document) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for chunk in doc.text().chunks() {
+        hasher.write(chunk.as_bytes());
+    }
+    hasher.finish()
+}
+
+fn is_kind_requested(only: Option<&[CodeActionKind]>, kind: &CodeActionKind) -> bool {
+    only.is_none_or(|only| only.iter().any(|o| is_kind_match(o, kind)))
+}
+
+fn is_kind_match(requested: &CodeActionKind, kind: &CodeActionKind) -> bool {
+    // Kinds are hierarchical - requesting "source" also includes "source.fixAll"
+    let (requested, kind) = (requested.as_str(), kind.as_str());
+    kind == requested
+        || kind
+            .strip_prefix(requested)
+            .is_some_and(|rest| rest.starts_with('.'))
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -8,12 +8,14 @@ use async_language_server::{
 use deputy_parser::wally;
 use deputy_versioning::{VersionReq, VersionReqExt, Versioned};
 
-use crate::shared::{CodeActionMetadata, ResolveContext};
+use crate::settings::EcosystemSettings;
+use crate::shared::{CodeActionMetadata, DiagnosticKind, ResolveContext};
 
 use super::Clients;
 
 pub async fn get_wally_diagnostics(
     clients: &Clients,
+    settings: &EcosystemSettings,
     doc: &Document,
     index_url: &str,
     node: Node<'_>,
@@ -38,6 +40,7 @@ pub async fn get_wally_diagnostics(
     if let Some(diag) = missing_diag {
         return Ok(vec![Diagnostic {
             source: Some(String::from("Wally")),
+            code: Some(DiagnosticKind::InvalidSpec.into()),
             range: ts_range_to_lsp_range(dep.spec.range()),
             message: diag.to_string(),
             severity: Some(DiagnosticSeverity::WARNING), // Most likely during typing, don't emit a hard error
@@ -48,6 +51,9 @@ pub async fn get_wally_diagnostics(
     let (Some(owner), Some(repository), Some(version)) = ranges.text(doc) else {
         return Ok(Vec::new());
     };
+    if settings.is_ignored(&format!("{owner}/{repository}")) {
+        return Ok(Vec::new());
+    }
 
     // Fetch versions and make sure there is at least one
     let Ok(parsed_version_req) = VersionReq::parse(version) else {
@@ -65,6 +71,7 @@ pub async fn get_wally_diagnostics(
             if e.is_not_found_error() {
                 return Ok(vec![Diagnostic {
                     source: Some(String::from("Wally")),
+                    code: Some(DiagnosticKind::UnknownPackage.into()),
                     range: ts_range_to_lsp_range(dep.spec.range()),
                     message: format!("No package exists with the name `{owner}/{repository}`"),
                     severity: Some(DiagnosticSeverity::ERROR),
@@ -77,6 +84,7 @@ pub async fn get_wally_diagnostics(
     if metadatas.is_empty() {
         return Ok(vec![Diagnostic {
             source: Some(String::from("Wally")),
+            code: Some(DiagnosticKind::NoVersions.into()),
             range: ts_range_to_lsp_range(dep.spec.range()),
             message: format!("No versions exist for the package `{owner}/{repository}`"),
             severity: Some(DiagnosticSeverity::ERROR),
@@ -94,6 +102,7 @@ pub async fn get_wally_diagnostics(
     }) {
         return Ok(vec![Diagnostic {
             source: Some(String::from("Wally")),
+            code: Some(DiagnosticKind::UnknownVersion.into()),
             range: ts_range_to_lsp_range(dep.spec.range()),
             message: format!(
                 "Version `{parsed_version}` does not exist for the package `{owner}/{repository}`"
@@ -113,15 +122,18 @@ pub async fn get_wally_diagnostics(
         let latest_version_string = latest_version.item_version.to_string();
 
         let metadata = CodeActionMetadata::LatestVersion {
+            package_name: format!("{owner}/{repository}"),
             edit_range: ts_range_to_lsp_range(ranges.version.unwrap()),
             source_uri: doc.url().clone(),
             source_text: version.to_string(),
             version_current: parsed_version.to_string(),
             version_latest: latest_version_string.clone(),
+            version_compatible: None,
         };
 
         return Ok(vec![Diagnostic {
             source: Some(String::from("Wally")),
+            code: Some(DiagnosticKind::Outdated.into()),
             range: ts_range_to_lsp_range(dep.spec.range()),
             message: format!(
                 "A newer version of `{owner}/{repository}` is available.\
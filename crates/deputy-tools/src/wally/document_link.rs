@@ -0,0 +1,67 @@
+use async_language_server::{
+    lsp_types::{DocumentLink, Range},
+    server::Document,
+    tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_parser::wally;
+
+use crate::shared::{ProjectLink, ProjectLinks, lazy_document_link};
+
+use super::Clients;
+use super::constants::WALLY_DEFAULT_REGISTRY;
+
+pub fn get_wally_document_link(doc: &Document, node: Node<'_>) -> Option<DocumentLink> {
+    let dep = wally::parse_dependency(node)?;
+
+    let ranges = dep.spec_ranges(doc);
+    let (Some(owner_range), Some(repository_range)) = (ranges.owner, ranges.repository) else {
+        return None;
+    };
+    let (Some(owner), Some(repository), _) = ranges.text(doc) else {
+        return None;
+    };
+
+    let range = Range::new(
+        ts_range_to_lsp_range(owner_range).start,
+        ts_range_to_lsp_range(repository_range).end,
+    );
+    Some(lazy_document_link(
+        doc.url(),
+        range,
+        format!("{owner}/{repository}"),
+    ))
+}
+
+pub async fn resolve_wally_document_link(
+    clients: &Clients,
+    index_url: &str,
+    name: &str,
+) -> Option<ProjectLink> {
+    let (owner, repository) = name.split_once('/')?;
+    let metadatas = clients
+        .wally
+        .get_index_metadatas(index_url, owner, repository)
+        .await
+        .ok()?;
+    let metadata = metadatas.first()?;
+
+    let links = ProjectLinks::new(
+        None,
+        metadata.package.repository.as_deref(),
+        metadata.package.homepage.as_deref(),
+    );
+    links.primary().or_else(|| {
+        metadata
+            .package
+            .registry
+            .eq_ignore_ascii_case(WALLY_DEFAULT_REGISTRY)
+            .then(|| {
+                ProjectLink::new(
+                    "Package",
+                    format!("https://wally.run/package/{}", metadata.package.name),
+                )
+            })
+    })
+}
@@ -0,0 +1,41 @@
+use async_language_server::{
+    lsp_types::InlayHint, server::Document, tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
+};
+
+use deputy_parser::wally;
+use deputy_versioning::{VersionReq, VersionReqExt, Versioned};
+
+use crate::shared::version_inlay_hint;
+
+use super::Clients;
+
+pub async fn get_wally_inlay_hint(
+    clients: &Clients,
+    doc: &Document,
+    index_url: &str,
+    node: Node<'_>,
+) -> Option<InlayHint> {
+    let dep = wally::parse_dependency(node)?;
+
+    let ranges = dep.spec_ranges(doc);
+    let (Some(owner), Some(repository), Some(version)) = ranges.text(doc) else {
+        return None;
+    };
+
+    let version_req = VersionReq::parse(version).ok()?;
+    let version_used = version_req.minimum_version();
+    let metadatas = clients
+        .wally
+        .get_index_metadatas(index_url, owner, repository)
+        .await
+        .ok()?;
+    let latest = version_used.extract_latest_version(metadatas)?;
+
+    let version_latest = latest.item_version.to_string();
+    Some(version_inlay_hint(
+        ts_range_to_lsp_range(node.range()).end,
+        &version_used.to_string(),
+        (latest.item_version > latest.this_version).then_some(version_latest.as_str()),
+    ))
+}
@@ -1,25 +1,36 @@
 use std::io::{BufRead, BufReader};
 
 use async_language_server::{
-    lsp_types::{CompletionResponse, Diagnostic, DocumentDiagnosticParams, Hover, Position},
+    lsp_types::{
+        CompletionResponse, Diagnostic, DocumentDiagnosticParams, DocumentLink, Hover, InlayHint,
+        InlayHintParams, Position,
+    },
     server::{Document, ServerResult},
     tree_sitter::Node,
+    tree_sitter_utils::ts_range_to_lsp_range,
 };
-use futures::future::try_join_all;
+use futures::future::{join_all, try_join_all};
 use tracing::debug;
 
 use deputy_clients::Clients;
 use deputy_parser::wally;
 
+use crate::settings::EcosystemSettings;
+use crate::shared::{DocumentLinkMetadata, ranges_overlap, resolve_document_link};
+
 mod completion;
 mod constants;
 mod diagnostics;
+mod document_link;
 mod hover;
+mod inlay_hint;
 
 use self::completion::get_wally_completions;
 use self::constants::WALLY_DEFAULT_REGISTRY;
 use self::diagnostics::get_wally_diagnostics;
+use self::document_link::{get_wally_document_link, resolve_wally_document_link};
 use self::hover::get_wally_hover;
+use self::inlay_hint::get_wally_inlay_hint;
 
 #[derive(Debug, Clone)]
 pub struct Wally {
@@ -69,6 +80,7 @@ impl Wally {
         &self,
         doc: &Document,
         _params: DocumentDiagnosticParams,
+        settings: &EcosystemSettings,
     ) -> ServerResult<Vec<Diagnostic>> {
         // Find all dependencies
         let dependencies = wally::find_all_dependencies(doc);
@@ -80,15 +92,68 @@ impl Wally {
 
         // Fetch all diagnostics concurrently
         debug!("Fetching wally diagnostics for dependencies");
-        let results = try_join_all(
+        let results = try_join_all(dependencies.into_iter().map(|node| {
+            get_wally_diagnostics(&self.clients, settings, doc, index_url.as_str(), node)
+        }))
+        .await?;
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    pub(super) async fn inlay_hints(
+        &self,
+        doc: &Document,
+        params: InlayHintParams,
+    ) -> ServerResult<Vec<InlayHint>> {
+        // Find all dependencies within the requested range
+        let dependencies = wally::find_all_dependencies(doc)
+            .into_iter()
+            .filter(|node| ranges_overlap(ts_range_to_lsp_range(node.range()), params.range))
+            .collect::<Vec<_>>();
+        if dependencies.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let index_url = extract_wally_index_url(doc);
+
+        // Fetch all inlay hints concurrently
+        debug!("Fetching wally inlay hints for dependencies");
+        let results = join_all(
             dependencies
                 .into_iter()
-                .map(|node| get_wally_diagnostics(&self.clients, doc, index_url.as_str(), node)),
+                .map(|node| get_wally_inlay_hint(&self.clients, doc, index_url.as_str(), node)),
         )
-        .await?;
+        .await;
 
         Ok(results.into_iter().flatten().collect())
     }
+
+    #[allow(clippy::unused_self)]
+    pub(super) fn document_links(&self, doc: &Document) -> Vec<DocumentLink> {
+        // Links are resolved later, so no package data is fetched here
+        wally::find_all_dependencies(doc)
+            .into_iter()
+            .filter_map(|node| get_wally_document_link(doc, node))
+            .collect()
+    }
+
+    pub(super) async fn resolve_document_link(
+        &self,
+        doc: &Document,
+        link: DocumentLink,
+    ) -> ServerResult<DocumentLink> {
+        let Some(meta) = DocumentLinkMetadata::from_document_link(&link) else {
+            return Ok(link);
+        };
+        let index_url = extract_wally_index_url(doc);
+
+        debug!("Resolving document link: {meta:?}");
+        let target =
+            resolve_wally_document_link(&self.clients, index_url.as_str(), &meta.package_name)
+                .await;
+
+        Ok(resolve_document_link(link, &meta.package_name, target))
+    }
 }
 
 fn extract_wally_index_url(doc: &Document) -> String {
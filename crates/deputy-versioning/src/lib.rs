@@ -11,5 +11,5 @@ pub mod util;
 pub use self::pep_types::{PepVersion, PepVersionReq};
 pub use self::pep_version::{PepCompletionVersion, PepLatestVersion, PepVersioned};
 pub use self::pep_version_req::PepVersionReqExt;
-pub use self::version::{CompletionVersion, LatestVersion, Versioned};
+pub use self::version::{CompletionVersion, LatestVersion, LatestVersions, Versioned};
 pub use self::version_req::VersionReqExt;
@@ -34,6 +34,40 @@ pub fn trim_pep_version_specifiers(s: &str) -> String {
         .to_string()
 }
 
+/**
+    Checks if going from one version to another is a breaking change.
+
+    Follows the same rules as Cargo, where the leftmost nonzero version
+    component is considered the "major" version - meaning `1.2.3 -> 2.0.0`,
+    `0.2.3 -> 0.3.0` and `0.0.3 -> 0.0.4` are all breaking changes.
+
+    Version specifiers and any leading `v` are ignored. Versions that
+    can not be compared at all are considered to be breaking changes.
+*/
+#[must_use]
+pub fn is_breaking_version_change(from: &str, to: &str) -> bool {
+    let from = version_components(from);
+    let to = version_components(to);
+    if from.is_empty() || to.is_empty() {
+        return true;
+    }
+
+    let significant = from.iter().position(|c| *c != 0).unwrap_or(from.len() - 1);
+
+    (0..=significant).any(|i| from.get(i) != to.get(i))
+}
+
+fn version_components(s: &str) -> Vec<u64> {
+    let s = trim_pep_version_specifiers(&trim_semver_version_specifiers(s));
+    s.trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map_while(|c| c.parse().ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,4 +131,28 @@ mod tests {
         assert_eq!(trim_pep_version_specifiers("1.0.0"), "1.0.0");
         assert_eq!(trim_pep_version_specifiers("  >= 1.0  "), "1.0");
     }
+
+    #[test]
+    fn breaking_version_change_major() {
+        assert!(is_breaking_version_change("1.2.3", "2.0.0"));
+        assert!(is_breaking_version_change("^1.2", "3.0.0"));
+        assert!(is_breaking_version_change("v1.2.3", "v2.0.0"));
+        assert!(!is_breaking_version_change("1.2.3", "1.9.0"));
+        assert!(!is_breaking_version_change(">=1.2", "1.2.5"));
+        assert!(!is_breaking_version_change("v1.2.3", "v1.3.0"));
+    }
+
+    #[test]
+    fn breaking_version_change_zero_major() {
+        assert!(is_breaking_version_change("0.2.3", "0.3.0"));
+        assert!(is_breaking_version_change("0.0.3", "0.0.4"));
+        assert!(!is_breaking_version_change("0.2.3", "0.2.9"));
+        assert!(!is_breaking_version_change("0.2", "0.2.1"));
+    }
+
+    #[test]
+    fn breaking_version_change_unparseable() {
+        assert!(is_breaking_version_change("latest", "1.0.0"));
+        assert!(is_breaking_version_change("1.0.0", ""));
+    }
 }
@@ -9,6 +9,7 @@ use crate::util::trim_semver_version_specifiers;
     well as the associated data for whatever was compared to.
 */
 #[allow(dead_code)]
+#[derive(Clone)]
 pub struct LatestVersion<T> {
     pub is_semver_compatible: bool,
     pub is_exactly_compatible: bool,
@@ -17,6 +18,36 @@ pub struct LatestVersion<T> {
     pub item: T,
 }
 
+/**
+    The latest found versions from a comparison, split into the
+    latest version overall, and the latest semver compatible version.
+
+    The compatible version may be the same as the latest version, and is
+    `None` if no version compatible with the compared version was found.
+*/
+#[allow(dead_code)]
+pub struct LatestVersions<T> {
+    pub compatible: Option<LatestVersion<T>>,
+    pub latest: LatestVersion<T>,
+}
+
+impl<T> LatestVersions<T> {
+    /**
+        Returns the latest compatible version, if it is newer
+        than the compared version, but not the latest version.
+
+        This means that there are both compatible and incompatible
+        updates available, and either one may be chosen.
+    */
+    #[must_use]
+    pub fn compatible_update(&self) -> Option<&LatestVersion<T>> {
+        self.compatible.as_ref().filter(|compatible| {
+            compatible.item_version > compatible.this_version
+                && compatible.item_version < self.latest.item_version
+        })
+    }
+}
+
 /**
     A version to be used for completion purposes.
 
@@ -77,49 +108,13 @@ pub trait Versioned {
     {
         let this_version = self.parse_version().ok()?;
         let this_version_req = self.parse_version_req().ok();
-
-        let other_versions = other_versions
-            .into_iter()
-            .filter(|v| !v.deprecated())
-            .filter_map(|o| match o.parse_version() {
-                Ok(v) => Some((o, v)),
-                Err(_) => None,
-            })
-            .filter(|(_, v)| {
-                if v.pre.trim().is_empty() {
-                    // No prerelease = always consider
-                    true
-                } else {
-                    // Prerelease = only consider if this is also part of the same x.y.z prereleases
-                    v.major == this_version.major
-                        && v.minor == this_version.minor
-                        && v.patch == this_version.patch
-                }
-            })
-            .collect::<Vec<_>>();
-
-        let mut latest_versions = other_versions
-            .into_iter()
-            .map(|(item, item_version)| {
-                let is_exactly_compatible = item_version
-                    .to_string()
-                    .eq_ignore_ascii_case(&this_version.to_string());
-                LatestVersion {
-                    is_semver_compatible: is_exactly_compatible
-                        || this_version_req
-                            .as_ref()
-                            .is_some_and(|req| req.matches(&item_version)),
-                    is_exactly_compatible,
-                    this_version: this_version.clone(),
-                    item_version,
-                    item,
-                }
-            })
-            .collect::<Vec<_>>();
-
-        latest_versions.retain(|latest_version| filter_fn(latest_version));
-        latest_versions.sort_by_key(|latest_version| latest_version.item_version.clone());
-        latest_versions.pop()
+        sorted_latest_versions(
+            &this_version,
+            this_version_req.as_ref(),
+            other_versions,
+            filter_fn,
+        )
+        .pop()
     }
 
     fn extract_latest_version<I, V>(&self, other_versions: I) -> Option<LatestVersion<V>>
@@ -130,6 +125,43 @@ pub trait Versioned {
         self.extract_latest_version_filtered(other_versions, |_| true)
     }
 
+    fn extract_latest_versions_filtered<I, V, F>(
+        &self,
+        other_versions: I,
+        filter_fn: F,
+    ) -> Option<LatestVersions<V>>
+    where
+        I: IntoIterator<Item = V>,
+        V: Versioned + Clone,
+        F: Fn(&LatestVersion<V>) -> bool,
+    {
+        let this_version = self.parse_version().ok()?;
+        let this_version_req = self.parse_version_req().ok();
+
+        let mut versions = sorted_latest_versions(
+            &this_version,
+            this_version_req.as_ref(),
+            other_versions,
+            filter_fn,
+        );
+        let latest = versions.pop()?;
+        let compatible = if latest.is_semver_compatible {
+            Some(latest.clone())
+        } else {
+            versions.into_iter().rev().find(|v| v.is_semver_compatible)
+        };
+
+        Some(LatestVersions { compatible, latest })
+    }
+
+    fn extract_latest_versions<I, V>(&self, other_versions: I) -> Option<LatestVersions<V>>
+    where
+        I: IntoIterator<Item = V>,
+        V: Versioned + Clone,
+    {
+        self.extract_latest_versions_filtered(other_versions, |_| true)
+    }
+
     fn extract_completion_versions_filtered<I, V, F>(
         &self,
         potential_versions: I,
@@ -199,6 +231,64 @@ pub trait Versioned {
     }
 }
 
+/**
+    Compares all of the given versions to `this_version`, filtering
+    out deprecated and unrelated prerelease versions, and returns
+    the remaining versions sorted in ascending order.
+*/
+fn sorted_latest_versions<I, V, F>(
+    this_version: &Version,
+    this_version_req: Option<&VersionReq>,
+    other_versions: I,
+    filter_fn: F,
+) -> Vec<LatestVersion<V>>
+where
+    I: IntoIterator<Item = V>,
+    V: Versioned,
+    F: Fn(&LatestVersion<V>) -> bool,
+{
+    let other_versions = other_versions
+        .into_iter()
+        .filter(|v| !v.deprecated())
+        .filter_map(|o| match o.parse_version() {
+            Ok(v) => Some((o, v)),
+            Err(_) => None,
+        })
+        .filter(|(_, v)| {
+            if v.pre.trim().is_empty() {
+                // No prerelease = always consider
+                true
+            } else {
+                // Prerelease = only consider if this is also part of the same x.y.z prereleases
+                v.major == this_version.major
+                    && v.minor == this_version.minor
+                    && v.patch == this_version.patch
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut latest_versions = other_versions
+        .into_iter()
+        .map(|(item, item_version)| {
+            let is_exactly_compatible = item_version
+                .to_string()
+                .eq_ignore_ascii_case(&this_version.to_string());
+            LatestVersion {
+                is_semver_compatible: is_exactly_compatible
+                    || this_version_req.is_some_and(|req| req.matches(&item_version)),
+                is_exactly_compatible,
+                this_version: this_version.clone(),
+                item_version,
+                item,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    latest_versions.retain(|latest_version| filter_fn(latest_version));
+    latest_versions.sort_by_key(|latest_version| latest_version.item_version.clone());
+    latest_versions
+}
+
 impl Versioned for Version {
     fn raw_version_string(&self) -> String {
         self.to_string()
@@ -363,6 +453,78 @@ mod tests {
         assert!(result.is_none());
     }
 
+    // extract_latest_versions
+
+    #[derive(Clone)]
+    struct CloneVersion(&'static str);
+
+    impl Versioned for CloneVersion {
+        fn raw_version_string(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    #[test]
+    fn latest_versions_compatible_and_major() {
+        let versions = vec![
+            CloneVersion("1.0.0"),
+            CloneVersion("1.5.0"),
+            CloneVersion("2.0.0"),
+        ];
+        let result = "1.0.0".extract_latest_versions(versions).unwrap();
+        assert_eq!(result.latest.item_version.to_string(), "2.0.0");
+        assert!(!result.latest.is_semver_compatible);
+        let compatible = result.compatible_update().unwrap();
+        assert_eq!(compatible.item_version.to_string(), "1.5.0");
+        assert!(compatible.is_semver_compatible);
+    }
+
+    #[test]
+    fn latest_versions_only_compatible() {
+        let versions = vec![CloneVersion("1.0.0"), CloneVersion("1.5.0")];
+        let result = "1.0.0".extract_latest_versions(versions).unwrap();
+        assert_eq!(result.latest.item_version.to_string(), "1.5.0");
+        let compatible = result.compatible.as_ref().unwrap();
+        assert_eq!(compatible.item_version.to_string(), "1.5.0");
+        // Same as the latest version, so it is not a separate update
+        assert!(result.compatible_update().is_none());
+    }
+
+    #[test]
+    fn latest_versions_no_compatible_update() {
+        let versions = vec![CloneVersion("1.0.0"), CloneVersion("2.0.0")];
+        let result = "1.0.0".extract_latest_versions(versions).unwrap();
+        assert_eq!(result.latest.item_version.to_string(), "2.0.0");
+        // 1.0.0 is compatible, but it is not an update
+        assert_eq!(
+            result.compatible.as_ref().unwrap().item_version.to_string(),
+            "1.0.0"
+        );
+        assert!(result.compatible_update().is_none());
+    }
+
+    #[test]
+    fn latest_versions_none_compatible() {
+        let versions = vec![CloneVersion("2.0.0"), CloneVersion("3.0.0")];
+        let result = "1.0.0".extract_latest_versions(versions).unwrap();
+        assert_eq!(result.latest.item_version.to_string(), "3.0.0");
+        assert!(result.compatible.is_none());
+    }
+
+    #[test]
+    fn latest_versions_zero_major() {
+        let versions = vec![
+            CloneVersion("0.3.1"),
+            CloneVersion("0.3.4"),
+            CloneVersion("0.4.0"),
+        ];
+        // "0.3.1" parses as req "^0.3.1", which does not match 0.4.x
+        let result = "0.3.1".extract_latest_versions(versions).unwrap();
+        assert_eq!(result.latest.item_version.to_string(), "0.4.0");
+        let compatible = result.compatible_update().unwrap();
+        assert_eq!(compatible.item_version.to_string(), "0.3.4");
+    }
+
     // extract_completion_versions
 
     #[test]
@@ -0,0 +1,296 @@
+use std::{
+    env::current_dir,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Result, bail};
+use async_language_server::{
+    lsp_types::{
+        Diagnostic, DiagnosticSeverity, DocumentDiagnosticParams, PartialResultParams,
+        TextDocumentIdentifier, WorkDoneProgressParams,
+    },
+    server::Document,
+};
+use clap::Parser;
+use futures::{StreamExt, TryStreamExt, stream};
+use tracing::debug;
+
+use deputy_clients::{
+    Clients, HttpTransport, RecordingTransport, ReplayTransport, ReqwestTransport,
+};
+use deputy_tools::Tools;
+
+use crate::manifests::{find_manifests, load_manifest};
+
+use super::output::{CheckedManifest, OutputFormat};
+
+// How many manifests are checked at once - each one sends many requests of its own,
+// so checking all of them at once in a large workspace would only make each one slower
+const MAX_CONCURRENT_MANIFESTS: usize = 8;
+
+#[derive(Debug, Clone, Parser)]
+pub struct CheckCommand {
+    /// The directory or manifest to check - defaults to the current directory
+    pub path: Option<PathBuf>,
+    /// Also exit with a nonzero exit code if any warnings were found
+    #[arg(long)]
+    pub deny_warnings: bool,
+    /// The format to print diagnostics in
+    #[arg(long, value_enum, default_value_t)]
+    pub format: OutputFormat,
+    /// Do not send any requests, and only use data from the persistent cache
+    #[arg(long, env = "DEPUTY_OFFLINE")]
+    pub offline: bool,
+    /// Record all responses into the given directory, such as for reproducing a bug report
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+    /// Do not send any requests, and only use responses previously recorded using `--record`
+    #[arg(long, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+    #[arg(long, env = "GITHUB_TOKEN")]
+    pub github_token: Option<String>,
+}
+
+impl CheckCommand {
+    fn transport(&self, replay: Option<&Arc<ReplayTransport>>) -> Arc<dyn HttpTransport> {
+        if let Some(replay) = replay {
+            replay.clone()
+        } else if let Some(dir) = &self.record {
+            Arc::new(RecordingTransport::new(
+                Arc::new(ReqwestTransport::new()),
+                dir,
+            ))
+        } else {
+            Arc::new(ReqwestTransport::new())
+        }
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let cwd = current_dir()?;
+        let root = self.path.clone().unwrap_or_else(|| cwd.clone());
+
+        let replay = self
+            .replay
+            .as_ref()
+            .map(|dir| Arc::new(ReplayTransport::new(dir)));
+        let clients = Clients::new(self.transport(replay.as_ref()));
+        let tools = Tools::new(&clients);
+        clients.set_offline(self.offline);
+        if self.record.is_some() || self.replay.is_some() {
+            // Cached responses would otherwise never be recorded, or be used instead of replays
+            clients.set_persistent_cache(false, None);
+        }
+
+        if let Some(github_token) = &self.github_token {
+            clients.github.set_auth_token(github_token);
+        }
+
+        let results = check_manifests(&tools, &root).await?;
+
+        // Diagnostics for requests that were never recorded would be misleading
+        if let Some(replay) = &replay {
+            let unrecorded = replay.unrecorded();
+            if !unrecorded.is_empty() {
+                for request in &unrecorded {
+                    eprintln!("Request was not recorded: {request}");
+                }
+                bail!(
+                    "{} requests were not recorded, the recordings may be out of date",
+                    unrecorded.len()
+                );
+            }
+        }
+
+        let mut num_errors = 0;
+        let mut num_warnings = 0;
+        let mut manifests = Vec::new();
+        for result in &results {
+            for diag in &result.diagnostics {
+                match diag.severity {
+                    Some(DiagnosticSeverity::ERROR) => num_errors += 1,
+                    Some(DiagnosticSeverity::WARNING) => num_warnings += 1,
+                    _ => {}
+                }
+            }
+            manifests.push(CheckedManifest {
+                path: result.path.strip_prefix(&cwd).unwrap_or(&result.path),
+                diagnostics: &result.diagnostics,
+            });
+        }
+
+        print!("{}", self.format.render(&cwd, &manifests)?);
+
+        eprintln!(
+            "Checked {} manifests - found {num_errors} errors and {num_warnings} warnings",
+            results.len()
+        );
+
+        if num_errors > 0 || (self.deny_warnings && num_warnings > 0) {
+            bail!("dependency check failed");
+        }
+
+        Ok(())
+    }
+}
+
+/**
+    A manifest that was found on disk, along with its diagnostics.
+*/
+pub(super) struct ManifestDiagnostics {
+    pub path: PathBuf,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/**
+    Finds, loads, and checks all known manifests in the given directory.
+
+    Fails if any manifest could not be read or checked, since
+    its dependencies would otherwise silently go unchecked.
+*/
+pub(super) async fn check_manifests(
+    tools: &Tools,
+    root: &Path,
+) -> Result<Vec<ManifestDiagnostics>> {
+    let manifest_paths = find_manifests(root);
+    debug!(
+        "Found {} manifests in {}",
+        manifest_paths.len(),
+        root.display()
+    );
+
+    let mut documents = Vec::new();
+    for path in manifest_paths {
+        let Some(doc) = load_manifest(&path).await else {
+            bail!("failed to read manifest at {}", path.display());
+        };
+        documents.push((path, doc));
+    }
+
+    stream::iter(documents)
+        .map(|(path, doc)| check_document(tools, path, doc))
+        .buffered(MAX_CONCURRENT_MANIFESTS)
+        .try_collect()
+        .await
+}
+
+async fn check_document(
+    tools: &Tools,
+    path: PathBuf,
+    doc: Document,
+) -> Result<ManifestDiagnostics> {
+    let params = DocumentDiagnosticParams {
+        text_document: TextDocumentIdentifier {
+            uri: doc.url().clone(),
+        },
+        identifier: None,
+        previous_result_id: None,
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let diagnostics = match tools.diagnostics(&doc, params).await {
+        Ok(diagnostics) => diagnostics,
+        Err(e) => bail!("failed to check manifest at {} - {e}", path.display()),
+    };
+
+    Ok(ManifestDiagnostics { path, diagnostics })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use deputy_clients::{TransportFuture, TransportRequest, TransportResponse};
+
+    use super::*;
+
+    const MANIFEST: &str = r#"[package]
+name = "fixture"
+version = "0.1.0"
+
+[dependencies]
+serde = "1.0.0"
+serde-missing = "1"
+"#;
+
+    const SERDE_INDEX: &str = concat!(
+        r#"{"name":"serde","vers":"1.0.0","deps":[],"features":{},"yanked":false}"#,
+        "\n",
+        r#"{"name":"serde","vers":"2.0.0","deps":[],"features":{},"yanked":false}"#,
+    );
+
+    // Serves fixed responses, only used to write the fixtures that are then replayed
+    #[derive(Debug)]
+    struct FixtureTransport(HashMap<&'static str, &'static str>);
+
+    impl HttpTransport for FixtureTransport {
+        fn execute(&self, request: TransportRequest) -> TransportFuture<'_> {
+            let response = match self.0.get(request.url.as_str()) {
+                Some(body) => TransportResponse::new(200, *body),
+                None => TransportResponse::new(404, "not found"),
+            };
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("deputy-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    async fn check_with(transport: Arc<dyn HttpTransport>, root: &Path) -> Vec<Diagnostic> {
+        let clients = Clients::new(transport);
+        clients.set_persistent_cache(false, None);
+        let tools = Tools::new(&clients);
+
+        let mut results = check_manifests(&tools, root).await.unwrap();
+        assert_eq!(results.len(), 1);
+        results.remove(0).diagnostics
+    }
+
+    #[tokio::test]
+    async fn checks_manifest_with_replayed_responses() {
+        let root = temp_dir("check-replay");
+        let recordings = root.join("recordings");
+        std::fs::write(root.join("Cargo.toml"), MANIFEST).unwrap();
+
+        let fixtures = FixtureTransport(HashMap::from([(
+            "https://index.crates.io/se/rd/serde",
+            SERDE_INDEX,
+        )]));
+        let recording = RecordingTransport::new(Arc::new(fixtures), &recordings);
+        let recorded = check_with(Arc::new(recording), &root).await;
+
+        let replayed = check_with(Arc::new(ReplayTransport::new(&recordings)), &root).await;
+        assert_eq!(replayed, recorded);
+
+        let messages = replayed
+            .iter()
+            .map(|diag| diag.message.as_str())
+            .collect::<Vec<_>>();
+        assert!(
+            messages
+                .iter()
+                .any(|m| m.starts_with("A newer version of `serde` is available"))
+        );
+        assert!(messages.contains(&"No package exists with the name `serde-missing`"));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn fails_on_unreadable_manifest() {
+        let root = temp_dir("check-unreadable");
+        std::fs::write(root.join("Cargo.toml"), [0xff, 0xfe, 0xfd]).unwrap();
+
+        let clients = Clients::new(Arc::new(ReplayTransport::new(root.join("recordings"))));
+        let tools = Tools::new(&clients);
+        let result = check_manifests(&tools, &root).await;
+        assert!(result.is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,13 +1,20 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+mod check;
+mod output;
 mod serve;
+mod upgrade;
 
+use self::check::CheckCommand;
 use self::serve::ServeCommand;
+use self::upgrade::UpgradeCommand;
 
 #[derive(Debug, Clone, Subcommand)]
 pub enum CliSubcommand {
     Serve(ServeCommand),
+    Check(CheckCommand),
+    Upgrade(UpgradeCommand),
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -25,6 +32,8 @@ impl Cli {
     pub async fn run(self) -> Result<()> {
         match self.subcommand {
             CliSubcommand::Serve(cmd) => cmd.run().await,
+            CliSubcommand::Check(cmd) => cmd.run().await,
+            CliSubcommand::Upgrade(cmd) => cmd.run().await,
         }
     }
 }
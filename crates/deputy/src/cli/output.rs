@@ -0,0 +1,515 @@
+use std::{fmt::Write, path::Path};
+
+use anyhow::Result;
+use async_language_server::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use clap::ValueEnum;
+use serde_json::{Value as JsonValue, json};
+
+use deputy_tools::CodeActionMetadata;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const INFORMATION_URI: &str = "https://github.com/filiptibell/deputy";
+const SARIF_SRCROOT: &str = "%SRCROOT%";
+
+/**
+    The format to print diagnostics in, when running outside of an editor.
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable, one line per diagnostic
+    #[default]
+    Text,
+    /// JSON lines, one object per diagnostic
+    Json,
+    /// A single SARIF 2.1.0 log, for code scanning dashboards
+    Sarif,
+    /// GitHub Actions workflow command annotations
+    Github,
+}
+
+/**
+    A manifest that has been checked, along with its diagnostics.
+
+    The path should be relative to the current directory, if possible.
+*/
+pub struct CheckedManifest<'a> {
+    pub path: &'a Path,
+    pub diagnostics: &'a [Diagnostic],
+}
+
+impl OutputFormat {
+    /**
+        Renders diagnostics for all of the given manifests in this format.
+
+        The root should be the absolute directory that relative manifest paths
+        are relative to, and is used for the `%SRCROOT%` base of SARIF logs.
+    */
+    pub fn render(self, root: &Path, manifests: &[CheckedManifest<'_>]) -> Result<String> {
+        let diagnostics = manifests
+            .iter()
+            .flat_map(|m| m.diagnostics.iter().map(move |d| (m.path, d)));
+        Ok(match self {
+            Self::Text => diagnostics
+                .map(|(path, diag)| format_text(path, diag) + "\n")
+                .collect(),
+            Self::Json => {
+                let mut lines = String::new();
+                for (path, diag) in diagnostics {
+                    lines.push_str(&serde_json::to_string(&format_json(path, diag))?);
+                    lines.push('\n');
+                }
+                lines
+            }
+            Self::Sarif => {
+                let mut sarif = serde_json::to_string_pretty(&format_sarif(root, diagnostics))?;
+                sarif.push('\n');
+                sarif
+            }
+            Self::Github => diagnostics
+                .map(|(path, diag)| format_github(path, diag) + "\n")
+                .collect(),
+        })
+    }
+}
+
+fn format_text(path: &Path, diag: &Diagnostic) -> String {
+    let severity = match diag.severity {
+        Some(DiagnosticSeverity::ERROR) => "error",
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        Some(DiagnosticSeverity::HINT) => "hint",
+        _ => "info",
+    };
+
+    format!(
+        "{}:{}:{}: {severity} [{}] {}",
+        path.display(),
+        diag.range.start.line + 1,
+        diag.range.start.character + 1,
+        source(diag),
+        single_line(&diag.message),
+    )
+}
+
+fn format_json(path: &Path, diag: &Diagnostic) -> JsonValue {
+    let severity = match diag.severity {
+        Some(DiagnosticSeverity::ERROR) => "error",
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        Some(DiagnosticSeverity::HINT) => "hint",
+        _ => "information",
+    };
+
+    json!({
+        "file": portable_path(path),
+        "source": source(diag),
+        "code": diag.code,
+        "severity": severity,
+        "range": diag.range,
+        "message": diag.message,
+        "metadata": CodeActionMetadata::from_diagnostic(diag),
+    })
+}
+
+fn format_sarif<'a>(
+    root: &Path,
+    diagnostics: impl Iterator<Item = (&'a Path, &'a Diagnostic)>,
+) -> JsonValue {
+    let mut rules = Vec::<String>::new();
+    let mut results = Vec::new();
+
+    for (path, diag) in diagnostics {
+        let rule_id = rule_id(diag);
+        let rule_index = rules.iter().position(|r| r == &rule_id).unwrap_or_else(|| {
+            rules.push(rule_id.clone());
+            rules.len() - 1
+        });
+
+        let level = match diag.severity {
+            Some(DiagnosticSeverity::ERROR) => "error",
+            Some(DiagnosticSeverity::WARNING) => "warning",
+            _ => "note",
+        };
+
+        let location = sarif_artifact_location(path);
+        let metadata = CodeActionMetadata::from_diagnostic(diag);
+
+        // SARIF uses 1-based lines and columns, and UTF-16 columns by default, same as LSP
+        let mut result = json!({
+            "ruleId": rule_id,
+            "ruleIndex": rule_index,
+            "level": level,
+            "message": { "text": diag.message },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": location,
+                    "region": {
+                        "startLine": diag.range.start.line + 1,
+                        "startColumn": diag.range.start.character + 1,
+                        "endLine": diag.range.end.line + 1,
+                        "endColumn": diag.range.end.character + 1,
+                    },
+                },
+            }],
+            "properties": {
+                "source": source(diag),
+                "metadata": metadata,
+            },
+        });
+
+        if let Some(metadata) = &metadata
+            && let Some(edit) = metadata.text_edit()
+        {
+            let description = match metadata {
+                CodeActionMetadata::LatestVersion { .. } => "Update to latest version",
+                CodeActionMetadata::PatchedVersion { .. } => "Update to patched version",
+            };
+            result["fixes"] = json!([{
+                "description": { "text": description },
+                "artifactChanges": [{
+                    "artifactLocation": location,
+                    "replacements": [{
+                        "deletedRegion": {
+                            "startLine": edit.range.start.line + 1,
+                            "startColumn": edit.range.start.character + 1,
+                            "endLine": edit.range.end.line + 1,
+                            "endColumn": edit.range.end.character + 1,
+                        },
+                        "insertedContent": { "text": edit.new_text },
+                    }],
+                }],
+            }]);
+        }
+
+        results.push(result);
+    }
+
+    let rules = rules
+        .into_iter()
+        .map(|id| json!({ "id": id, "name": id }))
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "deputy",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": INFORMATION_URI,
+                    "rules": rules,
+                },
+            },
+            "originalUriBaseIds": {
+                SARIF_SRCROOT: { "uri": format!("{}/", file_uri(root).trim_end_matches('/')) },
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn format_github(path: &Path, diag: &Diagnostic) -> String {
+    let command = match diag.severity {
+        Some(DiagnosticSeverity::ERROR) => "error",
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        _ => "notice",
+    };
+
+    let title = format!("Deputy ({})", source(diag));
+
+    format!(
+        "::{command} file={},line={},col={},endLine={},endColumn={},title={}::{}",
+        escape_github_property(&portable_path(path)),
+        diag.range.start.line + 1,
+        diag.range.start.character + 1,
+        diag.range.end.line + 1,
+        diag.range.end.character + 1,
+        escape_github_property(&title),
+        escape_github_data(&diag.message),
+    )
+}
+
+fn source(diag: &Diagnostic) -> &str {
+    diag.source.as_deref().unwrap_or("Deputy")
+}
+
+fn rule_id(diag: &Diagnostic) -> String {
+    match &diag.code {
+        Some(NumberOrString::String(s)) => s.clone(),
+        Some(NumberOrString::Number(n)) => n.to_string(),
+        None => source(diag).to_ascii_lowercase(),
+    }
+}
+
+fn single_line(message: &str) -> String {
+    // Messages may span multiple lines, keep the output to one line per diagnostic
+    message.lines().collect::<Vec<_>>().join(" ")
+}
+
+fn portable_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+fn sarif_artifact_location(path: &Path) -> JsonValue {
+    // Relative paths resolve against the source root, anything else is left as an absolute file uri
+    if path.is_absolute() {
+        json!({ "uri": file_uri(path) })
+    } else {
+        json!({ "uri": percent_encode_path(&portable_path(path)), "uriBaseId": SARIF_SRCROOT })
+    }
+}
+
+fn file_uri(path: &Path) -> String {
+    let path = portable_path(path);
+    if path.starts_with('/') {
+        format!("file://{}", percent_encode_path(&path))
+    } else {
+        // Windows paths such as C:/dir need an extra slash before the drive letter
+        format!("file:///{}", percent_encode_path(&path))
+    }
+}
+
+fn percent_encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'/' | b':' | b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+fn escape_github_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_github_property(s: &str) -> String {
+    escape_github_data(s)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use async_language_server::lsp_types::{Position, Range};
+
+    use super::*;
+
+    fn diagnostic(
+        line: u32,
+        severity: DiagnosticSeverity,
+        code: Option<&str>,
+        message: &str,
+    ) -> Diagnostic {
+        Diagnostic {
+            range: Range::new(Position::new(line, 10), Position::new(line, 17)),
+            severity: Some(severity),
+            code: code.map(|c| NumberOrString::String(c.to_string())),
+            source: Some("Cargo".to_string()),
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn render(format: OutputFormat) -> String {
+        let cargo = [
+            diagnostic(
+                4,
+                DiagnosticSeverity::WARNING,
+                Some("outdated"),
+                "A newer version of `serde` is available.\nThe latest version is `1.0.200`",
+            ),
+            diagnostic(
+                7,
+                DiagnosticSeverity::ERROR,
+                Some("unknown-package"),
+                "No package exists with the name `serdee`, did you mean: `serde`?",
+            ),
+        ];
+        let npm = [diagnostic(2, DiagnosticSeverity::HINT, None, "100% done")];
+        let manifests = [
+            CheckedManifest {
+                path: Path::new("Cargo.toml"),
+                diagnostics: &cargo,
+            },
+            CheckedManifest {
+                path: Path::new("web app/package.json"),
+                diagnostics: &npm,
+            },
+        ];
+        format
+            .render(Path::new("/work/my repo"), &manifests)
+            .unwrap()
+    }
+
+    // render
+
+    #[test]
+    fn renders_text() {
+        assert_eq!(
+            render(OutputFormat::Text),
+            "\
+Cargo.toml:5:11: warning [Cargo] A newer version of `serde` is available. The latest version is `1.0.200`
+Cargo.toml:8:11: error [Cargo] No package exists with the name `serdee`, did you mean: `serde`?
+web app/package.json:3:11: hint [Cargo] 100% done
+"
+        );
+    }
+
+    #[test]
+    fn renders_json() {
+        let lines = render(OutputFormat::Json)
+            .lines()
+            .map(|line| serde_json::from_str::<JsonValue>(line).unwrap())
+            .collect::<Vec<_>>();
+        let range = |line: u32| {
+            json!({
+                "start": { "line": line, "character": 10 },
+                "end": { "line": line, "character": 17 },
+            })
+        };
+        assert_eq!(
+            lines,
+            [
+                json!({
+                    "file": "Cargo.toml",
+                    "source": "Cargo",
+                    "code": "outdated",
+                    "severity": "warning",
+                    "range": range(4),
+                    "message": "A newer version of `serde` is available.\nThe latest version is `1.0.200`",
+                    "metadata": null,
+                }),
+                json!({
+                    "file": "Cargo.toml",
+                    "source": "Cargo",
+                    "code": "unknown-package",
+                    "severity": "error",
+                    "range": range(7),
+                    "message": "No package exists with the name `serdee`, did you mean: `serde`?",
+                    "metadata": null,
+                }),
+                json!({
+                    "file": "web app/package.json",
+                    "source": "Cargo",
+                    "code": null,
+                    "severity": "hint",
+                    "range": range(2),
+                    "message": "100% done",
+                    "metadata": null,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_sarif() {
+        let sarif = serde_json::from_str::<JsonValue>(&render(OutputFormat::Sarif)).unwrap();
+        let result =
+            |rule: &str, index: usize, level: &str, uri: &str, line: u32, message: &str| {
+                json!({
+                    "ruleId": rule,
+                    "ruleIndex": index,
+                    "level": level,
+                    "message": { "text": message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" },
+                            "region": {
+                                "startLine": line,
+                                "startColumn": 11,
+                                "endLine": line,
+                                "endColumn": 18,
+                            },
+                        },
+                    }],
+                    "properties": { "source": "Cargo", "metadata": null },
+                })
+            };
+        assert_eq!(
+            sarif,
+            json!({
+                "$schema": SARIF_SCHEMA,
+                "version": SARIF_VERSION,
+                "runs": [{
+                    "tool": {
+                        "driver": {
+                            "name": "deputy",
+                            "version": env!("CARGO_PKG_VERSION"),
+                            "informationUri": INFORMATION_URI,
+                            "rules": [
+                                { "id": "outdated", "name": "outdated" },
+                                { "id": "unknown-package", "name": "unknown-package" },
+                                { "id": "cargo", "name": "cargo" },
+                            ],
+                        },
+                    },
+                    "originalUriBaseIds": {
+                        "%SRCROOT%": { "uri": "file:///work/my%20repo/" },
+                    },
+                    "results": [
+                        result(
+                            "outdated",
+                            0,
+                            "warning",
+                            "Cargo.toml",
+                            5,
+                            "A newer version of `serde` is available.\nThe latest version is `1.0.200`",
+                        ),
+                        result(
+                            "unknown-package",
+                            1,
+                            "error",
+                            "Cargo.toml",
+                            8,
+                            "No package exists with the name `serdee`, did you mean: `serde`?",
+                        ),
+                        result("cargo", 2, "note", "web%20app/package.json", 3, "100% done"),
+                    ],
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn renders_github() {
+        assert_eq!(
+            render(OutputFormat::Github),
+            "\
+::warning file=Cargo.toml,line=5,col=11,endLine=5,endColumn=18,title=Deputy (Cargo)::A newer version of `serde` is available.%0AThe latest version is `1.0.200`
+::error file=Cargo.toml,line=8,col=11,endLine=8,endColumn=18,title=Deputy (Cargo)::No package exists with the name `serdee`, did you mean: `serde`?
+::notice file=web app/package.json,line=3,col=11,endLine=3,endColumn=18,title=Deputy (Cargo)::100%25 done
+"
+        );
+    }
+
+    // sarif_artifact_location
+
+    #[test]
+    fn sarif_locations_are_uris() {
+        for (path, expected) in [
+            (
+                "Cargo.toml",
+                json!({ "uri": "Cargo.toml", "uriBaseId": "%SRCROOT%" }),
+            ),
+            (
+                "crates/my crate/Cargo.toml",
+                json!({ "uri": "crates/my%20crate/Cargo.toml", "uriBaseId": "%SRCROOT%" }),
+            ),
+            (
+                "web/#app/package.json",
+                json!({ "uri": "web/%23app/package.json", "uriBaseId": "%SRCROOT%" }),
+            ),
+            (
+                "/elsewhere/ünïcode/go.mod",
+                json!({ "uri": "file:///elsewhere/%C3%BCn%C3%AFcode/go.mod" }),
+            ),
+        ] {
+            assert_eq!(sarif_artifact_location(Path::new(path)), expected, "{path}");
+        }
+    }
+}
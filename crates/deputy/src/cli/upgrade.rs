@@ -0,0 +1,403 @@
+use std::{
+    env::current_dir,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use async_language_server::lsp_types::{Position, TextEdit};
+use clap::{Parser, ValueEnum};
+use similar::TextDiff;
+
+use deputy_clients::Clients;
+use deputy_tools::{CodeActionMetadata, Tools};
+use deputy_versioning::util::is_breaking_version_change;
+
+use crate::manifests::Manifest;
+
+use super::check::check_manifests;
+
+/**
+    The kinds of version bumps that `deputy upgrade` should apply.
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum UpgradeBumps {
+    /// Apply all available upgrades
+    #[default]
+    All,
+    /// Only apply upgrades that are semver compatible with the current version
+    Compatible,
+    /// Only apply upgrades that are breaking changes, such as major version bumps
+    Major,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct UpgradeCommand {
+    /// The directory or manifest to upgrade - defaults to the current directory
+    pub path: Option<PathBuf>,
+    /// Print a diff of the changes instead of writing them to disk
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Only upgrade dependencies from the given ecosystems, such as `cargo` or `npm`
+    #[arg(long = "ecosystem", value_name = "ECOSYSTEM")]
+    pub ecosystems: Vec<String>,
+    /// Only upgrade dependencies with the given names
+    #[arg(long = "package", short = 'p', value_name = "NAME")]
+    pub packages: Vec<String>,
+    /// The kinds of version bumps to apply
+    #[arg(long, value_enum, default_value_t)]
+    pub bumps: UpgradeBumps,
+    /// Do not send any requests, and only use data from the persistent cache
+    #[arg(long, env = "DEPUTY_OFFLINE")]
+    pub offline: bool,
+    #[arg(long, env = "GITHUB_TOKEN")]
+    pub github_token: Option<String>,
+}
+
+/**
+    A single dependency upgrade, along with the edit that applies it.
+*/
+struct Upgrade {
+    package_name: String,
+    version_current: String,
+    version_target: String,
+    edit: TextEdit,
+}
+
+impl UpgradeCommand {
+    pub async fn run(self) -> Result<()> {
+        let cwd = current_dir()?;
+        let root = self.path.clone().unwrap_or_else(|| cwd.clone());
+
+        let clients = Clients::default();
+        let tools = Tools::new(&clients);
+        clients.set_offline(self.offline);
+
+        if let Some(github_token) = &self.github_token {
+            clients.github.set_auth_token(github_token);
+        }
+
+        let mut num_upgrades = 0;
+        let mut num_manifests = 0;
+        for result in check_manifests(&tools, &root).await? {
+            if !self.includes_manifest(&result.path) {
+                continue;
+            }
+
+            let upgrades = result
+                .diagnostics
+                .iter()
+                .filter_map(CodeActionMetadata::from_diagnostic)
+                .filter_map(|metadata| self.select_upgrade(metadata))
+                .collect::<Vec<_>>();
+            if upgrades.is_empty() {
+                continue;
+            }
+
+            let display_path = result.path.strip_prefix(&cwd).unwrap_or(&result.path);
+            let old_text = tokio::fs::read_to_string(&result.path)
+                .await
+                .with_context(|| format!("failed to read {}", display_path.display()))?;
+            let new_text = apply_edits(&old_text, upgrades.iter().map(|u| &u.edit));
+            if new_text == old_text {
+                continue;
+            }
+
+            if self.dry_run {
+                print!("{}", format_diff(display_path, &old_text, &new_text));
+            } else {
+                tokio::fs::write(&result.path, &new_text)
+                    .await
+                    .with_context(|| format!("failed to write {}", display_path.display()))?;
+                for upgrade in &upgrades {
+                    println!(
+                        "{}: {} {} -> {}",
+                        display_path.display(),
+                        upgrade.package_name,
+                        upgrade.version_current,
+                        upgrade.version_target,
+                    );
+                }
+            }
+
+            num_upgrades += upgrades.len();
+            num_manifests += 1;
+        }
+
+        eprintln!(
+            "{} {num_upgrades} dependencies in {num_manifests} manifests",
+            if self.dry_run {
+                "Would upgrade"
+            } else {
+                "Upgraded"
+            }
+        );
+
+        Ok(())
+    }
+
+    /**
+        Checks if the manifest at the given path is from one of the
+        ecosystems to upgrade, using the names of known manifest kinds.
+    */
+    fn includes_manifest(&self, path: &Path) -> bool {
+        self.ecosystems.is_empty()
+            || Manifest::from_path(path).is_some_and(|manifest| {
+                self.ecosystems
+                    .iter()
+                    .any(|e| e.eq_ignore_ascii_case(manifest.name))
+            })
+    }
+
+    /**
+        Picks the version to upgrade to, if any, based on the given
+        filters - preferring the latest compatible version when
+        only compatible upgrades should be applied.
+    */
+    fn select_upgrade(&self, metadata: CodeActionMetadata) -> Option<Upgrade> {
+        let edit_latest = metadata.text_edit();
+        let edit_compatible = metadata.text_edit_compatible();
+        let CodeActionMetadata::LatestVersion {
+            package_name,
+            version_current,
+            version_latest,
+            version_compatible,
+            ..
+        } = metadata
+        else {
+            return None;
+        };
+
+        if !self.packages.is_empty() && !self.packages.contains(&package_name) {
+            return None;
+        }
+
+        let is_breaking = is_breaking_version_change(&version_current, &version_latest);
+        let (version_target, edit) = match self.bumps {
+            UpgradeBumps::All => (version_latest, edit_latest?),
+            UpgradeBumps::Major if is_breaking => (version_latest, edit_latest?),
+            UpgradeBumps::Compatible if !is_breaking => (version_latest, edit_latest?),
+            UpgradeBumps::Compatible => (version_compatible?, edit_compatible?),
+            UpgradeBumps::Major => return None,
+        };
+
+        Some(Upgrade {
+            package_name,
+            version_current,
+            version_target,
+            edit,
+        })
+    }
+}
+
+/**
+    Applies text edits to the given text, the same way an editor would.
+
+    Edits are applied from the end of the text towards the start,
+    so that earlier edits do not shift the positions of later ones.
+    Duplicate edits are applied once, and any edit that overlaps
+    with an edit after it is skipped, since both can not be applied.
+*/
+fn apply_edits<'a>(text: &str, edits: impl IntoIterator<Item = &'a TextEdit>) -> String {
+    let mut edits = edits
+        .into_iter()
+        .filter_map(|edit| {
+            let start = position_to_offset(text, edit.range.start)?;
+            let end = position_to_offset(text, edit.range.end)?;
+            (start <= end).then_some((start, end, edit.new_text.as_str()))
+        })
+        .collect::<Vec<_>>();
+    edits.sort_unstable();
+    edits.dedup();
+
+    let mut result = text.to_string();
+    let mut applied_start = text.len();
+    for (start, end, new_text) in edits.into_iter().rev() {
+        if end > applied_start {
+            continue;
+        }
+        result.replace_range(start..end, new_text);
+        applied_start = start;
+    }
+    result
+}
+
+/**
+    Converts an LSP position (UTF-16 based) into a byte offset in the given text.
+*/
+fn position_to_offset(text: &str, position: Position) -> Option<usize> {
+    let mut offset = 0;
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        if index == position.line as usize {
+            let content = line.trim_end_matches(['\r', '\n']);
+            let mut units = 0;
+            for (byte_index, c) in content.char_indices() {
+                if units >= position.character as usize {
+                    return Some(offset + byte_index);
+                }
+                units += c.len_utf16();
+            }
+            return Some(offset + content.len());
+        }
+        offset += line.len();
+    }
+    // The position just after a trailing newline is still within the text
+    (position.line as usize == text.split_inclusive('\n').count() && text.ends_with('\n'))
+        .then_some(text.len())
+}
+
+/**
+    Formats a unified diff between two versions of a manifest,
+    without any context lines around the changed lines.
+*/
+fn format_diff(path: &Path, old: &str, new: &str) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(0)
+        .header(&format!("a/{path}"), &format!("b/{path}"))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use async_language_server::lsp_types::Range;
+
+    use super::*;
+
+    fn edit(start: (u32, u32), end: (u32, u32), new_text: &str) -> TextEdit {
+        TextEdit {
+            range: Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
+            new_text: new_text.to_string(),
+        }
+    }
+
+    // position_to_offset
+
+    #[test]
+    fn converts_utf16_positions() {
+        let text = "a = \"é\"\n😀 = \"1.0\"\n";
+        for ((line, character), expected) in [
+            ((0, 0), Some(0)),
+            ((0, 5), Some(5)),
+            ((0, 6), Some(7)),
+            ((1, 0), Some(9)),
+            ((1, 2), Some(13)),
+            ((1, 6), Some(17)),
+            ((1, 100), Some(21)),
+            ((2, 0), Some(22)),
+            ((3, 0), None),
+        ] {
+            assert_eq!(
+                position_to_offset(text, Position::new(line, character)),
+                expected,
+                "{line}:{character}"
+            );
+        }
+    }
+
+    #[test]
+    fn converts_crlf_positions() {
+        let text = "a = \"1\"\r\nb = \"2\"\r\n";
+        for ((line, character), expected) in [
+            ((0, 7), Some(7)),
+            ((0, 100), Some(7)),
+            ((1, 0), Some(9)),
+            ((1, 5), Some(14)),
+        ] {
+            assert_eq!(
+                position_to_offset(text, Position::new(line, character)),
+                expected,
+                "{line}:{character}"
+            );
+        }
+    }
+
+    // apply_edits
+
+    #[test]
+    fn applies_edits_after_multibyte_characters() {
+        let text = "# 😀\nserde = \"1.0\"\n";
+        let edits = [edit((0, 2), (0, 4), "🚀"), edit((1, 9), (1, 12), "2.0")];
+        assert_eq!(apply_edits(text, &edits), "# 🚀\nserde = \"2.0\"\n");
+    }
+
+    #[test]
+    fn applies_edits_in_crlf_files() {
+        let text = "[dependencies]\r\nserde = \"1.0\"\r\ntokio = \"1.0\"\r\n";
+        let edits = [
+            edit((2, 9), (2, 12), "1.45"),
+            edit((1, 9), (1, 12), "1.0.200"),
+        ];
+        assert_eq!(
+            apply_edits(text, &edits),
+            "[dependencies]\r\nserde = \"1.0.200\"\r\ntokio = \"1.45\"\r\n"
+        );
+    }
+
+    #[test]
+    fn applies_duplicate_edits_once() {
+        let text = "serde = \"1.0\"\n";
+        let edits = [edit((0, 9), (0, 12), "2.0"), edit((0, 9), (0, 12), "2.0")];
+        assert_eq!(apply_edits(text, &edits), "serde = \"2.0\"\n");
+    }
+
+    #[test]
+    fn skips_overlapping_edits() {
+        let text = "serde = \"1.0\"\n";
+        let edits = [
+            edit((0, 9), (0, 12), "2.0"),
+            edit((0, 8), (0, 13), "\"3.0\""),
+        ];
+        assert_eq!(apply_edits(text, &edits), "serde = \"2.0\"\n");
+    }
+
+    #[test]
+    fn applies_edits_on_last_line_without_newline() {
+        let text = "[dependencies]\nserde = \"1.0\"";
+        let edits = [edit((1, 9), (1, 12), "2.0")];
+        assert_eq!(apply_edits(text, &edits), "[dependencies]\nserde = \"2.0\"");
+        let edits = [edit((1, 13), (1, 13), "\n")];
+        assert_eq!(
+            apply_edits(text, &edits),
+            "[dependencies]\nserde = \"1.0\"\n"
+        );
+    }
+
+    // format_diff
+
+    #[test]
+    fn formats_diff_hunks() {
+        let old = "[dependencies]\nserde = \"1.0\"\nrand = \"0.8\"\ntokio = \"1.0\"\n";
+        let new = "[dependencies]\nserde = \"2.0\"\nrand = \"0.8\"\ntokio = \"1.45\"\n";
+        assert_eq!(
+            format_diff(Path::new("Cargo.toml"), old, new),
+            "\
+--- a/Cargo.toml
++++ b/Cargo.toml
+@@ -2 +2 @@
+-serde = \"1.0\"
++serde = \"2.0\"
+@@ -4 +4 @@
+-tokio = \"1.0\"
++tokio = \"1.45\"
+"
+        );
+    }
+
+    #[test]
+    fn formats_diff_hunks_that_change_line_counts() {
+        let old = "[dependencies]\nserde = \"1.0\"\ntokio = \"1.0\"";
+        let new = "[dependencies]\nserde = { version = \"2.0\",\n  features = [\"derive\"] }\ntokio = \"1.0\"";
+        assert_eq!(
+            format_diff(Path::new("Cargo.toml"), old, new),
+            "\
+--- a/Cargo.toml
++++ b/Cargo.toml
+@@ -2 +2,2 @@
+-serde = \"1.0\"
++serde = { version = \"2.0\",
++  features = [\"derive\"] }
+"
+        );
+    }
+}
@@ -1,4 +1,5 @@
 mod cli;
+mod manifests;
 mod server;
 mod tracing;
 
@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+
+use async_language_server::{
+    lsp_types::Url,
+    server::{Document, DocumentMatcher},
+};
+use ignore::WalkBuilder;
+use tree_sitter_language::LanguageFn;
+
+use deputy_parser::{GOMOD_LANGUAGE, JSON_LANGUAGE, TOML_LANGUAGE};
+
+/**
+    Directories that never contain manifests we care about,
+    even if they are not ignored by any `.gitignore` file.
+*/
+const SKIPPED_DIRECTORIES: &[&str] = &["target", "node_modules"];
+
+/**
+    A kind of manifest that the language server knows how to handle.
+*/
+#[derive(Clone, Copy)]
+pub struct Manifest {
+    pub name: &'static str,
+    pub file_name: &'static str,
+    pub language: LanguageFn,
+}
+
+impl Manifest {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?;
+        MANIFESTS.iter().find(|m| m.file_name == file_name).copied()
+    }
+
+    pub fn matcher(&self) -> DocumentMatcher {
+        DocumentMatcher::new(self.name)
+            .with_url_globs([format!("**/{}", self.file_name), self.file_name.to_string()])
+            .with_lang_grammar(self.language.into())
+    }
+}
+
+pub const MANIFESTS: &[Manifest] = &[
+    Manifest {
+        name: "Cargo",
+        file_name: "Cargo.toml",
+        language: TOML_LANGUAGE,
+    },
+    Manifest {
+        name: "GoMod",
+        file_name: "go.mod",
+        language: GOMOD_LANGUAGE,
+    },
+    Manifest {
+        name: "NPM",
+        file_name: "package.json",
+        language: JSON_LANGUAGE,
+    },
+    Manifest {
+        name: "PyProject",
+        file_name: "pyproject.toml",
+        language: TOML_LANGUAGE,
+    },
+    Manifest {
+        name: "Rokit",
+        file_name: "rokit.toml",
+        language: TOML_LANGUAGE,
+    },
+    Manifest {
+        name: "Wally",
+        file_name: "wally.toml",
+        language: TOML_LANGUAGE,
+    },
+];
+
+/**
+    Finds all known manifests in the given directory, recursively.
+
+    Respects `.gitignore` files and skips hidden directories,
+    as well as directories such as `target` and `node_modules`.
+*/
+pub fn find_manifests(root: &Path) -> Vec<PathBuf> {
+    if root.is_file() {
+        return Manifest::from_path(root)
+            .map(|_| vec![root.to_path_buf()])
+            .unwrap_or_default();
+    }
+
+    let mut paths = WalkBuilder::new(root)
+        .hidden(true)
+        .require_git(false)
+        .filter_entry(|entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            !is_dir
+                || !entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| SKIPPED_DIRECTORIES.contains(&name))
+        })
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+        .map(ignore::DirEntry::into_path)
+        .filter(|path| Manifest::from_path(path).is_some())
+        .collect::<Vec<_>>();
+
+    paths.sort();
+    paths
+}
+
+/**
+    Reads and parses a manifest from disk, outside of any editor.
+
+    Returns `None` if the path is not a known manifest or could not be read.
+*/
+pub async fn load_manifest(path: &Path) -> Option<Document> {
+    let manifest = Manifest::from_path(path)?;
+
+    let path = tokio::fs::canonicalize(path).await.ok()?;
+    let text = tokio::fs::read_to_string(&path).await.ok()?;
+    let url = Url::from_file_path(&path).ok()?;
+
+    Some(Document::from_matcher(&manifest.matcher(), url, text))
+}
@@ -1,36 +1,298 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    hash::{DefaultHasher, Hash, Hasher},
+    path::PathBuf,
+    sync::{Arc, PoisonError, RwLock},
+    time::Duration,
+};
+
 use async_language_server::{
     lsp_types::{
-        ClientCapabilities, CodeActionKind, CodeActionOptions, CodeActionParams,
-        CodeActionProviderCapability, CodeActionResponse, CompletionOptions, CompletionParams,
-        CompletionResponse, DiagnosticOptions, DiagnosticServerCapabilities,
+        ApplyWorkspaceEditParams, ClientCapabilities, CodeActionKind, CodeActionOptions,
+        CodeActionParams, CodeActionProviderCapability, CodeActionResponse, CodeLens,
+        CodeLensOptions, CodeLensParams, CompletionOptions, CompletionParams, CompletionResponse,
+        Diagnostic, DiagnosticOptions, DiagnosticServerCapabilities, DidChangeConfigurationParams,
+        DidChangeWatchedFilesParams, DidChangeWorkspaceFoldersParams, DidCloseTextDocumentParams,
         DocumentDiagnosticParams, DocumentDiagnosticReport, DocumentDiagnosticReportResult,
-        FullDocumentDiagnosticReport, Hover, HoverParams, HoverProviderCapability,
-        RelatedFullDocumentDiagnosticReport, ServerCapabilities, ServerInfo,
+        DocumentLink, DocumentLinkOptions, DocumentLinkParams, ExecuteCommandOptions,
+        ExecuteCommandParams, FileChangeType, FullDocumentDiagnosticReport, GotoDefinitionParams,
+        GotoDefinitionResponse, Hover, HoverParams, HoverProviderCapability, InitializeParams,
+        InlayHint, InlayHintParams, MessageType, OneOf, PartialResultParams, ProgressToken,
+        RelatedFullDocumentDiagnosticReport, RelatedUnchangedDocumentDiagnosticReport,
+        ServerCapabilities, ServerInfo, ShowMessageParams, TextDocumentIdentifier,
+        UnchangedDocumentDiagnosticReport, Url, WorkDoneProgressOptions, WorkDoneProgressParams,
+        WorkspaceDiagnosticParams, WorkspaceDiagnosticReport,
+        WorkspaceDiagnosticReportPartialResult, WorkspaceDiagnosticReportResult,
+        WorkspaceDocumentDiagnosticReport, WorkspaceFolder, WorkspaceFoldersServerCapabilities,
+        WorkspaceFullDocumentDiagnosticReport, WorkspaceServerCapabilities,
+        WorkspaceUnchangedDocumentDiagnosticReport,
+        notification::{Notification, ShowMessage},
+        request::{ApplyWorkspaceEdit, CodeLensRefresh, WorkspaceDiagnosticRefresh},
     },
-    server::{DocumentMatcher, Server, ServerResult, ServerState},
+    server::{Document, DocumentMatcher, Server, ServerResult, ServerState},
 };
+use futures::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tokio::sync::broadcast::error::RecvError;
 
 use deputy_clients::Clients;
-use deputy_parser::{GOMOD_LANGUAGE, JSON_LANGUAGE, TOML_LANGUAGE};
-use deputy_tools::Tools;
+use deputy_tools::{
+    COMMAND_UPDATE_DEPENDENCIES, ResolveContextPartial, SOURCE_FIX_ALL_DEPUTY,
+    SOURCE_UPDATE_DEPENDENCIES, Settings, Tools,
+};
+
+use crate::manifests::{MANIFESTS, Manifest, find_manifests, load_manifest};
+
+// How many manifests are checked at once for workspace diagnostics - each one
+// sends many requests of its own, so checking all of them at once in a large
+// workspace would only make every manifest wait longer for its requests
+const MAX_CONCURRENT_MANIFESTS: usize = 8;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct DeputyLanguageServer {
     clients: Clients,
     tools: Tools,
+    workspace_roots: Arc<RwLock<Vec<PathBuf>>>,
 }
 
 impl DeputyLanguageServer {
     pub fn new() -> Self {
-        let clients = Clients::new();
+        let clients = Clients::default();
         let tools = Tools::new(&clients);
-        Self { clients, tools }
+        Self {
+            clients,
+            tools,
+            workspace_roots: Arc::new(RwLock::new(Vec::new())),
+        }
     }
 
     pub fn set_github_token(&self, github_token: impl AsRef<str>) {
         self.clients.github.set_auth_token(github_token);
     }
+
+    /**
+        Parses and applies settings sent by the editor.
+
+        Editors that use the pull model for configuration send `null`
+        for configuration changes, which we ignore, to not reset
+        any settings that were given during initialization.
+
+        Returns `true` if new settings were applied.
+    */
+    fn apply_settings(&self, value: JsonValue) -> bool {
+        if value.is_null() {
+            return false;
+        }
+        match Settings::from_json(value) {
+            Ok(settings) => {
+                tracing::debug!("Applying settings: {settings:?}");
+                self.tools.set_settings(settings);
+                true
+            }
+            Err(e) => {
+                tracing::error!("Failed to parse settings - {e}");
+                false
+            }
+        }
+    }
+
+    /**
+        Asks the editor to pull diagnostics and code lenses again, since
+        settings such as registries and ignored packages change their results.
+        Editors that do not support refreshing reply with an error, which is ignored.
+    */
+    fn refresh_diagnostics(state: ServerState) {
+        tokio::spawn(async move {
+            if let Err(e) = state.request::<WorkspaceDiagnosticRefresh>(()).await {
+                tracing::debug!("Failed to refresh diagnostics - {e}");
+            }
+            if let Err(e) = state.request::<CodeLensRefresh>(()).await {
+                tracing::debug!("Failed to refresh code lenses - {e}");
+            }
+        });
+    }
+
+    /**
+        Shows a message in the editor whenever a registry goes down - diagnostics
+        for its packages are missing until it recovers, which would otherwise be silent.
+        Each outage is only reported once, and not again until the registry recovers.
+    */
+    fn report_outages(&self, state: ServerState) {
+        let mut outages = self.clients.subscribe_outages();
+        tokio::spawn(async move {
+            loop {
+                let outage = match outages.recv().await {
+                    Ok(outage) => outage,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                let retry = match outage.paused_for {
+                    Some(paused_for) => {
+                        format!("will try again in {}", describe_duration(paused_for))
+                    }
+                    None => String::from("will keep trying"),
+                };
+                let message = format!(
+                    "Deputy could not reach `{}`, and {retry} - {}",
+                    outage.host, outage.reason
+                );
+                if let Err(e) = state.notify::<ShowMessage>(ShowMessageParams {
+                    typ: MessageType::WARNING,
+                    message,
+                }) {
+                    tracing::error!("Failed to report outage - {e}");
+                }
+            }
+        });
+    }
+
+    fn set_workspace_folders(&self, folders: &[WorkspaceFolder]) {
+        let roots = folder_paths(folders);
+        tracing::debug!("Using workspace roots: {roots:?}");
+        *self
+            .workspace_roots
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = roots;
+    }
+
+    /**
+        Finds all known manifests in the workspace folders.
+
+        Walking directories may take a while in large projects,
+        so this is done on a separate thread, without blocking.
+    */
+    async fn find_workspace_manifests(&self) -> Vec<PathBuf> {
+        let roots = self
+            .workspace_roots
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        tokio::task::spawn_blocking(move || {
+            // Workspace folders may be nested, so paths are deduplicated
+            roots
+                .iter()
+                .flat_map(|root| find_manifests(root))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        })
+        .await
+        .unwrap_or_default()
+    }
+
+    /**
+        Creates a diagnostic report for a single manifest in the workspace.
+
+        Manifests that are open in the editor are checked using their current
+        contents, while all other manifests are read from disk. Manifests that
+        have not changed since the editor last received their diagnostics are
+        not checked again, and are reported as unchanged.
+    */
+    async fn workspace_manifest_report(
+        &self,
+        state: &ServerState,
+        path: PathBuf,
+        previous_result_ids: &HashMap<Url, String>,
+    ) -> Option<WorkspaceDocumentDiagnosticReport> {
+        let doc = match Url::from_file_path(&path)
+            .ok()
+            .and_then(|url| state.document(&url))
+        {
+            Some(doc) => doc,
+            None => load_manifest(&path).await?,
+        };
+
+        let uri = doc.url().clone();
+        let previous_result_id = previous_result_ids.get(&uri);
+        let latest_result_id = previous_result_id
+            .and_then(|_| self.tools.latest_diagnostics(&doc))
+            .map(|items| diagnostics_result_id(&items));
+        if let Some(result_id) = latest_result_id.filter(|id| Some(id) == previous_result_id) {
+            return Some(unchanged_workspace_report(uri, result_id));
+        }
+
+        let items = workspace_manifest_diagnostics(&self.tools, &doc).await;
+        let result_id = diagnostics_result_id(&items);
+        if previous_result_id == Some(&result_id) {
+            return Some(unchanged_workspace_report(uri, result_id));
+        }
+
+        Some(WorkspaceDocumentDiagnosticReport::Full(
+            WorkspaceFullDocumentDiagnosticReport {
+                uri,
+                version: None,
+                full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                    result_id: Some(result_id),
+                    items,
+                },
+            },
+        ))
+    }
+}
+
+fn unchanged_workspace_report(uri: Url, result_id: String) -> WorkspaceDocumentDiagnosticReport {
+    WorkspaceDocumentDiagnosticReport::Unchanged(WorkspaceUnchangedDocumentDiagnosticReport {
+        uri,
+        version: None,
+        unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport { result_id },
+    })
+}
+
+/**
+    A `$/progress` notification carrying a partial result,
+    which is not covered by the types in `lsp_types`.
+*/
+enum PartialResultProgress {}
+
+impl Notification for PartialResultProgress {
+    type Params = PartialResultProgressParams;
+    const METHOD: &'static str = "$/progress";
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PartialResultProgressParams {
+    token: ProgressToken,
+    value: WorkspaceDiagnosticReportPartialResult,
+}
+
+fn folder_paths(folders: &[WorkspaceFolder]) -> Vec<PathBuf> {
+    folders
+        .iter()
+        .filter_map(|folder| folder.uri.to_file_path().ok())
+        .collect()
+}
+
+async fn workspace_manifest_diagnostics(tools: &Tools, doc: &Document) -> Vec<Diagnostic> {
+    let params = DocumentDiagnosticParams {
+        text_document: TextDocumentIdentifier {
+            uri: doc.url().clone(),
+        },
+        identifier: None,
+        previous_result_id: None,
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    match tools.diagnostics(doc, params).await {
+        Ok(items) => items,
+        Err(e) => {
+            tracing::warn!("Failed to check manifest at {} - {e}", doc.url());
+            Vec::new()
+        }
+    }
+}
+
+/**
+    Creates an id for a diagnostic report, which is the same whenever the diagnostics are,
+    so that the editor can be told that they are unchanged instead of receiving them again.
+*/
+fn diagnostics_result_id(items: &[Diagnostic]) -> String {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(items)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
 }
 
 impl Default for DeputyLanguageServer {
@@ -55,42 +317,117 @@ impl Server for DeputyLanguageServer {
                 trigger_characters: Some(completion_trigger_characters()),
                 ..Default::default()
             }),
+            definition_provider: Some(OneOf::Left(true)),
+            document_link_provider: Some(DocumentLinkOptions {
+                resolve_provider: Some(true),
+                work_done_progress_options: WorkDoneProgressOptions::default(),
+            }),
             code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
-                code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+                code_action_kinds: Some(vec![
+                    CodeActionKind::QUICKFIX,
+                    SOURCE_UPDATE_DEPENDENCIES,
+                    SOURCE_FIX_ALL_DEPUTY,
+                ]),
                 resolve_provider: Some(false),
                 ..Default::default()
             })),
+            code_lens_provider: Some(CodeLensOptions {
+                resolve_provider: Some(false),
+            }),
+            execute_command_provider: Some(ExecuteCommandOptions {
+                commands: vec![COMMAND_UPDATE_DEPENDENCIES.to_string()],
+                work_done_progress_options: WorkDoneProgressOptions::default(),
+            }),
             diagnostic_provider: Some(DiagnosticServerCapabilities::Options(DiagnosticOptions {
                 inter_file_dependencies: false,
-                workspace_diagnostics: false,
+                workspace_diagnostics: true,
                 ..Default::default()
             })),
+            inlay_hint_provider: Some(OneOf::Left(true)),
+            workspace: Some(WorkspaceServerCapabilities {
+                workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+                    supported: Some(true),
+                    change_notifications: Some(OneOf::Left(true)),
+                }),
+                file_operations: None,
+            }),
             ..Default::default()
         })
     }
 
     fn server_document_matchers() -> Vec<DocumentMatcher> {
-        let matchers = [
-            ("Cargo", ["**/Cargo.toml", "Cargo.toml"], TOML_LANGUAGE),
-            ("GoMod", ["**/go.mod", "go.mod"], GOMOD_LANGUAGE),
-            ("NPM", ["**/package.json", "package.json"], JSON_LANGUAGE),
-            (
-                "PyProject",
-                ["**/pyproject.toml", "pyproject.toml"],
-                TOML_LANGUAGE,
-            ),
-            ("Rokit", ["**/rokit.toml", "rokit.toml"], TOML_LANGUAGE),
-            ("Wally", ["**/wally.toml", "wally.toml"], TOML_LANGUAGE),
-        ];
-
-        matchers
-            .into_iter()
-            .map(|(name, globs, lang)| {
-                DocumentMatcher::new(name)
-                    .with_url_globs(globs)
-                    .with_lang_grammar(lang.into())
-            })
-            .collect()
+        MANIFESTS.iter().map(Manifest::matcher).collect()
+    }
+
+    async fn initialize(&self, state: ServerState, params: InitializeParams) -> ServerResult<()> {
+        self.report_outages(state);
+        if let Some(options) = params.initialization_options {
+            self.apply_settings(options);
+        }
+        #[allow(deprecated)]
+        let folders = params.workspace_folders.unwrap_or_else(|| {
+            // Older editors only send a single root, if any
+            params
+                .root_uri
+                .map(|uri| WorkspaceFolder {
+                    uri,
+                    name: String::new(),
+                })
+                .into_iter()
+                .collect()
+        });
+        self.set_workspace_folders(&folders);
+        Ok(())
+    }
+
+    async fn did_change_workspace_folders(
+        &self,
+        _: ServerState,
+        params: DidChangeWorkspaceFoldersParams,
+    ) -> ServerResult<()> {
+        let removed = folder_paths(&params.event.removed);
+        let added = folder_paths(&params.event.added);
+
+        let mut roots = self
+            .workspace_roots
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        roots.retain(|root| !removed.contains(root));
+        roots.extend(added);
+        Ok(())
+    }
+
+    async fn did_change_configuration(
+        &self,
+        state: ServerState,
+        params: DidChangeConfigurationParams,
+    ) -> ServerResult<()> {
+        if self.apply_settings(params.settings) {
+            Self::refresh_diagnostics(state);
+        }
+        Ok(())
+    }
+
+    async fn did_close(
+        &self,
+        _: ServerState,
+        params: DidCloseTextDocumentParams,
+    ) -> ServerResult<()> {
+        self.tools.forget_diagnostics(&params.text_document.uri);
+        Ok(())
+    }
+
+    async fn did_change_watched_files(
+        &self,
+        _: ServerState,
+        params: DidChangeWatchedFilesParams,
+    ) -> ServerResult<()> {
+        for change in params.changes {
+            if change.typ == FileChangeType::DELETED {
+                self.tools.forget_diagnostics(&change.uri);
+            }
+        }
+        Ok(())
     }
 
     async fn hover(&self, state: ServerState, params: HoverParams) -> ServerResult<Option<Hover>> {
@@ -145,27 +482,139 @@ impl Server for DeputyLanguageServer {
         self.tools.completion(&doc, pos, node).await
     }
 
+    async fn definition(
+        &self,
+        state: ServerState,
+        params: GotoDefinitionParams,
+    ) -> ServerResult<Option<GotoDefinitionResponse>> {
+        let url = params.text_document_position_params.text_document.uri;
+        let pos = params.text_document_position_params.position;
+
+        let Some(doc) = state.document(&url) else {
+            return Ok(None);
+        };
+        let Some(node) = doc.node_at_position_named(pos) else {
+            return Ok(None);
+        };
+
+        tracing::debug!(
+            "Getting definition for node at {}:{}",
+            pos.line,
+            pos.character
+        );
+
+        self.tools.definition(&doc, pos, node).await
+    }
+
     async fn document_diagnostics(
         &self,
         state: ServerState,
         params: DocumentDiagnosticParams,
     ) -> ServerResult<DocumentDiagnosticReportResult> {
+        let previous_result_id = params.previous_result_id.clone();
         let items = match state.document(&params.text_document.uri) {
             Some(doc) => self.tools.diagnostics(&doc, params).await?,
             None => Vec::new(),
         };
 
+        let result_id = diagnostics_result_id(&items);
+        if previous_result_id.as_ref() == Some(&result_id) {
+            return Ok(DocumentDiagnosticReportResult::Report(
+                DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+                    related_documents: None,
+                    unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                        result_id,
+                    },
+                }),
+            ));
+        }
+
         Ok(DocumentDiagnosticReportResult::Report(
             DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
                 related_documents: None,
                 full_document_diagnostic_report: FullDocumentDiagnosticReport {
-                    result_id: None,
+                    result_id: Some(result_id),
                     items,
                 },
             }),
         ))
     }
 
+    async fn workspace_diagnostics(
+        &self,
+        state: ServerState,
+        params: WorkspaceDiagnosticParams,
+    ) -> ServerResult<WorkspaceDiagnosticReportResult> {
+        let paths = self.find_workspace_manifests().await;
+        tracing::debug!("Checking {} manifests in workspace", paths.len());
+
+        // Reports for manifests that are unchanged since the editor last asked are not sent again
+        let previous_result_ids = params
+            .previous_result_ids
+            .into_iter()
+            .map(|previous| (previous.uri, previous.value))
+            .collect::<HashMap<_, _>>();
+
+        let mut reports = stream::iter(paths)
+            .map(|path| self.workspace_manifest_report(&state, path, &previous_result_ids))
+            .buffer_unordered(MAX_CONCURRENT_MANIFESTS);
+
+        // Stream each report as soon as it is ready, if the editor supports it,
+        // since fetching data for every dependency in a large workspace is slow
+        let token = params.partial_result_params.partial_result_token;
+        let mut items = Vec::new();
+        while let Some(report) = reports.next().await {
+            let Some(report) = report else {
+                continue;
+            };
+            if let Some(token) = &token {
+                let partial = WorkspaceDiagnosticReportPartialResult {
+                    items: vec![report],
+                };
+                state.notify::<PartialResultProgress>(PartialResultProgressParams {
+                    token: token.clone(),
+                    value: partial,
+                })?;
+            } else {
+                items.push(report);
+            }
+        }
+
+        Ok(WorkspaceDiagnosticReportResult::Report(
+            WorkspaceDiagnosticReport { items },
+        ))
+    }
+
+    async fn document_link(
+        &self,
+        state: ServerState,
+        params: DocumentLinkParams,
+    ) -> ServerResult<Option<Vec<DocumentLink>>> {
+        if let Some(doc) = state.document(&params.text_document.uri) {
+            self.tools.document_links(&doc).await.map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn document_link_resolve(
+        &self,
+        state: ServerState,
+        link: DocumentLink,
+    ) -> ServerResult<DocumentLink> {
+        let Some(context) = link
+            .data
+            .as_ref()
+            .and_then(|data| ResolveContextPartial::try_from(data).ok())
+        else {
+            return Ok(link);
+        };
+        match state.document(&context.uri) {
+            Some(doc) => self.tools.document_link_resolve(&doc, link).await,
+            None => Ok(link),
+        }
+    }
+
     async fn code_action(
         &self,
         state: ServerState,
@@ -177,6 +626,56 @@ impl Server for DeputyLanguageServer {
             Ok(None)
         }
     }
+
+    async fn code_lens(
+        &self,
+        state: ServerState,
+        params: CodeLensParams,
+    ) -> ServerResult<Option<Vec<CodeLens>>> {
+        if let Some(doc) = state.document(&params.text_document.uri) {
+            self.tools.code_lenses(&doc, params).await.map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn execute_command(
+        &self,
+        state: ServerState,
+        params: ExecuteCommandParams,
+    ) -> ServerResult<Option<JsonValue>> {
+        let Some(edit) = self.tools.command_edit(params) else {
+            return Ok(None);
+        };
+        let response = state
+            .request::<ApplyWorkspaceEdit>(ApplyWorkspaceEditParams {
+                label: Some(String::from("Update dependencies")),
+                edit,
+            })
+            .await?;
+        if !response.applied {
+            tracing::warn!(
+                "Editor did not apply dependency updates - {}",
+                response
+                    .failure_reason
+                    .as_deref()
+                    .unwrap_or("no reason given")
+            );
+        }
+        Ok(None)
+    }
+
+    async fn inlay_hint(
+        &self,
+        state: ServerState,
+        params: InlayHintParams,
+    ) -> ServerResult<Option<Vec<InlayHint>>> {
+        if let Some(doc) = state.document(&params.text_document.uri) {
+            self.tools.inlay_hints(&doc, params).await.map(Some)
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 pub fn completion_trigger_characters() -> Vec<String> {
@@ -193,3 +692,58 @@ pub fn completion_trigger_characters() -> Vec<String> {
     chars.sort();
     chars
 }
+
+/**
+    Describes a duration in whole minutes, or in seconds if shorter than a minute.
+*/
+fn describe_duration(duration: Duration) -> String {
+    match duration.as_secs() {
+        60..120 => String::from("a minute"),
+        secs @ 120.. => format!("{} minutes", secs / 60),
+        1 => String::from("a second"),
+        secs => format!("{secs} seconds"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(message: &str) -> Diagnostic {
+        Diagnostic {
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    // diagnostics_result_id
+
+    #[test]
+    fn result_id_is_stable_for_same_diagnostics() {
+        let items = vec![diagnostic("A newer version of `serde` is available")];
+        assert_eq!(
+            diagnostics_result_id(&items),
+            diagnostics_result_id(&items.clone())
+        );
+    }
+
+    #[test]
+    fn result_id_changes_with_diagnostics() {
+        let before = diagnostics_result_id(&[diagnostic("No package exists")]);
+        assert_ne!(before, diagnostics_result_id(&[]));
+        assert_ne!(
+            before,
+            diagnostics_result_id(&[diagnostic("No version exists")])
+        );
+    }
+
+    // describe_duration
+
+    #[test]
+    fn describes_durations() {
+        assert_eq!(describe_duration(Duration::from_secs(1)), "a second");
+        assert_eq!(describe_duration(Duration::from_secs(30)), "30 seconds");
+        assert_eq!(describe_duration(Duration::from_mins(1)), "a minute");
+        assert_eq!(describe_duration(Duration::from_mins(5)), "5 minutes");
+    }
+}
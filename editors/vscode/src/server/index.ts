@@ -88,6 +88,11 @@ export const start = async () => {
 			{ scheme: "file", language: "json" },
 			{ scheme: "file", language: "go.mod" },
 		],
+		synchronize: {
+			fileEvents: vscode.workspace.createFileSystemWatcher(
+				"**/{Cargo.toml,go.mod,package.json,pyproject.toml,rokit.toml,wally.toml}",
+			),
+		},
 		outputChannel,
 	};
 
@@ -112,6 +117,13 @@ export const start = async () => {
 	);
 };
 
+/**
+	Returns the language client, if the language server is running.
+*/
+export const getClient = (): LanguageClient | undefined => {
+	return client;
+};
+
 /**
 	Stops the language server.
 